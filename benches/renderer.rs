@@ -0,0 +1,32 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use teenygame::bench::Bench;
+use teenygame::math::UVec2;
+
+fn renderer(c: &mut Criterion) {
+    let mut bench = match futures::executor::block_on(Bench::new(UVec2::new(1280, 720))) {
+        Ok(bench) => bench,
+        Err(e) => {
+            eprintln!("skipping renderer benchmarks: {e}");
+            return;
+        }
+    };
+
+    let mut group = c.benchmark_group("renderer");
+    for count in [100, 1_000, 10_000] {
+        group.bench_with_input(BenchmarkId::new("sprites", count), &count, |b, &count| {
+            b.iter_custom(|iters| bench.sprites(count, iters as u32).total)
+        });
+    }
+
+    let attrs =
+        bench.add_font(include_bytes!("../examples/snake/src/PixelOperator.ttf"))[0].clone();
+    for count in [10, 100] {
+        group.bench_with_input(BenchmarkId::new("text", count), &count, |b, &count| {
+            b.iter_custom(|iters| bench.text(&attrs, count, iters as u32).total)
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, renderer);
+criterion_main!(benches);
@@ -2,10 +2,13 @@ use std::{f32::consts::TAU, num::NonZero};
 
 use soa_rs::{soa, Soa, Soars};
 use teenygame::{
-    graphics::{font, Canvas, Color, Drawable, Lazy, Texture, TextureSlice},
+    graphics::{
+        color::{ColorExt as _, Hsl},
+        font, Canvas, Color, Drawable, Lazy, Texture, TextureSlice,
+    },
     image,
     math::*,
-    time, Context,
+    Context,
 };
 
 #[derive(Soars)]
@@ -23,7 +26,6 @@ struct Game {
     bullets: Soa<Bullet>,
     bullet_texture: Lazy<Texture>,
     elapsed: usize,
-    last_draw_time: time::Instant,
     font: Lazy<Vec<font::Attrs>>,
 }
 
@@ -44,6 +46,8 @@ const SIZE: UVec2 = uvec2(1024, 1024);
 const SCALE: u32 = 2;
 
 impl teenygame::Game for Game {
+    type Event = std::convert::Infallible;
+
     fn new() -> Self {
         Self {
             n: 0,
@@ -52,7 +56,6 @@ impl teenygame::Game for Game {
                 image::load_from_memory(include_bytes!("Shot_01.png")).unwrap(),
             ),
             elapsed: 0,
-            last_draw_time: time::Instant::now(),
             font: Lazy::new(include_bytes!("PixelOperator.ttf").to_vec()),
         }
     }
@@ -117,7 +120,6 @@ impl teenygame::Game for Game {
     }
 
     fn draw<'a>(&'a mut self, ctxt: &mut Context, canvas: &mut Canvas<'a>) {
-        let start_time = time::Instant::now();
         let slices = TextureSlices::new(self.bullet_texture.get_or_load(ctxt.gfx)).unwrap();
 
         let mut to_draw = self
@@ -136,17 +138,14 @@ impl teenygame::Game for Game {
         to_draw.sort_by_key(|(n, (_, _))| *n);
 
         for (n, (pos, theta)) in to_draw {
-            let color = coolor::Hsl {
+            let color = Color::from_hsl(Hsl {
                 h: n as f32 / 5.0,
                 s: 1.0,
                 l: 0.5,
-            }
-            .to_rgb();
+            });
 
             canvas.draw(
-                slices
-                    .bullet
-                    .tinted(Color::new(color.r, color.g, color.b, 0xff)),
+                slices.bullet.tinted(color),
                 Affine2::from_scale(vec2(SCALE as f32, SCALE as f32))
                     * Affine2::from_translation(vec2(pos.x, pos.y))
                     * Affine2::from_angle(theta + TAU / 4.0)
@@ -160,9 +159,12 @@ impl teenygame::Game for Game {
             ctxt.gfx
                 .prepare_text(
                     format!(
-                        "num bullets: {}\nfps: {:.02}",
+                        "num bullets: {}\nfps: {:.02}\np99: {:.02}ms\nupdate: {:.02}ms\ndraw: {:.02}ms",
                         self.bullets.len(),
-                        1.0 / (start_time - self.last_draw_time).as_secs_f32()
+                        ctxt.time.fps(),
+                        ctxt.time.frame_time_percentile(0.99).as_secs_f32() * 1000.0,
+                        ctxt.time.update_duration().as_secs_f32() * 1000.0,
+                        ctxt.time.draw_duration().as_secs_f32() * 1000.0,
                     ),
                     font::Metrics::relative(64.0, 1.0),
                     face,
@@ -170,7 +172,5 @@ impl teenygame::Game for Game {
                 .tinted(Color::new(0xff, 0xff, 0xff, 0xff)),
             translate(16.0, 56.0),
         );
-
-        self.last_draw_time = start_time;
     }
 }
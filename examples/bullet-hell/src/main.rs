@@ -2,7 +2,10 @@ use std::{f32::consts::TAU, num::NonZero};
 
 use soa_rs::{soa, Soa, Soars};
 use teenygame::{
-    graphics::{font, Canvas, Color, Drawable, Lazy, Texture, TextureSlice},
+    graphics::{
+        color::{ColorExt as _, Hsl},
+        font, Canvas, Color, Drawable, Lazy, Texture, TextureSlice,
+    },
     image,
     input::KeyCode,
     math::*,
@@ -52,6 +55,8 @@ const BULLET_RADIUS: f32 = 4.0;
 const PLAYER_HITBOX: f32 = 4.0;
 
 impl teenygame::Game for Game {
+    type Event = std::convert::Infallible;
+
     fn new() -> Self {
         Self {
             deaths: 0,
@@ -195,17 +200,14 @@ impl teenygame::Game for Game {
         to_draw.sort_by_key(|(n, (_, _))| *n);
 
         for (n, (pos, theta)) in to_draw {
-            let color = coolor::Hsl {
+            let color = Color::from_hsl(Hsl {
                 h: n as f32 / 5.0,
                 s: 1.0,
                 l: 0.5,
-            }
-            .to_rgb();
+            });
 
             canvas.draw(
-                slices
-                    .bullet
-                    .tinted(Color::new(color.r, color.g, color.b, 0xff)),
+                slices.bullet.tinted(color),
                 Affine2::from_scale(vec2(SCALE as f32, SCALE as f32))
                     * Affine2::from_translation(vec2(pos.x, pos.y))
                     * Affine2::from_angle(theta + TAU / 4.0)
@@ -1,10 +1,9 @@
-use rand::prelude::IteratorRandom;
 use std::collections::VecDeque;
 use teenygame::{
     audio::{PlaybackHandle, Region, Sound, Source},
-    graphics::{font, Canvas, Color, Drawable as _, Lazy, Texture},
+    graphics::{font, Canvas, CanvasExt as _, Color, Drawable as _, Lazy, Texture},
     input::KeyCode,
-    math::*,
+    math::{rng::Rng, *},
     Context,
 };
 
@@ -25,7 +24,7 @@ const WEST: IVec2 = ivec2(-1, 0);
 
 #[teenygame::game]
 struct Game {
-    texture: Lazy<Texture>,
+    pixel: Lazy<Texture>,
     pickup_sfx: Sound,
     game_over_sfx: Sound,
     bgm_handle: Option<PlaybackHandle>,
@@ -37,28 +36,27 @@ struct Game {
     score: u32,
     elapsed: u32,
     font: Lazy<Vec<font::Attrs>>,
+    rng: Rng,
 }
 
 impl Game {
     fn spawn_fruit(&mut self) {
-        let mut rng = rand::thread_rng();
         let (x, y) = self
-            .board
-            .iter()
-            .enumerate()
-            .flat_map(|(y, row)| {
+            .rng
+            .choose_iter(self.board.iter().enumerate().flat_map(|(y, row)| {
                 row.iter()
                     .enumerate()
                     .filter(|(_, cell)| cell.is_none())
                     .map(move |(x, _)| (x, y))
-            })
-            .choose(&mut rng)
+            }))
             .unwrap();
         self.board[y][x] = Some(Cell::Fruit);
     }
 }
 
 impl teenygame::Game for Game {
+    type Event = std::convert::Infallible;
+
     fn new() -> Self {
         let mut board = [[None; BOARD_SIZE.x as usize]; BOARD_SIZE.y as usize];
         let snake = VecDeque::from([BOARD_SIZE / 2]);
@@ -68,11 +66,7 @@ impl teenygame::Game for Game {
         }
 
         let mut game = Self {
-            texture: Lazy::new(teenygame::image::Img::new(
-                vec![Color::new(0xff, 0xff, 0xff, 0xff)],
-                uvec2(1, 1),
-                1,
-            )),
+            pixel: Lazy::white_pixel(),
             pickup_sfx: Sound::new(Source::load(include_bytes!("pickup.wav")).unwrap()),
             game_over_sfx: Sound::new(Source::load(include_bytes!("game_over.wav")).unwrap()),
             bgm_handle: None,
@@ -84,6 +78,7 @@ impl teenygame::Game for Game {
             score: 0,
             elapsed: 0,
             font: Lazy::new(include_bytes!("PixelOperator.ttf").to_vec()),
+            rng: Rng::from_time(),
         };
         game.spawn_fruit();
         game
@@ -178,20 +173,22 @@ impl teenygame::Game for Game {
     }
 
     fn draw<'a>(&'a mut self, ctxt: &mut Context, canvas: &mut Canvas<'a>) {
-        let texture = self.texture.get_or_load(ctxt.gfx);
+        let pixel = self.pixel.get_or_load(ctxt.gfx).layer(0).unwrap();
 
         for (y, row) in self.board.iter().enumerate() {
             for (x, cell) in row.iter().enumerate() {
-                canvas.draw(
-                    texture.layer(0).unwrap().tinted(match cell {
-                        None => {
-                            continue;
-                        }
-                        Some(Cell::Fruit) => Color::new(0xff, 0x00, 0x00, 0xff),
-                        Some(Cell::Snake) => Color::new(0xff, 0xff, 0xff, 0xff),
-                    }),
-                    Affine2::from_translation(vec2(x as f32, y as f32) * CELL_SIZE as f32)
-                        * Affine2::from_scale(vec2(CELL_SIZE as f32, CELL_SIZE as f32)),
+                let color = match cell {
+                    None => {
+                        continue;
+                    }
+                    Some(Cell::Fruit) => Color::new(0xff, 0x00, 0x00, 0xff),
+                    Some(Cell::Snake) => Color::new(0xff, 0xff, 0xff, 0xff),
+                };
+                let pos = vec2(x as f32, y as f32) * CELL_SIZE as f32;
+                canvas.fill_rect(
+                    pixel,
+                    Rect::from_pos_size(pos, Vec2::splat(CELL_SIZE as f32)),
+                    color,
                 );
             }
         }
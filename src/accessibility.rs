@@ -0,0 +1,135 @@
+//! Accessibility preferences.
+//!
+//! Players may ask their OS or browser for less motion or more contrast. Games can follow these hints to tone down effects automatically, while still offering their own settings:
+//!
+//! ```ignore
+//! let shake = if ctxt.accessibility().reduced_motion { 0.0 } else { self.shake };
+//! ```
+//!
+//! On WASM, preferences are read from the browser's `prefers-reduced-motion`, `prefers-contrast` and `forced-colors` media queries. Native platforms don't expose them portably, so they are only set by the `reduced-motion` and `high-contrast` launch flags, which also apply on WASM.
+//!
+//! Text may also be announced to screen readers, e.g. the label of the focused menu item, such that menus are usable without seeing them:
+//!
+//! ```ignore
+//! if let Some(event) = self.nav.update(&widgets, ctxt.input) {
+//!     event.announce(|id| id.label().to_string());
+//! }
+//! ```
+//!
+//! Announcements go to the [`Announcer`] set via [`set_announcer`] if any, e.g. one forwarding to a text-to-speech library. Otherwise:
+//!
+//! - On WASM, they go to an ARIA live region on the page, which the browser passes on to the screen reader.
+//! - On desktop platforms with the `accesskit` feature enabled, they go to a live region exposed to the screen reader via [AccessKit](https://accesskit.dev).
+//! - Elsewhere, nothing reads them out, so games that should be playable without seeing them need to enable the `accesskit` feature or set an [`Announcer`].
+
+use std::sync::RwLock;
+
+#[cfg(all(
+    feature = "accesskit",
+    not(any(target_arch = "wasm32", target_os = "android", target_os = "ios"))
+))]
+pub(crate) mod native;
+
+/// Accessibility hints from the player's OS, browser or launch options.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct Preferences {
+    /// Whether the player prefers reduced motion, e.g. no screen shake, flashing or parallax.
+    pub reduced_motion: bool,
+
+    /// Whether the player prefers high contrast, e.g. stronger outlines and no subtle color differences.
+    pub high_contrast: bool,
+}
+
+/// Gets the player's current accessibility preferences.
+///
+/// This is also available as [`crate::Context::accessibility`]. Preferences may change while the game is running, e.g. when the player changes their browser settings.
+pub fn preferences() -> Preferences {
+    let launch_options = crate::launch::launch_options();
+    Preferences {
+        reduced_motion: launch_options.has("reduced-motion") || system_reduced_motion(),
+        high_contrast: launch_options.has("high-contrast") || system_high_contrast(),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn system_reduced_motion() -> bool {
+    crate::web::media_matches("(prefers-reduced-motion: reduce)")
+}
+
+#[cfg(target_arch = "wasm32")]
+fn system_high_contrast() -> bool {
+    crate::web::media_matches("(prefers-contrast: more)")
+        || crate::web::media_matches("(forced-colors: active)")
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn system_reduced_motion() -> bool {
+    false
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn system_high_contrast() -> bool {
+    false
+}
+
+/// How urgently an announcement should be read out.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum Politeness {
+    /// Read out once the screen reader is done with what it is currently reading.
+    #[default]
+    Polite,
+
+    /// Read out immediately, interrupting what the screen reader is currently reading.
+    Assertive,
+}
+
+/// Reads out announcements.
+pub trait Announcer: Send + Sync {
+    /// Reads out the given text.
+    ///
+    /// This may be called from any thread, and should not block.
+    fn announce(&self, text: &str, politeness: Politeness);
+}
+
+static ANNOUNCER: RwLock<Option<Box<dyn Announcer>>> = RwLock::new(None);
+
+/// Sets the announcer that reads out announcements, replacing any previous one and the platform's default.
+pub fn set_announcer(announcer: impl Announcer + 'static) {
+    *ANNOUNCER.write().unwrap_or_else(|e| e.into_inner()) = Some(Box::new(announcer));
+}
+
+/// Removes the announcer.
+pub fn clear_announcer() {
+    *ANNOUNCER.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Announces text to screen readers once they are done with what they are currently reading.
+pub fn announce(text: &str) {
+    announce_with(text, Politeness::Polite);
+}
+
+/// Announces text to screen readers with the given urgency.
+pub fn announce_with(text: &str, politeness: Politeness) {
+    if let Some(announcer) = ANNOUNCER.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        announcer.announce(text, politeness);
+        return;
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    crate::web::announce(text, politeness == Politeness::Assertive);
+
+    #[cfg(all(
+        feature = "accesskit",
+        not(any(target_arch = "wasm32", target_os = "android", target_os = "ios"))
+    ))]
+    native::announce(text, politeness);
+
+    #[cfg(not(any(
+        target_arch = "wasm32",
+        all(
+            feature = "accesskit",
+            not(any(target_os = "android", target_os = "ios"))
+        )
+    )))]
+    log::debug!("announce: {text}");
+}
@@ -0,0 +1,154 @@
+use std::sync::Mutex;
+
+use accesskit::{
+    ActionHandler, ActionRequest, ActivationHandler, Live, Node, NodeId, Role, Tree, TreeUpdate,
+};
+use winit::raw_window_handle::{HasWindowHandle as _, RawWindowHandle};
+
+use super::Politeness;
+
+const WINDOW: NodeId = NodeId(0);
+const POLITE: NodeId = NodeId(1);
+const ASSERTIVE: NodeId = NodeId(2);
+
+/// Announcements not yet passed on to the adapter, as they may be made from any thread, but the adapter must be used from the one running the event loop.
+static PENDING: Mutex<Vec<(String, Politeness)>> = Mutex::new(vec![]);
+
+pub(crate) fn announce(text: &str, politeness: Politeness) {
+    PENDING
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push((text.to_string(), politeness));
+}
+
+/// Creates a live region holding the last announcement of the given politeness.
+fn live_region(live: Live, text: &str) -> Node {
+    let mut node = Node::new(Role::Label);
+    node.set_live(live);
+    node.set_value(text);
+    node
+}
+
+struct InitialTree;
+
+impl ActivationHandler for InitialTree {
+    fn request_initial_tree(&mut self) -> Option<TreeUpdate> {
+        let mut window = Node::new(Role::Window);
+        window.set_children(vec![POLITE, ASSERTIVE]);
+        Some(TreeUpdate {
+            nodes: vec![
+                (WINDOW, window),
+                (POLITE, live_region(Live::Polite, "")),
+                (ASSERTIVE, live_region(Live::Assertive, "")),
+            ],
+            tree: Some(Tree::new(WINDOW)),
+            focus: WINDOW,
+        })
+    }
+}
+
+/// The tree only holds announcements, so there is nothing to act on.
+struct NoActions;
+
+impl ActionHandler for NoActions {
+    fn do_action(&mut self, _request: ActionRequest) {}
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+struct NoDeactivation;
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+impl accesskit::DeactivationHandler for NoDeactivation {
+    fn deactivate_accessibility(&mut self) {}
+}
+
+#[cfg(target_os = "windows")]
+type PlatformAdapter = accesskit_windows::SubclassingAdapter;
+
+#[cfg(target_os = "macos")]
+type PlatformAdapter = accesskit_macos::SubclassingAdapter;
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+type PlatformAdapter = accesskit_unix::Adapter;
+
+/// Exposes announcements to the platform's screen reader via AccessKit.
+pub(crate) struct Adapter(PlatformAdapter);
+
+impl Adapter {
+    /// Creates an adapter for the window, returning [`None`] if its handle is not of the platform's kind.
+    ///
+    /// Unlike the adapter from `accesskit_winit`, this may be created after the window was shown, which wginit does before we see it.
+    pub(crate) fn new(window: &winit::window::Window) -> Option<Self> {
+        let handle = window.window_handle().ok()?.as_raw();
+
+        #[cfg(target_os = "windows")]
+        let adapter = match handle {
+            RawWindowHandle::Win32(handle) => accesskit_windows::SubclassingAdapter::new(
+                accesskit_windows::HWND(handle.hwnd.get() as _),
+                InitialTree,
+                NoActions,
+            ),
+            _ => return None,
+        };
+
+        // SAFETY: The view belongs to the window, which outlives the adapter as both are owned by the application.
+        #[cfg(target_os = "macos")]
+        let adapter = match handle {
+            RawWindowHandle::AppKit(handle) => unsafe {
+                accesskit_macos::SubclassingAdapter::new(
+                    handle.ns_view.as_ptr(),
+                    InitialTree,
+                    NoActions,
+                )
+            },
+            _ => return None,
+        };
+
+        // AT-SPI is not tied to a window.
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        let adapter = {
+            _ = handle;
+            accesskit_unix::Adapter::new(InitialTree, NoActions, NoDeactivation)
+        };
+
+        Some(Self(adapter))
+    }
+
+    /// Tells the screen reader whether the window has focus, which AT-SPI can't find out by itself.
+    pub(crate) fn set_focused(&mut self, focused: bool) {
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        self.0.update_window_focus_state(focused);
+
+        #[cfg(any(target_os = "windows", target_os = "macos"))]
+        _ = focused;
+    }
+
+    /// Passes announcements made since the last update on to the screen reader, if one is active.
+    pub(crate) fn update(&mut self) {
+        let pending = std::mem::take(&mut *PENDING.lock().unwrap_or_else(|e| e.into_inner()));
+        for (text, politeness) in pending {
+            let (id, live) = match politeness {
+                Politeness::Polite => (POLITE, Live::Polite),
+                Politeness::Assertive => (ASSERTIVE, Live::Assertive),
+            };
+            // Clear the region first, such that repeating the same text is announced again.
+            for text in ["", &text] {
+                self.update_if_active(|| TreeUpdate {
+                    nodes: vec![(id, live_region(live, text))],
+                    tree: None,
+                    focus: WINDOW,
+                });
+            }
+        }
+    }
+
+    fn update_if_active(&mut self, update: impl FnOnce() -> TreeUpdate) {
+        #[cfg(any(target_os = "windows", target_os = "macos"))]
+        if let Some(events) = self.0.update_if_active(update) {
+            events.raise();
+        }
+
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        self.0.update_if_active(update);
+    }
+}
@@ -0,0 +1,39 @@
+//! Glue for running on Android.
+
+use jni::objects::{JObject, JValue};
+use jni::JavaVM;
+
+/// Calls a function with a JNI environment and a system service, e.g. `VIBRATOR_SERVICE`.
+///
+/// `name` is the name of the constant in `android.content.Context` holding the service's name. The function is not called if the service is unavailable.
+///
+/// The game thread never returns to Java, so local references are only freed when their frame is popped. Everything runs in a local frame popped before returning, so `f` must not return local references.
+pub fn with_system_service<T>(
+    name: &str,
+    f: impl FnOnce(&mut jni::JNIEnv, &JObject) -> jni::errors::Result<T>,
+) -> jni::errors::Result<Option<T>> {
+    let ctxt = ndk_context::android_context();
+
+    // SAFETY: The VM and activity pointers are provided by the Android glue and stay valid for the lifetime of the app.
+    let vm = unsafe { JavaVM::from_raw(ctxt.vm().cast()) }?;
+    let activity = unsafe { JObject::from_raw(ctxt.context().cast()) };
+    let mut env = vm.attach_current_thread()?;
+
+    env.with_local_frame(16, |env| {
+        let service_name = env
+            .get_static_field("android/content/Context", name, "Ljava/lang/String;")?
+            .l()?;
+        let service = env
+            .call_method(
+                &activity,
+                "getSystemService",
+                "(Ljava/lang/String;)Ljava/lang/Object;",
+                &[JValue::Object(&service_name)],
+            )?
+            .l()?;
+        if service.is_null() {
+            return Ok(None);
+        }
+        f(env, &service).map(Some)
+    })
+}
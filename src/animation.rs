@@ -0,0 +1,338 @@
+//! Flipbook animations and animation state machines.
+//!
+//! An [`Animation`] cycles through frames at a fixed rate. Frames may be anything, e.g. [`crate::graphics::TextureSlice`]s or indices into a sprite sheet.
+//!
+//! An [`AnimationGraph`] switches between named animation states based on parameters set by the game, crossfading between them:
+//!
+//! ```ignore
+//! let mut graph = AnimationGraph::new();
+//! let idle = graph.add_state("idle", Animation::new(idle_frames, Duration::from_millis(200)));
+//! let run = graph.add_state("run", Animation::new(run_frames, Duration::from_millis(100)));
+//! graph.add_transition(idle, run, Condition::Greater("speed".into(), 0.1), Duration::ZERO);
+//! graph.add_transition(run, idle, Condition::Less("speed".into(), 0.1), Duration::from_millis(100));
+//!
+//! graph.set_float("speed", self.player.vel.length());
+//! graph.update(dt);
+//! for (frame, weight) in graph.frames() {
+//!     // Draw the frame with its weight as opacity.
+//! }
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+/// A flipbook animation.
+#[derive(Clone, Debug)]
+pub struct Animation<F> {
+    frames: Vec<F>,
+    frame_duration: Duration,
+    looping: bool,
+}
+
+impl<F> Animation<F> {
+    /// Creates a looping animation where each frame is shown for the given duration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no frames.
+    pub fn new(frames: Vec<F>, frame_duration: Duration) -> Self {
+        assert!(!frames.is_empty(), "animation must have at least one frame");
+        Self {
+            frames,
+            frame_duration,
+            looping: true,
+        }
+    }
+
+    /// Sets whether the animation loops. Non-looping animations stay on their last frame once finished.
+    pub fn looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    /// Gets the frames.
+    pub fn frames(&self) -> &[F] {
+        &self.frames
+    }
+
+    /// Gets the duration of a single pass through all frames.
+    pub fn duration(&self) -> Duration {
+        self.frame_duration * self.frames.len() as u32
+    }
+
+    /// Checks if a non-looping animation has finished after playing for the given time.
+    ///
+    /// Looping animations never finish.
+    pub fn is_finished(&self, elapsed: Duration) -> bool {
+        !self.looping && elapsed >= self.duration()
+    }
+
+    /// Gets the index of the frame to show after playing for the given time.
+    pub fn frame_index(&self, elapsed: Duration) -> usize {
+        let i =
+            (elapsed.as_secs_f64() / self.frame_duration.as_secs_f64().max(f64::EPSILON)) as usize;
+        if self.looping {
+            i % self.frames.len()
+        } else {
+            i.min(self.frames.len() - 1)
+        }
+    }
+
+    /// Gets the frame to show after playing for the given time.
+    pub fn frame(&self, elapsed: Duration) -> &F {
+        &self.frames[self.frame_index(elapsed)]
+    }
+}
+
+/// Identifies a state in an [`AnimationGraph`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct StateId(usize);
+
+/// A condition for a transition between animation states.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Condition {
+    /// Always true.
+    Always,
+
+    /// The named bool parameter has the given value.
+    Bool(String, bool),
+
+    /// The named float parameter is greater than the given value.
+    Greater(String, f32),
+
+    /// The named float parameter is less than the given value.
+    Less(String, f32),
+
+    /// The named trigger was fired. Triggers are consumed when a transition is taken.
+    Trigger(String),
+
+    /// The current state's animation has finished. This is never true for looping animations.
+    Finished,
+
+    /// All of the conditions are true.
+    All(Vec<Condition>),
+
+    /// Any of the conditions is true.
+    Any(Vec<Condition>),
+}
+
+struct State<F> {
+    name: String,
+    animation: Animation<F>,
+}
+
+struct Transition {
+    /// The state to transition from, or `None` for any state.
+    from: Option<StateId>,
+    to: StateId,
+    condition: Condition,
+    crossfade: Duration,
+}
+
+/// The state that is being faded out after a transition.
+struct Fade {
+    state: StateId,
+    elapsed: Duration,
+    remaining: Duration,
+    crossfade: Duration,
+}
+
+/// A state machine that selects animations based on parameters.
+///
+/// Transitions are checked in the order they were added, and the first one whose condition holds is taken.
+pub struct AnimationGraph<F> {
+    states: Vec<State<F>>,
+    transitions: Vec<Transition>,
+    floats: HashMap<String, f32>,
+    bools: HashMap<String, bool>,
+    triggers: HashSet<String>,
+    current: StateId,
+    elapsed: Duration,
+    fade: Option<Fade>,
+}
+
+impl<F> Default for AnimationGraph<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F> AnimationGraph<F> {
+    /// Creates an empty animation graph.
+    pub fn new() -> Self {
+        Self {
+            states: vec![],
+            transitions: vec![],
+            floats: HashMap::new(),
+            bools: HashMap::new(),
+            triggers: HashSet::new(),
+            current: StateId(0),
+            elapsed: Duration::ZERO,
+            fade: None,
+        }
+    }
+
+    /// Adds a state. The first state added is the initial state.
+    pub fn add_state(&mut self, name: impl Into<String>, animation: Animation<F>) -> StateId {
+        self.states.push(State {
+            name: name.into(),
+            animation,
+        });
+        StateId(self.states.len() - 1)
+    }
+
+    /// Adds a transition from one state to another, crossfading over the given duration.
+    pub fn add_transition(
+        &mut self,
+        from: StateId,
+        to: StateId,
+        condition: Condition,
+        crossfade: Duration,
+    ) {
+        self.transitions.push(Transition {
+            from: Some(from),
+            to,
+            condition,
+            crossfade,
+        });
+    }
+
+    /// Adds a transition from any state other than the target, e.g. to a hurt animation.
+    pub fn add_any_transition(&mut self, to: StateId, condition: Condition, crossfade: Duration) {
+        self.transitions.push(Transition {
+            from: None,
+            to,
+            condition,
+            crossfade,
+        });
+    }
+
+    /// Finds a state by name.
+    pub fn state(&self, name: &str) -> Option<StateId> {
+        self.states
+            .iter()
+            .position(|state| state.name == name)
+            .map(StateId)
+    }
+
+    /// Gets the name of a state.
+    pub fn state_name(&self, id: StateId) -> &str {
+        &self.states[id.0].name
+    }
+
+    /// Gets the current state.
+    pub fn current_state(&self) -> StateId {
+        self.current
+    }
+
+    /// Gets how long the current state has been playing.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Sets a float parameter.
+    pub fn set_float(&mut self, name: &str, value: f32) {
+        self.floats.insert(name.to_string(), value);
+    }
+
+    /// Sets a bool parameter.
+    pub fn set_bool(&mut self, name: &str, value: bool) {
+        self.bools.insert(name.to_string(), value);
+    }
+
+    /// Fires a trigger, which stays set until a transition that checks it is taken.
+    pub fn trigger(&mut self, name: &str) {
+        self.triggers.insert(name.to_string());
+    }
+
+    /// Switches to a state immediately, regardless of transitions.
+    pub fn play(&mut self, id: StateId, crossfade: Duration) {
+        self.fade = (!crossfade.is_zero()).then_some(Fade {
+            state: self.current,
+            elapsed: self.elapsed,
+            remaining: crossfade,
+            crossfade,
+        });
+        self.current = id;
+        self.elapsed = Duration::ZERO;
+    }
+
+    fn check(&self, condition: &Condition) -> bool {
+        match condition {
+            Condition::Always => true,
+            Condition::Bool(name, value) => {
+                self.bools.get(name).copied().unwrap_or(false) == *value
+            }
+            Condition::Greater(name, value) => self.floats.get(name).is_some_and(|v| v > value),
+            Condition::Less(name, value) => self.floats.get(name).is_some_and(|v| v < value),
+            Condition::Trigger(name) => self.triggers.contains(name),
+            Condition::Finished => self.states[self.current.0]
+                .animation
+                .is_finished(self.elapsed),
+            Condition::All(conditions) => conditions.iter().all(|c| self.check(c)),
+            Condition::Any(conditions) => conditions.iter().any(|c| self.check(c)),
+        }
+    }
+
+    fn consume_triggers(&mut self, condition: &Condition) {
+        match condition {
+            Condition::Trigger(name) => {
+                self.triggers.remove(name);
+            }
+            Condition::All(conditions) | Condition::Any(conditions) => {
+                for c in conditions {
+                    self.consume_triggers(c);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Advances time and takes at most one transition.
+    pub fn update(&mut self, dt: Duration) {
+        self.elapsed += dt;
+        if let Some(fade) = &mut self.fade {
+            fade.elapsed += dt;
+            fade.remaining = fade.remaining.saturating_sub(dt);
+            if fade.remaining.is_zero() {
+                self.fade = None;
+            }
+        }
+
+        let Some(i) = self.transitions.iter().position(|t| {
+            t.from
+                .map_or(t.to != self.current, |from| from == self.current)
+                && self.check(&t.condition)
+        }) else {
+            return;
+        };
+        let condition = self.transitions[i].condition.clone();
+        self.consume_triggers(&condition);
+        self.play(self.transitions[i].to, self.transitions[i].crossfade);
+    }
+
+    /// Gets the frame of the current state.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no states were added.
+    pub fn frame(&self) -> &F {
+        self.states[self.current.0].animation.frame(self.elapsed)
+    }
+
+    /// Gets the frames to draw along with their weights, which sum to 1.
+    ///
+    /// While crossfading, this includes the frame of the previous state, to be drawn with the given weight as opacity. Otherwise, this is just the current frame with weight 1.
+    pub fn frames(&self) -> impl Iterator<Item = (&F, f32)> + '_ {
+        let fade = self.fade.as_ref().map(|fade| {
+            let weight = fade.remaining.as_secs_f32() / fade.crossfade.as_secs_f32();
+            (
+                self.states[fade.state.0].animation.frame(fade.elapsed),
+                weight,
+            )
+        });
+        let current_weight = 1.0 - fade.as_ref().map_or(0.0, |(_, weight)| *weight);
+        fade.into_iter()
+            .chain(std::iter::once((self.frame(), current_weight)))
+    }
+}
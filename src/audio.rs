@@ -1,14 +1,22 @@
 //! Audio support.
 
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, Weak};
+use std::task::{Context, Poll, Waker};
 use std::time::Duration;
 
 pub use kira::sound::FromFileError;
 use kira::{
-    manager::{AudioManager, AudioManagerSettings, DefaultBackend},
+    clock::{ClockHandle, ClockSpeed},
+    manager::{backend::mock::MockBackend, AudioManager, AudioManagerSettings, DefaultBackend},
     sound::{
         static_sound::{StaticSoundData, StaticSoundHandle},
-        EndPosition, PlaybackPosition, PlaybackRate,
+        EndPosition, PlaybackPosition, PlaybackRate, PlaybackState,
     },
+    track::{TrackBuilder, TrackHandle},
+    tween::Tween,
     Volume,
 };
 
@@ -68,9 +76,22 @@ impl Source {
     ///
     /// This will also perform decoding, depending on what codecs are available. This uses [Symphonia](https://github.com/pdeljanov/Symphonia) internally.
     pub fn load(buf: &[u8]) -> Result<Self, FromFileError> {
-        Ok(Self(StaticSoundData::from_cursor(std::io::Cursor::new(
+        let source = Self(StaticSoundData::from_cursor(std::io::Cursor::new(
             buf.to_vec(),
-        ))?))
+        ))?);
+        crate::stats::AUDIO.add(source.decoded_bytes());
+        Ok(source)
+    }
+
+    fn decoded_bytes(&self) -> u64 {
+        // Frames are decoded to stereo f32 samples.
+        (self.num_frames() * 2 * std::mem::size_of::<f32>()) as u64
+    }
+}
+
+impl Drop for Source {
+    fn drop(&mut self) {
+        crate::stats::AUDIO.sub(self.decoded_bytes());
     }
 }
 
@@ -99,14 +120,20 @@ impl Sound {
     }
 }
 
+/// Wakers of [`Finished`] futures, which are woken every frame to check if their sound has finished.
+type Waiters = Arc<Mutex<Vec<Waker>>>;
+
 /// Handle for controlling playback of a currently playing sound.
 ///
 /// Will stop playback when dropped, unless detached.
-pub struct PlaybackHandle(Option<StaticSoundHandle>);
+pub struct PlaybackHandle {
+    handle: Option<Arc<Mutex<StaticSoundHandle>>>,
+    waiters: Waiters,
+}
 
 impl Drop for PlaybackHandle {
     fn drop(&mut self) {
-        if self.0.is_none() {
+        if self.handle.is_none() {
             return;
         }
         self.stop();
@@ -114,57 +141,480 @@ impl Drop for PlaybackHandle {
 }
 
 impl PlaybackHandle {
+    fn with_handle<T>(&self, f: impl FnOnce(&mut StaticSoundHandle) -> T) -> T {
+        f(&mut self.handle.as_ref().unwrap().lock().unwrap())
+    }
+
     /// Stops playback.
     pub fn stop(&mut self) {
-        self.0.as_mut().unwrap().stop(kira::tween::Tween::default());
+        self.with_handle(|h| h.stop(kira::tween::Tween::default()));
     }
 
     /// Set panning of the audio, where -1.0 is hard left and 1.0 is hard right.
     pub fn set_panning(&mut self, panning: f64) {
-        self.0
-            .as_mut()
-            .unwrap()
-            .set_panning((panning - 0.5) * 2.0, kira::tween::Tween::default());
+        self.with_handle(|h| h.set_panning((panning - 0.5) * 2.0, kira::tween::Tween::default()));
     }
 
     /// Set volume of the audio, where the volume is the multiplier of the amplitude.
     pub fn set_volume(&mut self, volume: f64) {
-        self.0
-            .as_mut()
-            .unwrap()
-            .set_volume(Volume::Amplitude(volume), kira::tween::Tween::default());
+        self.with_handle(|h| {
+            h.set_volume(Volume::Amplitude(volume), kira::tween::Tween::default())
+        });
     }
 
     /// Set speed of the audio, where the speed is the multiplier of the play speed.
     pub fn set_speed(&mut self, speed: f64) {
-        self.0
-            .as_mut()
-            .unwrap()
-            .set_playback_rate(PlaybackRate::Factor(speed), kira::tween::Tween::default());
+        self.with_handle(|h| {
+            h.set_playback_rate(PlaybackRate::Factor(speed), kira::tween::Tween::default())
+        });
+    }
+
+    /// Checks if the sound is currently playing.
+    pub fn is_playing(&self) -> bool {
+        self.with_handle(|h| h.state() == PlaybackState::Playing)
+    }
+
+    /// Checks if the sound has finished playing, either by reaching its end or by being stopped.
+    ///
+    /// Sounds with a loop region never finish on their own.
+    pub fn is_finished(&self) -> bool {
+        self.with_handle(|h| h.state() == PlaybackState::Stopped)
+    }
+
+    /// Gets a future that resolves when the sound has finished playing.
+    ///
+    /// The future does not borrow the handle, so it may be spawned to sequence sounds:
+    ///
+    /// ```ignore
+    /// let finished = jingle.finished();
+    /// teenygame::futures::spawn(async move {
+    ///     finished.await;
+    ///     // Resume background music.
+    /// });
+    /// ```
+    pub fn finished(&self) -> Finished {
+        Finished {
+            handle: self.handle.clone().unwrap(),
+            waiters: self.waiters.clone(),
+        }
     }
 
     /// Detaches this playback such that it will continue playing.
     ///
     /// Note that this consumes the handle and it will be lost after detaching.
     pub fn detach(mut self) {
-        self.0 = None;
+        self.handle = None;
+    }
+}
+
+/// A future that resolves when a sound has finished playing.
+///
+/// Created by [`PlaybackHandle::finished`].
+pub struct Finished {
+    handle: Arc<Mutex<StaticSoundHandle>>,
+    waiters: Waiters,
+}
+
+impl Future for Finished {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.handle.lock().unwrap().state() == PlaybackState::Stopped {
+            return Poll::Ready(());
+        }
+        self.waiters.lock().unwrap().push(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+/// How far a stem may drift from the first stem before it is seeked back into sync, in seconds.
+const MAX_STEM_DRIFT: f64 = 0.02;
+
+/// Handle for controlling synchronized music stems, e.g. for an adaptive soundtrack that adds layers as gameplay intensifies.
+///
+/// Created by [`Audio::play_layers`]. Will stop playback when dropped.
+pub struct MusicLayers {
+    stems: Arc<Mutex<Vec<StaticSoundHandle>>>,
+
+    /// The clock the stems were scheduled on, which must outlive them.
+    _clock: ClockHandle,
+}
+
+impl Drop for MusicLayers {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+impl MusicLayers {
+    /// Gets the number of layers.
+    pub fn len(&self) -> usize {
+        self.stems.lock().unwrap().len()
+    }
+
+    /// Checks if there are no layers.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Fades a layer to the given volume over the given duration, where the volume is the multiplier of the amplitude.
+    pub fn set_layer_volume(&mut self, layer: usize, volume: f64, fade: Duration) {
+        self.stems.lock().unwrap()[layer].set_volume(
+            Volume::Amplitude(volume),
+            Tween {
+                duration: fade,
+                ..Default::default()
+            },
+        );
+    }
+
+    /// Fades layers in and out according to the given intensity, from 0 to 1.
+    ///
+    /// The first layer is always audible, and the remaining layers fade in one after another as intensity rises, such that all layers are audible at 1.
+    pub fn set_intensity(&mut self, intensity: f64, fade: Duration) {
+        let len = self.len();
+        let steps = len.saturating_sub(1).max(1) as f64;
+        for layer in 0..len {
+            let volume = (intensity.clamp(0.0, 1.0) * steps - (layer as f64 - 1.0)).clamp(0.0, 1.0);
+            self.set_layer_volume(layer, volume, fade);
+        }
+    }
+
+    /// Stops all layers.
+    pub fn stop(&mut self) {
+        for stem in self.stems.lock().unwrap().iter_mut() {
+            stem.stop(Tween::default());
+        }
+    }
+}
+
+/// Lowers the volume of one bus while sounds are playing on another, e.g. to keep dialogue audible over music.
+#[derive(Clone, Debug)]
+pub struct Ducking {
+    /// The bus whose volume is lowered.
+    pub target: String,
+
+    /// The bus whose sounds trigger ducking.
+    pub trigger: String,
+
+    /// How much to lower the target's volume by, in decibels.
+    pub amount_db: f64,
+
+    /// How long it takes to lower the volume once a sound starts playing on the trigger bus.
+    pub attack: Duration,
+
+    /// How long it takes to restore the volume once no sounds are playing on the trigger bus.
+    pub release: Duration,
+}
+
+struct Bus {
+    track: TrackHandle,
+
+    /// Volume set by the game, as an amplitude multiplier.
+    volume: f64,
+
+    /// Amplitude multiplier from ducking.
+    duck: f64,
+
+    /// Sounds that may still be playing on this bus.
+    playing: Vec<Arc<Mutex<StaticSoundHandle>>>,
+
+    /// Whether the game paused this bus.
+    paused: bool,
+}
+
+impl Bus {
+    fn apply_volume(&mut self, duration: Duration) {
+        self.track.set_volume(
+            Volume::Amplitude(self.volume * self.duck),
+            Tween {
+                duration,
+                ..Default::default()
+            },
+        );
     }
 }
 
+/// Audio manager, either playing to the audio device or, when running headlessly (e.g. in tests), to nowhere.
+enum Manager {
+    Device(AudioManager),
+    Mock(AudioManager<MockBackend>),
+}
+
+/// Calls the same method on the audio manager, whichever backend it uses.
+macro_rules! manager {
+    ($manager:expr, $m:ident => $call:expr) => {
+        match $manager {
+            Manager::Device($m) => $call,
+            Manager::Mock($m) => $call,
+        }
+    };
+}
+
 /// Context for playing audio.
 pub struct Audio {
-    audio_manager: AudioManager,
+    audio_manager: Manager,
+    waiters: Waiters,
+    buses: HashMap<String, Bus>,
+    duckings: Vec<Ducking>,
+    music_layers: Vec<Weak<Mutex<Vec<StaticSoundHandle>>>>,
+
+    /// Whether the game paused all audio.
+    paused: bool,
+
+    /// Whether the app is suspended, which pauses all audio independently of the game.
+    suspended: bool,
 }
 
 impl Audio {
     pub(crate) fn new() -> Result<Self, Box<dyn std::error::Error>> {
-        Ok(Self {
-            audio_manager: AudioManager::<DefaultBackend>::new(AudioManagerSettings::default())?,
-        })
+        let manager = AudioManager::<DefaultBackend>::new(AudioManagerSettings::default())?;
+        Ok(Self::with_manager(Manager::Device(manager)))
+    }
+
+    /// Creates audio that isn't played to any device, for running without one, e.g. on headless CI machines.
+    pub(crate) fn headless() -> Self {
+        Self::with_manager(Manager::Mock(
+            AudioManager::<MockBackend>::new(AudioManagerSettings::default())
+                .expect("mock audio backend can't fail"),
+        ))
+    }
+
+    fn with_manager(audio_manager: Manager) -> Self {
+        Self {
+            audio_manager,
+            waiters: Default::default(),
+            buses: HashMap::new(),
+            duckings: vec![],
+            music_layers: vec![],
+            paused: false,
+            suspended: false,
+        }
+    }
+
+    /// Called every frame.
+    pub(crate) fn update(&mut self) {
+        // Nothing drives the mock backend, so process its commands and a sample per update, such that sounds still finish and free their resources.
+        if let Manager::Mock(manager) = &mut self.audio_manager {
+            manager.backend_mut().on_start_processing();
+            manager.backend_mut().process();
+        }
+
+        // Wake all Finished futures such that they check if their sound has finished.
+        for waker in std::mem::take(&mut *self.waiters.lock().unwrap()) {
+            waker.wake();
+        }
+
+        for bus in self.buses.values_mut() {
+            bus.playing
+                .retain(|handle| handle.lock().unwrap().state() != PlaybackState::Stopped);
+        }
+
+        // Seek drifting stems back to the first stem, e.g. after one of them underran.
+        self.music_layers.retain(|stems| {
+            let Some(stems) = stems.upgrade() else {
+                return false;
+            };
+            let mut stems = stems.lock().unwrap();
+            let Some((first, rest)) = stems.split_first_mut() else {
+                return false;
+            };
+            if first.state() != PlaybackState::Playing {
+                return first.state() != PlaybackState::Stopped;
+            }
+            let position = first.position();
+            for stem in rest {
+                if stem.state() == PlaybackState::Playing
+                    && (stem.position() - position).abs() > MAX_STEM_DRIFT
+                {
+                    stem.seek_to(position);
+                }
+            }
+            true
+        });
+
+        // Each ducked bus takes the strongest ducking among its active rules.
+        let mut targets = HashMap::<&str, (f64, Duration)>::new();
+        for ducking in self.duckings.iter() {
+            let active = self.buses.get(&ducking.trigger).is_some_and(|bus| {
+                bus.playing
+                    .iter()
+                    .any(|handle| handle.lock().unwrap().state() == PlaybackState::Playing)
+            });
+            let target = targets
+                .entry(&ducking.target)
+                .or_insert((1.0, Duration::ZERO));
+            if active {
+                let duck = 10f64.powf(-ducking.amount_db.abs() / 20.0);
+                if duck < target.0 {
+                    *target = (duck, ducking.attack);
+                }
+            } else if target.0 == 1.0 {
+                target.1 = target.1.max(ducking.release);
+            }
+        }
+        for (name, (duck, duration)) in targets {
+            let Some(bus) = self.buses.get_mut(name) else {
+                continue;
+            };
+            if bus.duck != duck {
+                bus.duck = duck;
+                bus.apply_volume(duration);
+            }
+        }
+    }
+
+    fn bus(&mut self, name: &str) -> &mut Bus {
+        if !self.buses.contains_key(name) {
+            let track =
+                manager!(&mut self.audio_manager, m => m.add_sub_track(TrackBuilder::new()))
+                    .unwrap();
+            self.buses.insert(
+                name.to_string(),
+                Bus {
+                    track,
+                    volume: 1.0,
+                    duck: 1.0,
+                    playing: vec![],
+                    paused: false,
+                },
+            );
+        }
+        self.buses.get_mut(name).unwrap()
+    }
+
+    /// Sets the volume of a bus, where the volume is the multiplier of the amplitude.
+    ///
+    /// Buses are created on first use.
+    pub fn set_bus_volume(&mut self, bus: &str, volume: f64) {
+        let bus = self.bus(bus);
+        bus.volume = volume;
+        bus.apply_volume(Duration::ZERO);
+    }
+
+    fn apply_paused(&mut self) {
+        if self.paused || self.suspended {
+            manager!(&self.audio_manager, m => m.pause(Tween::default()));
+        } else {
+            manager!(&self.audio_manager, m => m.resume(Tween::default()));
+        }
+    }
+
+    /// Called when the app is suspended or resumed.
+    pub(crate) fn set_suspended(&mut self, suspended: bool) {
+        self.suspended = suspended;
+        self.apply_paused();
+    }
+
+    /// Pauses all audio, including detached sounds, e.g. when opening a pause menu.
+    ///
+    /// Playback positions are preserved, such that [`Audio::resume_all`] continues where it left off. Audio is also paused while the app is suspended, independently of this.
+    pub fn pause_all(&mut self) {
+        self.paused = true;
+        self.apply_paused();
+    }
+
+    /// Resumes all audio paused by [`Audio::pause_all`].
+    pub fn resume_all(&mut self) {
+        self.paused = false;
+        self.apply_paused();
+    }
+
+    /// Checks if all audio was paused by [`Audio::pause_all`].
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Pauses all sounds on a bus, including detached ones.
+    ///
+    /// Sounds played on the bus while it is paused start paused.
+    pub fn pause_bus(&mut self, bus: &str) {
+        let bus = self.bus(bus);
+        bus.paused = true;
+        for handle in bus.playing.iter() {
+            handle.lock().unwrap().pause(Tween::default());
+        }
+    }
+
+    /// Resumes all sounds on a bus paused by [`Audio::pause_bus`].
+    pub fn resume_bus(&mut self, bus: &str) {
+        let bus = self.bus(bus);
+        bus.paused = false;
+        for handle in bus.playing.iter() {
+            handle.lock().unwrap().resume(Tween::default());
+        }
+    }
+
+    /// Checks if a bus was paused by [`Audio::pause_bus`].
+    pub fn is_bus_paused(&self, bus: &str) -> bool {
+        self.buses.get(bus).is_some_and(|bus| bus.paused)
+    }
+
+    /// Adds a ducking rule.
+    ///
+    /// If multiple rules target the same bus, the strongest one that applies wins.
+    pub fn add_ducking(&mut self, ducking: Ducking) {
+        self.bus(&ducking.target);
+        self.bus(&ducking.trigger);
+        self.duckings.push(ducking);
+    }
+
+    /// Plays a sound on the given bus, such that it is affected by the bus's volume and ducking.
+    ///
+    /// Buses are created on first use.
+    pub fn play_on(&mut self, sound: &Sound, bus: &str) -> PlaybackHandle {
+        let sound_data = Self::sound_data(sound).output_destination(&self.bus(bus).track);
+        let handle = self.play_sound_data(sound_data);
+        let bus = self.bus(bus);
+        let inner = handle.handle.clone().unwrap();
+        if bus.paused {
+            inner.lock().unwrap().pause(Tween::default());
+        }
+        bus.playing.push(inner);
+        handle
+    }
+
+    /// Plays synchronized music stems as layers, all starting on the same sample.
+    ///
+    /// Stems should be the same length, and usually share a loop region. All layers start at full volume, see [`MusicLayers::set_intensity`] to fade them by gameplay intensity.
+    pub fn play_layers(&mut self, sounds: &[&Sound]) -> MusicLayers {
+        // Schedule all stems on a clock tick shortly in the future, so they start together regardless of when the audio thread picks up each command.
+        let clock =
+            manager!(&mut self.audio_manager, m => m.add_clock(ClockSpeed::TicksPerSecond(100.0)))
+                .unwrap();
+        let start_time = clock.time() + 1;
+        let stems = sounds
+            .iter()
+            .map(|sound| {
+                manager!(&mut self.audio_manager, m => m.play(Self::sound_data(sound).start_time(start_time)))
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        clock.start();
+
+        let stems = Arc::new(Mutex::new(stems));
+        self.music_layers.push(Arc::downgrade(&stems));
+        MusicLayers {
+            stems,
+            _clock: clock,
+        }
     }
 
     /// Plays a sound.
     pub fn play(&mut self, sound: &Sound) -> PlaybackHandle {
+        self.play_sound_data(Self::sound_data(sound))
+    }
+
+    fn play_sound_data(&mut self, sound_data: StaticSoundData) -> PlaybackHandle {
+        PlaybackHandle {
+            handle: Some(Arc::new(Mutex::new(
+                manager!(&mut self.audio_manager, m => m.play(sound_data)).unwrap(),
+            ))),
+            waiters: self.waiters.clone(),
+        }
+    }
+
+    fn sound_data(sound: &Sound) -> StaticSoundData {
         let mut sound_data = sound
             .source
             .0
@@ -174,6 +624,6 @@ impl Audio {
             sound_data = sound_data.loop_region(Some((*loop_region).into_impl()));
         }
 
-        PlaybackHandle(Some(self.audio_manager.play(sound_data).unwrap()))
+        sound_data
     }
 }
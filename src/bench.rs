@@ -0,0 +1,194 @@
+//! Benchmarking the renderer.
+//!
+//! A [`Bench`] renders to an offscreen texture without a window, and measures how long the CPU side of each frame takes: building the canvas, preparing it and submitting it to the GPU. This is useful for catching performance regressions in the draw path, and for comparing machines.
+//!
+//! ```ignore
+//! let mut bench = futures::executor::block_on(Bench::new(UVec2::new(1280, 720)))?;
+//! println!("{:?}", bench.sprites(10_000, 100));
+//! ```
+
+use std::time::Duration;
+
+use crate::graphics::{font, Canvas, Color, Offscreen, OffscreenError, Texture, TextureOptions};
+use crate::image::Img;
+use crate::math::{self, UVec2, Vec2};
+use crate::time::Instant;
+
+/// Errors that can occur while setting up a benchmark.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    /// No graphics adapter is available.
+    #[error("no adapter available")]
+    NoAdapter,
+
+    /// An underlying error occurred.
+    #[error("other: {0}")]
+    Other(Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// Timings of a benchmark run.
+#[derive(Clone, Copy, Debug)]
+pub struct BenchReport {
+    /// Number of frames rendered.
+    pub frames: u32,
+
+    /// Total CPU time of all frames.
+    pub total: Duration,
+
+    /// Fastest frame.
+    pub min: Duration,
+
+    /// Slowest frame.
+    pub max: Duration,
+}
+
+impl BenchReport {
+    fn new() -> Self {
+        Self {
+            frames: 0,
+            total: Duration::ZERO,
+            min: Duration::ZERO,
+            max: Duration::ZERO,
+        }
+    }
+
+    fn record(&mut self, elapsed: Duration) {
+        self.min = if self.frames == 0 {
+            elapsed
+        } else {
+            self.min.min(elapsed)
+        };
+        self.max = self.max.max(elapsed);
+        self.total += elapsed;
+        self.frames += 1;
+    }
+
+    /// Gets the average CPU time of a frame.
+    pub fn average(&self) -> Duration {
+        self.total / self.frames.max(1)
+    }
+}
+
+impl From<OffscreenError> for Error {
+    fn from(e: OffscreenError) -> Self {
+        match e {
+            OffscreenError::NoAdapter => Error::NoAdapter,
+            OffscreenError::RequestDevice(e) => Error::Other(e.into()),
+        }
+    }
+}
+
+/// A headless renderer for benchmarking.
+pub struct Bench {
+    offscreen: Offscreen,
+    sprite: Texture,
+}
+
+impl Bench {
+    /// Creates a headless renderer that renders frames of the given size.
+    pub async fn new(size: UVec2) -> Result<Self, Error> {
+        let offscreen = Offscreen::new(size).await?;
+        let sprite = Texture::create(
+            &offscreen.device,
+            &offscreen.queue,
+            Img::new(
+                vec![Color::new(0xff, 0xff, 0xff, 0xff); 16 * 16],
+                UVec2::new(16, 16),
+                1,
+            ),
+            TextureOptions::default(),
+        );
+
+        Ok(Self { offscreen, sprite })
+    }
+
+    /// Renders the given number of frames, each drawn by the given function with a 16x16 white sprite.
+    pub fn run(
+        &mut self,
+        frames: u32,
+        mut draw: impl for<'a> FnMut(&mut Canvas<'a>, &'a Texture),
+    ) -> BenchReport {
+        let mut report = BenchReport::new();
+
+        for _ in 0..frames {
+            let start = Instant::now();
+            let mut canvas = Canvas::new();
+            draw(&mut canvas, &self.sprite);
+            self.offscreen.render(&canvas);
+            let elapsed = start.elapsed();
+
+            // Wait for the GPU outside of the measured time, such that frames don't pile up.
+            self.offscreen.device.poll(wgpu::Maintain::Wait);
+
+            report.record(elapsed);
+        }
+
+        report
+    }
+
+    /// Renders the given number of frames, each with the given number of rotated sprites spread across the frame.
+    pub fn sprites(&mut self, count: u32, frames: u32) -> BenchReport {
+        let size = Vec2::new(
+            self.offscreen.target.width() as f32,
+            self.offscreen.target.height() as f32,
+        );
+        self.run(frames, |canvas, sprite| {
+            let Some(slice) = sprite.layer(0) else {
+                return;
+            };
+            for i in 0..count {
+                let t = i as f32 / count.max(1) as f32;
+                canvas.draw(
+                    slice,
+                    math::Affine2::from_translation(Vec2::new(
+                        (t * 7919.0).fract() * size.x,
+                        (t * 104729.0).fract() * size.y,
+                    )) * math::Affine2::from_angle(t * std::f32::consts::TAU),
+                );
+            }
+        })
+    }
+
+    /// Adds a font for use with [`Bench::text`].
+    pub fn add_font(&mut self, font: &[u8]) -> Vec<font::Attrs> {
+        let attrs = self.offscreen.canvasette_renderer.add_font(font);
+        crate::graphics::register_font(font, &attrs);
+        attrs
+    }
+
+    /// Renders the given number of frames, each with the given number of text blocks, which are prepared every frame.
+    pub fn text(&mut self, attrs: &font::Attrs, count: u32, frames: u32) -> BenchReport {
+        let mut report = BenchReport::new();
+
+        for frame in 0..frames {
+            let start = Instant::now();
+            let prepared = (0..count)
+                .map(|i| {
+                    self.offscreen.canvasette_renderer.prepare_text(
+                        format!("text block {i} on frame {frame}"),
+                        font::Metrics::relative(16.0, 1.0),
+                        attrs.clone(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            let mut canvas = Canvas::new();
+            for (i, text) in prepared.into_iter().enumerate() {
+                canvas.draw(
+                    text,
+                    math::translate(
+                        0.0,
+                        (i as f32 * 16.0) % self.offscreen.target.height() as f32,
+                    ),
+                );
+            }
+            self.offscreen.render(&canvas);
+            let elapsed = start.elapsed();
+
+            self.offscreen.device.poll(wgpu::Maintain::Wait);
+
+            report.record(elapsed);
+        }
+
+        report
+    }
+}
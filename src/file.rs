@@ -20,15 +20,13 @@ pub enum Error {
 
 /// Reads a file from the given path and return its bytes.
 ///
-/// On WASM, this will perform a HTTP GET request.
+/// On WASM, this will perform a HTTP GET request. Reads count towards the [`crate::loading`] progress. On iOS, relative paths are resolved against the app bundle.
 pub async fn read(path: &str) -> Result<Vec<u8>, Error> {
     #[cfg(not(target_arch = "wasm32"))]
-    {
-        return native::read(path).await;
-    }
+    let fut = native::read(path);
 
     #[cfg(target_arch = "wasm32")]
-    {
-        return web::read(path).await;
-    }
+    let fut = web::read(path);
+
+    crate::loading::track(fut).await
 }
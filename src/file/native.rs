@@ -1,8 +1,29 @@
 use std::io::ErrorKind;
+use std::path::PathBuf;
 
 use super::Error;
 
+/// Resolves a path relative to where assets are shipped.
+///
+/// On iOS, the working directory is not the app bundle, so relative paths are resolved against the bundle's resource directory, which is where the executable lives.
+fn resolve(path: &str) -> PathBuf {
+    #[cfg(target_os = "ios")]
+    {
+        if std::path::Path::new(path).is_relative() {
+            if let Some(bundle_dir) = std::env::current_exe()
+                .ok()
+                .and_then(|exe| exe.parent().map(|dir| dir.to_path_buf()))
+            {
+                return bundle_dir.join(path);
+            }
+        }
+    }
+
+    PathBuf::from(path)
+}
+
 pub async fn read(path: &str) -> Result<Vec<u8>, Error> {
+    let path = resolve(path);
     Ok(async {
         #[cfg(feature = "tokio")]
         {
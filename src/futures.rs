@@ -1,6 +1,10 @@
 //! Utilities for dealing with futures.
 
 use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::channel::oneshot;
 
 use crate::marker::WasmNotSend;
 
@@ -33,3 +37,74 @@ pub fn spawn(fut: impl Future<Output = ()> + WasmNotSend + 'static) {
         panic!("no executor available to spawn futures on!");
     }
 }
+
+/// The task of a [`JoinHandle`] ended without producing a result, e.g. because it panicked.
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("task canceled")]
+pub struct Canceled;
+
+/// A handle to the result of a future spawned via [`spawn_with_handle`].
+///
+/// The result can be checked for every tick without blocking, e.g. for pathfinding jobs started in [`crate::Game::update`]:
+///
+/// ```ignore
+/// if self.path_job.is_none() {
+///     let grid = self.grid.clone();
+///     self.path_job = Some(futures::spawn_with_handle(async move { grid.find_path(start, goal) }));
+/// }
+/// if let Some(job) = &mut self.path_job {
+///     if let Some(path) = job.try_take().unwrap() {
+///         self.path = path;
+///         self.path_job = None;
+///     }
+/// }
+/// ```
+///
+/// The handle may also be awaited from another future. Dropping the handle doesn't cancel the task, its result is discarded instead.
+pub struct JoinHandle<T> {
+    receiver: Option<oneshot::Receiver<T>>,
+}
+
+impl<T> JoinHandle<T> {
+    /// Takes the result if the task has finished, without blocking.
+    ///
+    /// Returns `Ok(None)` while the task is still running, and also once the result has been taken.
+    pub fn try_take(&mut self) -> Result<Option<T>, Canceled> {
+        let Some(receiver) = &mut self.receiver else {
+            return Ok(None);
+        };
+        let result = receiver.try_recv().map_err(|_| Canceled)?;
+        if result.is_some() {
+            self.receiver = None;
+        }
+        Ok(result)
+    }
+}
+
+impl<T> Future for JoinHandle<T> {
+    type Output = Result<T, Canceled>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let Some(receiver) = &mut self.receiver else {
+            return Poll::Ready(Err(Canceled));
+        };
+        let result = std::task::ready!(Pin::new(receiver).poll(cx)).map_err(|_| Canceled);
+        self.receiver = None;
+        Poll::Ready(result)
+    }
+}
+
+/// Spawns a future like [`spawn`], returning a handle to its result.
+pub fn spawn_with_handle<T>(fut: impl Future<Output = T> + WasmNotSend + 'static) -> JoinHandle<T>
+where
+    T: WasmNotSend + 'static,
+{
+    let (sender, receiver) = oneshot::channel();
+    spawn(async move {
+        // The handle may have been dropped, in which case nobody is interested in the result.
+        _ = sender.send(fut.await);
+    });
+    JoinHandle {
+        receiver: Some(receiver),
+    }
+}
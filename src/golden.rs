@@ -0,0 +1,341 @@
+//! Visual regression tests against golden images.
+//!
+//! A [`Harness`] renders frames to an offscreen texture without a window and reads them back, such that they can be compared against reference PNGs checked into the repository:
+//!
+//! ```ignore
+//! #[test]
+//! fn title_screen() {
+//!     let mut harness = futures::executor::block_on(Harness::new(UVec2::new(320, 180))).unwrap();
+//!     let logo = harness.create_texture(&logo_img, TextureOptions::default());
+//!
+//!     let mut title = TitleScreen::new();
+//!     for _ in 0..60 {
+//!         title.tick();
+//!     }
+//!     let frame = harness.render(|canvas| title.draw(canvas, &logo));
+//!     golden::assert_matches(&frame, "tests/golden/title_screen.png", Tolerance::default());
+//! }
+//! ```
+//!
+//! Whole games may also be run headlessly for a number of ticks, drawing as if into a window the size of the harness:
+//!
+//! ```ignore
+//! let frame = harness.run(&mut MyGame::new(), 120).unwrap();
+//! golden::assert_matches(&frame, "tests/golden/after_two_seconds.png", Tolerance::default());
+//! ```
+//!
+//! To create golden images, or to accept intentional changes, run the tests with the `TEENYGAME_UPDATE_GOLDENS` environment variable set, which writes all compared golden images. Without it, comparing against a golden image that doesn't exist fails, such that a test whose golden image was never committed doesn't pass silently in CI.
+//!
+//! Rendering may differ slightly between GPUs and drivers, so images are compared perceptually, in Oklab space, with a small [`Tolerance`].
+
+use std::path::Path;
+
+use crate::graphics::color::ColorExt as _;
+use crate::graphics::{
+    Canvas, Color, Offscreen, OffscreenError, Texture, TextureOptions, TexturePixel, TextureSlice,
+};
+use crate::haptics::Haptics;
+use crate::image::{AsImgRef, Img};
+use crate::input::InputState;
+use crate::math::UVec2;
+use crate::replay::{self, Replay};
+use crate::time::{Stepping, Timing};
+use crate::{Context, EventSender, EventSenderInner, Game};
+
+/// Environment variable that makes comparisons overwrite golden images instead of checking them.
+pub const UPDATE_ENV: &str = "TEENYGAME_UPDATE_GOLDENS";
+
+/// Errors that can occur while rendering or comparing golden images.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    /// No graphics adapter is available.
+    #[error("no adapter available")]
+    NoAdapter,
+
+    /// Decoding or encoding a golden image failed.
+    #[error("image: {0}")]
+    Image(#[from] image::ImageError),
+
+    /// Reading or writing a golden image failed.
+    #[error("io: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The golden image doesn't exist, and [`UPDATE_ENV`] is not set to create it.
+    #[error("golden image {} doesn't exist, set {UPDATE_ENV} to create it", .0.display())]
+    MissingGolden(std::path::PathBuf),
+
+    /// An underlying error occurred.
+    #[error("other: {0}")]
+    Other(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl From<OffscreenError> for Error {
+    fn from(e: OffscreenError) -> Self {
+        match e {
+            OffscreenError::NoAdapter => Error::NoAdapter,
+            OffscreenError::RequestDevice(e) => Error::Other(e.into()),
+        }
+    }
+}
+
+/// A headless renderer whose frames can be read back.
+pub struct Harness {
+    offscreen: Offscreen,
+}
+
+impl Harness {
+    /// Creates a headless renderer that renders frames of the given size.
+    pub async fn new(size: UVec2) -> Result<Self, Error> {
+        Ok(Self {
+            offscreen: Offscreen::new(size).await?,
+        })
+    }
+
+    /// Creates a texture for drawing in [`Harness::render`].
+    pub fn create_texture<P>(&self, img: impl AsImgRef<P>, options: TextureOptions) -> Texture
+    where
+        P: TexturePixel,
+    {
+        Texture::create(&self.offscreen.device, &self.offscreen.queue, img, options)
+    }
+
+    /// Gets a single white pixel for drawing solid shapes in [`Harness::render`], see [`crate::graphics::CanvasExt::fill_rect`].
+    pub fn white_pixel(&self) -> TextureSlice<'_> {
+        self.offscreen.white_pixel.layer(0).unwrap()
+    }
+
+    /// Renders a frame drawn by the given function and reads it back.
+    pub fn render<'a>(&mut self, draw: impl FnOnce(&mut Canvas<'a>)) -> Img<Vec<Color>> {
+        let mut canvas = Canvas::new();
+        draw(&mut canvas);
+        self.offscreen.render(&canvas);
+        self.read_back()
+    }
+
+    /// Runs a game without a window and reads back the frame it draws.
+    ///
+    /// The game is resumed, updated the given number of ticks with no input and drawn once, as if in a window the size of the harness. Events sent via its [`crate::EventSender`] are delivered between ticks, and inputs are only recorded into [`crate::Context::replay`] if [`crate::Game::RECORD_REPLAY`] is enabled, like when running normally. The random seed is fixed, such that the frame is the same on every run.
+    pub fn run<G>(&mut self, game: &mut G, ticks: u32) -> Result<Img<Vec<Color>>, Error>
+    where
+        G: Game,
+    {
+        let mut input = InputState::new();
+        let mut timing = Timing::new();
+        let mut replay = Replay::new(0);
+        replay::set_seed(0);
+        let mut stepping = Stepping::new(G::TICKS_PER_SECOND);
+        let mut haptics = Haptics::new();
+        #[cfg(feature = "debug")]
+        let mut gizmos = crate::graphics::debug::Gizmos::new();
+        // CI machines often have no audio device, so nothing is played.
+        #[cfg(feature = "audio")]
+        let mut audio = crate::audio::Audio::headless();
+        #[cfg(feature = "physics")]
+        let mut physics = crate::physics::PhysicsWorld::new();
+        let (sender, events) = std::sync::mpsc::channel();
+        let event_sender = EventSender(EventSenderInner::Queue(sender));
+
+        macro_rules! context {
+            () => {
+                Context {
+                    input: &input,
+                    time: &timing,
+                    replay: &replay,
+                    event_sender: &event_sender,
+                    stepping: &mut stepping,
+                    haptics: &mut haptics,
+                    #[cfg(feature = "debug")]
+                    debug: &mut gizmos,
+                    #[cfg(feature = "audio")]
+                    audio: &mut audio,
+                    #[cfg(feature = "physics")]
+                    physics: &mut physics,
+                    gfx: &mut self.offscreen.graphics(),
+                }
+            };
+        }
+
+        game.resumed(&mut context!());
+        for _ in 0..ticks {
+            for event in events.try_iter() {
+                game.user_event(event);
+            }
+
+            if G::RECORD_REPLAY {
+                replay.record(input.snapshot());
+            }
+
+            #[cfg(feature = "debug")]
+            gizmos.clear();
+
+            #[cfg(feature = "audio")]
+            audio.update();
+
+            game.update(&mut context!());
+
+            let tick_interval = stepping.tick_interval();
+            #[cfg(feature = "physics")]
+            physics.step(tick_interval.as_secs_f32());
+            input.update();
+            timing.record_tick();
+            self.offscreen.frame_uniforms.advance(tick_interval);
+        }
+
+        // Gizmos are drawn with a clone of the pixel, such that the canvas doesn't borrow the renderer.
+        #[cfg(feature = "debug")]
+        let white_pixel = self.offscreen.white_pixel.clone();
+        let mut canvas = Canvas::new();
+        game.draw(&mut context!(), &mut canvas);
+        #[cfg(feature = "debug")]
+        gizmos.flush(
+            &mut self.offscreen.graphics(),
+            &mut canvas,
+            white_pixel.layer(0).unwrap(),
+        );
+        self.offscreen.render(&canvas);
+        Ok(self.read_back())
+    }
+
+    fn read_back(&self) -> Img<Vec<Color>> {
+        let frame = crate::graphics::read_back(
+            &self.offscreen.device,
+            &self.offscreen.queue,
+            &self.offscreen.target,
+        );
+        self.offscreen.device.poll(wgpu::Maintain::Wait);
+        futures::executor::block_on(frame).expect("failed to read back frame")
+    }
+}
+
+/// How much rendered images may differ from golden images.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Tolerance {
+    /// Largest distance between two pixels in Oklab space (plus alpha) at which they are considered the same.
+    ///
+    /// A distance of about 0.02 is just noticeable.
+    pub pixel: f32,
+
+    /// Fraction of pixels, from 0 to 1, that may differ by more than [`Tolerance::pixel`], e.g. for antialiased edges.
+    pub pixels: f32,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self {
+            pixel: 0.02,
+            pixels: 0.001,
+        }
+    }
+}
+
+/// The result of comparing two images.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Comparison {
+    /// Largest distance between two pixels, in Oklab space plus alpha.
+    pub max_difference: f32,
+
+    /// Number of pixels that differ by more than the tolerance.
+    pub differing_pixels: u32,
+
+    /// Whether the images are within the tolerance.
+    pub matches: bool,
+}
+
+/// Perceptual distance between two colors.
+fn difference(a: Color, b: Color) -> f32 {
+    let (a_lab, b_lab) = (a.to_oklab(), b.to_oklab());
+    let alpha = (a.a as f32 - b.a as f32) / 255.0;
+    ((a_lab.l - b_lab.l).powi(2)
+        + (a_lab.a - b_lab.a).powi(2)
+        + (a_lab.b - b_lab.b).powi(2)
+        + alpha.powi(2))
+    .sqrt()
+}
+
+/// Compares the first layers of two images. Images of different sizes never match.
+pub fn compare(
+    actual: impl AsImgRef<Color>,
+    expected: impl AsImgRef<Color>,
+    tolerance: Tolerance,
+) -> Comparison {
+    let (actual, expected) = (actual.as_ref(), expected.as_ref());
+    let size = actual.size();
+    if size != expected.size() {
+        return Comparison {
+            max_difference: f32::INFINITY,
+            differing_pixels: size.x * size.y,
+            matches: false,
+        };
+    }
+
+    let len = (size.x * size.y) as usize;
+    let mut max_difference = 0.0f32;
+    let mut differing_pixels = 0;
+    for (a, b) in actual.as_buf()[..len].iter().zip(&expected.as_buf()[..len]) {
+        let d = difference(*a, *b);
+        max_difference = max_difference.max(d);
+        if d > tolerance.pixel {
+            differing_pixels += 1;
+        }
+    }
+    Comparison {
+        max_difference,
+        differing_pixels,
+        matches: differing_pixels as f32 <= tolerance.pixels * len as f32,
+    }
+}
+
+/// Compares an image against a golden PNG.
+///
+/// If [`UPDATE_ENV`] is set, the image is written to the golden image instead and reported as matching. Otherwise, fails with [`Error::MissingGolden`] if the golden image doesn't exist.
+pub fn compare_golden(
+    actual: impl AsImgRef<Color>,
+    path: impl AsRef<Path>,
+    tolerance: Tolerance,
+) -> Result<Comparison, Error> {
+    let path = path.as_ref();
+    if std::env::var_os(UPDATE_ENV).is_some() {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, crate::image::encode_png(actual)?)?;
+        return Ok(Comparison {
+            max_difference: 0.0,
+            differing_pixels: 0,
+            matches: true,
+        });
+    }
+
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(Error::MissingGolden(path.to_path_buf()));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let expected = crate::image::load_from_memory(&bytes)?;
+    Ok(compare(actual, &expected, tolerance))
+}
+
+/// Asserts that an image matches a golden PNG, see [`compare_golden`].
+///
+/// On mismatch, the image is written next to the golden image with an `.actual.png` extension for inspection.
+#[track_caller]
+pub fn assert_matches(actual: impl AsImgRef<Color>, path: impl AsRef<Path>, tolerance: Tolerance) {
+    let path = path.as_ref();
+    let comparison = compare_golden(&actual, path, tolerance)
+        .unwrap_or_else(|e| panic!("failed to compare against {}: {e}", path.display()));
+    if comparison.matches {
+        return;
+    }
+
+    let actual_path = path.with_extension("actual.png");
+    _ = crate::image::encode_png(&actual).map(|bytes| std::fs::write(&actual_path, bytes));
+    panic!(
+        "{} does not match: {} pixels differ, by up to {:.3} (actual image written to {})",
+        path.display(),
+        comparison.differing_pixels,
+        comparison.max_difference,
+        actual_path.display(),
+    );
+}
@@ -1,9 +1,31 @@
 //! Graphics support.
 
+pub mod atlas;
+pub mod camera;
+pub mod color;
+#[cfg(feature = "debug")]
+pub mod debug;
+pub mod gradient;
+pub mod hit;
+pub mod lighting;
+pub mod minimap;
+pub mod nine_patch;
+pub mod node;
+pub mod outline;
+pub mod parallax;
+pub mod post;
+pub mod sorted;
+pub mod trail;
+pub mod transition;
+
 use crate::{image::AsImgRef, math};
 pub use canvasette::{font, Canvas, Drawable, PreparedText, TextureSlice};
+use color::ColorExt as _;
+use std::sync::Arc;
 use wgpu::util::DeviceExt as _;
+pub use wgpu::{Backend, Backends, DeviceType, Limits, PowerPreference};
 use winit::dpi::PhysicalSize;
+use winit::window::Fullscreen;
 
 /// An 8-bit RGBA color.
 pub type Color = rgb::Rgba<u8>;
@@ -11,44 +33,213 @@ pub type Color = rgb::Rgba<u8>;
 /// A texture that can be rendered to.
 ///
 /// Framebuffers may be created via [`Graphics::create_framebuffer`].
-pub struct Framebuffer(wgpu::Texture);
+pub struct Framebuffer {
+    texture: wgpu::Texture,
+
+    /// Created once up front, such that rendering to the framebuffer doesn't create a view every time.
+    view: wgpu::TextureView,
+}
+
+impl Drop for Framebuffer {
+    fn drop(&mut self) {
+        crate::stats::TEXTURES.sub(texture_bytes(&self.texture));
+    }
+}
 
 impl Framebuffer {
     /// Gets the underlying texture as a [`TextureSlice`], which may be used for sprite drawing.
     pub fn as_texture_slice(&self) -> TextureSlice {
-        TextureSlice::new(&self.0, 0)
+        TextureSlice::new(&self.texture, 0)
+    }
+}
+
+/// Estimates the memory used by a texture.
+fn texture_bytes(texture: &wgpu::Texture) -> u64 {
+    let size = texture.size();
+    size.width as u64
+        * size.height as u64
+        * size.depth_or_array_layers as u64
+        * texture.format().block_copy_size(None).unwrap_or(4) as u64
+}
+
+/// Vertical metrics of a font at a given size, in pixels.
+///
+/// These are useful for positioning text precisely, e.g. to vertically center a line of text in a box:
+///
+/// ```ignore
+/// let metrics = ctxt.gfx.font_metrics(&face, 64.0).unwrap();
+/// let baseline = (box_height + metrics.cap_height) / 2.0;
+/// ```
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct FontMetrics {
+    /// Distance from the baseline to the top of the tallest glyphs.
+    pub ascent: f32,
+
+    /// Distance from the baseline to the bottom of the lowest glyphs, as a positive number.
+    pub descent: f32,
+
+    /// Recommended extra space between lines.
+    pub line_gap: f32,
+
+    /// Height of capital letters above the baseline.
+    ///
+    /// If the font doesn't specify this, it is approximated as 70% of the font size.
+    pub cap_height: f32,
+}
+
+impl FontMetrics {
+    /// Gets the recommended distance between the baselines of consecutive lines.
+    pub fn line_height(&self) -> f32 {
+        self.ascent + self.descent + self.line_gap
+    }
+
+    fn scaled(&self, size: f32) -> Self {
+        Self {
+            ascent: self.ascent * size,
+            descent: self.descent * size,
+            line_gap: self.line_gap * size,
+            cap_height: self.cap_height * size,
+        }
     }
 }
 
-#[derive(PartialEq, Eq)]
-struct DeviceId(*const wgpu::Device);
+/// The slant of a font face.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum FontStyle {
+    /// Upright.
+    #[default]
+    Normal,
+
+    /// Cursive slanted glyphs.
+    Italic,
+
+    /// Mechanically slanted glyphs.
+    Oblique,
+}
+
+/// Describes a face loaded from a font file.
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub struct FontFace {
+    /// The family name, e.g. `Noto Sans`.
+    pub family: String,
+
+    /// The weight, from 100 (thin) to 900 (black), where 400 is regular.
+    pub weight: u16,
+
+    /// The slant.
+    pub style: FontStyle,
+}
+
+struct FontInfo {
+    face: FontFace,
+
+    /// Metrics in ems.
+    metrics: FontMetrics,
+}
+
+/// Information about added fonts.
+///
+/// This is global rather than per renderer, as it only depends on the font data.
+static FONTS: std::sync::Mutex<Vec<(font::Attrs, FontInfo)>> = std::sync::Mutex::new(Vec::new());
+
+fn face_family(face: &ttf_parser::Face) -> String {
+    [
+        ttf_parser::name_id::TYPOGRAPHIC_FAMILY,
+        ttf_parser::name_id::FAMILY,
+    ]
+    .into_iter()
+    .find_map(|name_id| {
+        face.names()
+            .into_iter()
+            .filter(|name| name.name_id == name_id && name.is_unicode())
+            .find_map(|name| name.to_string())
+    })
+    .unwrap_or_default()
+}
+
+/// Records information about each face in a font, given the attributes that the renderer assigned to them in order.
+pub(crate) fn register_font(font: &[u8], attrs: &[font::Attrs]) {
+    let mut fonts = FONTS.lock().unwrap();
+    for (index, attrs) in attrs.iter().enumerate() {
+        let Ok(face) = ttf_parser::Face::parse(font, index as u32) else {
+            continue;
+        };
+        let units_per_em = face.units_per_em() as f32;
+        let info = FontInfo {
+            face: FontFace {
+                family: face_family(&face),
+                weight: face.weight().to_number(),
+                style: match face.style() {
+                    ttf_parser::Style::Normal => FontStyle::Normal,
+                    ttf_parser::Style::Italic => FontStyle::Italic,
+                    ttf_parser::Style::Oblique => FontStyle::Oblique,
+                },
+            },
+            metrics: FontMetrics {
+                ascent: face.ascender() as f32 / units_per_em,
+                descent: -(face.descender() as f32) / units_per_em,
+                line_gap: face.line_gap() as f32 / units_per_em,
+                cap_height: face
+                    .capital_height()
+                    .map_or(0.7, |h| h as f32 / units_per_em),
+            },
+        };
+        fonts.retain(|(a, _)| a != attrs);
+        fonts.push((attrs.clone(), info));
+    }
+}
+
+/// Identifies a graphics device by its address, such that resources can tell if the device they were loaded on was replaced.
+///
+/// wgpu 23 neither has `Device::global_id` nor implements [`Eq`] for devices, so the address is the only identity available.
+///
+/// This is stored as an integer rather than a pointer, such that resources remember it while staying [`Send`] and [`Sync`].
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) struct DeviceId(usize);
+
+impl DeviceId {
+    pub(crate) fn of(device: &wgpu::Device) -> Self {
+        Self(device as *const wgpu::Device as usize)
+    }
+}
 
 pub struct Graphics<'a> {
     pub(crate) canvasette_renderer: &'a mut canvasette::Renderer,
-    pub(crate) wgpu: &'a wginit::Wgpu,
-    pub(crate) window: &'a winit::window::Window,
+    pub(crate) adapter: &'a wgpu::Adapter,
+
+    /// Device to render with, which replaces the window's if that was lost.
+    pub(crate) device: &'a wgpu::Device,
+    pub(crate) queue: &'a wgpu::Queue,
+
+    /// Format frames are rendered in, which framebuffers are created with too.
+    pub(crate) format: wgpu::TextureFormat,
+    pub(crate) window: Window<'a>,
+    pub(crate) frame_uniforms: &'a mut post::FrameUniforms,
+    pub(crate) white_pixel: &'a Texture,
+    #[cfg(feature = "image")]
+    pub(crate) screenshots: &'a mut Vec<crate::screenshot::Action>,
 }
 
 pub(crate) fn render_to_texture(
-    wgpu: &wginit::Wgpu,
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
     canvasette_renderer: &mut canvasette::Renderer,
     canvas: &Canvas,
-    texture: &wgpu::Texture,
+    size: wgpu::Extent3d,
+    view: &wgpu::TextureView,
 ) {
     canvasette_renderer
-        .prepare(&wgpu.device, &wgpu.queue, texture.size(), canvas)
+        .prepare(device, queue, size, canvas)
         .unwrap();
 
-    let mut encoder = wgpu
-        .device
-        .create_command_encoder(&wgpu::CommandEncoderDescriptor {
-            label: Some("teenygame: encoder"),
-        });
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("teenygame: encoder"),
+    });
 
     {
         let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
             color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                view: &texture.create_view(&wgpu::TextureViewDescriptor::default()),
+                view,
                 resolve_target: None,
                 ops: wgpu::Operations {
                     load: wgpu::LoadOp::Clear(wgpu::Color {
@@ -65,17 +256,228 @@ pub(crate) fn render_to_texture(
         canvasette_renderer.render(&mut rpass);
     }
 
-    wgpu.queue.submit(Some(encoder.finish()));
+    queue.submit(Some(encoder.finish()));
+}
+
+/// Errors that can occur while creating an [`Offscreen`] renderer.
+pub(crate) enum OffscreenError {
+    NoAdapter,
+    RequestDevice(wgpu::RequestDeviceError),
+}
+
+/// A renderer drawing to a texture instead of a window, e.g. for tests and benchmarks.
+pub(crate) struct Offscreen {
+    pub(crate) adapter: wgpu::Adapter,
+    pub(crate) device: wgpu::Device,
+    pub(crate) queue: wgpu::Queue,
+    pub(crate) canvasette_renderer: canvasette::Renderer,
+    pub(crate) target: wgpu::Texture,
+    view: wgpu::TextureView,
+    pub(crate) white_pixel: Texture,
+    pub(crate) frame_uniforms: post::FrameUniforms,
+
+    /// Screenshots requested by games, which are discarded since there is no frame to present.
+    #[cfg(feature = "image")]
+    screenshots: Vec<crate::screenshot::Action>,
+}
+
+impl Offscreen {
+    /// Creates a renderer on the default adapter, rendering frames of the given size.
+    pub(crate) async fn new(size: math::UVec2) -> Result<Self, OffscreenError> {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions::default())
+            .await
+            .ok_or(OffscreenError::NoAdapter)?;
+        let (device, queue) = adapter
+            .request_device(&wgpu::DeviceDescriptor::default(), None)
+            .await
+            .map_err(OffscreenError::RequestDevice)?;
+
+        let format = wgpu::TextureFormat::Rgba8UnormSrgb;
+        let target = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("teenygame: Offscreen"),
+            size: wgpu::Extent3d {
+                width: size.x,
+                height: size.y,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+        let canvasette_renderer = canvasette::Renderer::new(&device, format);
+        let white_pixel = Texture::create(
+            &device,
+            &queue,
+            white_pixel_img(),
+            TextureOptions::default(),
+        );
+
+        Ok(Self {
+            adapter,
+            device,
+            queue,
+            canvasette_renderer,
+            target,
+            view,
+            white_pixel,
+            frame_uniforms: Default::default(),
+            #[cfg(feature = "image")]
+            screenshots: vec![],
+        })
+    }
+
+    /// Renders a canvas to the target texture.
+    pub(crate) fn render(&mut self, canvas: &Canvas) {
+        render_to_texture(
+            &self.device,
+            &self.queue,
+            &mut self.canvasette_renderer,
+            canvas,
+            self.target.size(),
+            &self.view,
+        );
+        #[cfg(feature = "image")]
+        self.screenshots.clear();
+    }
+
+    /// Gets graphics that render like a window the size of the target texture.
+    pub(crate) fn graphics(&mut self) -> Graphics<'_> {
+        let size = self.target.size();
+        Graphics {
+            canvasette_renderer: &mut self.canvasette_renderer,
+            adapter: &self.adapter,
+            device: &self.device,
+            queue: &self.queue,
+            format: self.target.format(),
+            window: Window::offscreen(math::UVec2::new(size.width, size.height)),
+            frame_uniforms: &mut self.frame_uniforms,
+            white_pixel: &self.white_pixel,
+            #[cfg(feature = "image")]
+            screenshots: &mut self.screenshots,
+        }
+    }
+}
+
+/// Copies an RGBA or BGRA texture into a buffer and reads it back as RGBA.
+///
+/// The returned future only completes once the device is polled, which happens on its own in browsers.
+#[cfg(feature = "image")]
+pub(crate) fn read_back(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    texture: &wgpu::Texture,
+) -> impl std::future::Future<Output = Result<crate::image::Img<Vec<Color>>, wgpu::BufferAsyncError>>
+{
+    let size = texture.size();
+    let row_bytes = size.width * 4;
+    let padded_row_bytes =
+        row_bytes.div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT) * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("teenygame: readback"),
+        size: (padded_row_bytes * size.height) as u64,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("teenygame: encoder"),
+    });
+    encoder.copy_texture_to_buffer(
+        texture.as_image_copy(),
+        wgpu::ImageCopyBuffer {
+            buffer: &buffer,
+            layout: wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(padded_row_bytes),
+                rows_per_image: None,
+            },
+        },
+        size,
+    );
+    queue.submit([encoder.finish()]);
+
+    let (sender, receiver) = futures::channel::oneshot::channel();
+    buffer
+        .slice(..)
+        .map_async(wgpu::MapMode::Read, move |result| {
+            _ = sender.send(result);
+        });
+
+    let bgra = matches!(
+        texture.format(),
+        wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb
+    );
+    async move {
+        // The sender is only dropped without sending if the device is lost.
+        receiver.await.unwrap_or(Err(wgpu::BufferAsyncError))?;
+        let data = buffer.slice(..).get_mapped_range();
+        let pixels = data
+            .chunks(padded_row_bytes as usize)
+            .flat_map(|row| bytemuck::cast_slice::<u8, Color>(&row[..row_bytes as usize]))
+            .map(|c| {
+                if bgra {
+                    Color::new(c.b, c.g, c.r, c.a)
+                } else {
+                    *c
+                }
+            })
+            .collect();
+        Ok(crate::image::Img::new(
+            pixels,
+            crate::math::UVec2::new(size.width, size.height),
+            1,
+        ))
+    }
 }
 
 impl<'a> Graphics<'a> {
     fn device_id(&self) -> DeviceId {
-        DeviceId(&self.wgpu.device as *const _)
+        DeviceId::of(self.device)
+    }
+
+    /// Gets a texture slice containing a single white pixel, which may be tinted and scaled to draw solid shapes.
+    ///
+    /// The pixel lives as long as the graphics device, so it may be drawn onto canvases rendered during the current call, e.g. via [`Graphics::render_to_framebuffer`]. The canvas passed to [`crate::Game::draw`] may outlive it, so games should load their own via [`Lazy::white_pixel`] for that.
+    pub fn white_pixel(&self) -> TextureSlice<'a> {
+        self.white_pixel.layer(0).unwrap()
     }
 
     /// Adds a font.
     pub fn add_font(&mut self, font: &[u8]) -> Vec<font::Attrs> {
-        self.canvasette_renderer.add_font(font)
+        let attrs = self.canvasette_renderer.add_font(font);
+        register_font(font, &attrs);
+        attrs
+    }
+
+    /// Gets a description of a face added via [`Graphics::add_font`].
+    ///
+    /// This may be used to tell which of the attributes returned by [`Graphics::add_font`] is e.g. the bold face.
+    ///
+    /// Returns `None` if the font was not added.
+    pub fn font_face(&self, attrs: &font::Attrs) -> Option<FontFace> {
+        FONTS
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(a, _)| a == attrs)
+            .map(|(_, info)| info.face.clone())
+    }
+
+    /// Gets the vertical metrics of a font added via [`Graphics::add_font`], scaled to the given font size in pixels.
+    ///
+    /// Returns `None` if the font was not added.
+    pub fn font_metrics(&self, attrs: &font::Attrs, size: f32) -> Option<FontMetrics> {
+        FONTS
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(a, _)| a == attrs)
+            .map(|(_, info)| info.metrics.scaled(size))
     }
 
     /// Prepares text for rendering.
@@ -89,115 +491,622 @@ impl<'a> Graphics<'a> {
             .prepare_text(contents, metrics, attrs)
     }
 
+    /// Gets the graphics backend in use.
+    ///
+    /// On WASM, this is [`Backend::BrowserWebGpu`] if WebGPU is available, and [`Backend::Gl`] (WebGL2) otherwise.
+    pub fn backend(&self) -> Backend {
+        self.adapter.get_info().backend
+    }
+
+    /// Gets information about the graphics adapter in use, e.g. for diagnostics screens and bug reports.
+    pub fn adapter_info(&self) -> AdapterInfo {
+        let info = self.adapter.get_info();
+        AdapterInfo {
+            name: info.name,
+            backend: info.backend,
+            device_type: info.device_type,
+            driver: info.driver,
+            driver_info: info.driver_info,
+            limits: self.device.limits(),
+        }
+    }
+
+    /// Gets the maximum width and height of textures and framebuffers supported by the device.
+    ///
+    /// This may be as low as 2048 when falling back to WebGL2.
+    pub fn max_texture_size(&self) -> u32 {
+        self.device.limits().max_texture_dimension_2d
+    }
+
     /// Retrieve the underlying window.
-    pub fn window(&self) -> Window {
-        Window(&self.window)
+    pub fn window(&self) -> Window<'a> {
+        self.window
     }
 
     /// Creates an empty framebuffer texture.
     pub fn create_framebuffer(&self, size: math::UVec2) -> Framebuffer {
-        Framebuffer(
-            self.wgpu.device.create_texture(&wgpu::TextureDescriptor {
-                label: Some("teenygame: Framebuffer"),
-                size: wgpu::Extent3d {
-                    width: size.x,
-                    height: size.y,
-                    depth_or_array_layers: 1,
-                },
-                mip_level_count: 1,
-                sample_count: 1,
-                dimension: wgpu::TextureDimension::D2,
-                format: self
-                    .wgpu
-                    .surface
-                    .get_capabilities(&self.wgpu.adapter)
-                    .formats[0],
-                usage: wgpu::TextureUsages::RENDER_ATTACHMENT
-                    | wgpu::TextureUsages::TEXTURE_BINDING
-                    | wgpu::TextureUsages::COPY_SRC,
-                view_formats: &[],
-            }),
-        )
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("teenygame: Framebuffer"),
+            size: wgpu::Extent3d {
+                width: size.x,
+                height: size.y,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: self.format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        crate::stats::TEXTURES.add(texture_bytes(&texture));
+        Framebuffer { texture, view }
     }
 
     /// Loads a texture.
-    pub fn load_texture(&self, img: impl AsImgRef<Color>) -> Texture {
-        let img = img.as_ref();
-        let size = img.size();
+    ///
+    /// See [`TexturePixel`] for the supported pixel formats.
+    pub fn load_texture<P>(&self, img: impl AsImgRef<P>) -> Texture
+    where
+        P: TexturePixel,
+    {
+        self.load_texture_with_options(img, TextureOptions::default())
+    }
 
-        Texture(self.wgpu.device.create_texture_with_data(
-            &self.wgpu.queue,
-            &wgpu::TextureDescriptor {
-                label: Some("teenygame: Texture"),
-                size: wgpu::Extent3d {
-                    width: size.x,
-                    height: size.y,
-                    depth_or_array_layers: 1,
-                },
-                mip_level_count: 1,
-                sample_count: 1,
-                dimension: wgpu::TextureDimension::D2,
-                format: wgpu::TextureFormat::Rgba8UnormSrgb,
-                usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_SRC,
-                view_formats: &[],
-            },
-            wgpu::util::TextureDataOrder::default(),
-            &bytemuck::cast_slice(img.as_buf()),
-        ))
+    /// Loads a texture with the given options.
+    pub fn load_texture_with_options<P>(
+        &self,
+        img: impl AsImgRef<P>,
+        options: TextureOptions,
+    ) -> Texture
+    where
+        P: TexturePixel,
+    {
+        Texture::create(&self.device, &self.queue, img, options)
     }
 
     /// Renders to a framebuffer.
     pub fn render_to_framebuffer(&mut self, canvas: &Canvas, framebuffer: &Framebuffer) {
         render_to_texture(
-            &self.wgpu,
+            &self.device,
+            &self.queue,
             &mut self.canvasette_renderer,
             canvas,
-            &framebuffer.0,
+            framebuffer.texture.size(),
+            &framebuffer.view,
         );
     }
+
+    /// Gets the values available to all post effects this frame, e.g. to pass the camera to custom effects.
+    pub fn frame_uniforms(&mut self) -> &mut post::FrameUniforms {
+        self.frame_uniforms
+    }
+
+    /// Copies the current frame to the clipboard once it has been drawn. See [`crate::screenshot`].
+    #[cfg(feature = "image")]
+    pub fn capture_to_clipboard(&mut self) {
+        self.screenshots.push(crate::screenshot::Action::Clipboard);
+    }
+
+    /// Shares the current frame with the given title once it has been drawn, e.g. via the browser's share sheet. See [`crate::screenshot`].
+    #[cfg(feature = "image")]
+    pub fn capture_and_share(&mut self, title: &str) {
+        self.screenshots.push(crate::screenshot::Action::Share {
+            title: title.to_string(),
+        });
+    }
+}
+
+/// Information about a graphics adapter.
+#[derive(Clone, Debug)]
+pub struct AdapterInfo {
+    /// Name of the adapter, e.g. the GPU's model.
+    pub name: String,
+
+    /// Backend the adapter is used through.
+    pub backend: Backend,
+
+    /// Whether the adapter is integrated, discrete, virtual or a software renderer.
+    pub device_type: DeviceType,
+
+    /// Name of the driver, if known.
+    pub driver: String,
+
+    /// Version or other information about the driver, if known.
+    pub driver_info: String,
+
+    /// Limits of the device created on the adapter.
+    pub limits: Limits,
+}
+
+/// Requests the backends and power preference used when picking an adapter.
+///
+/// This is only a hint: wginit has no settings for picking the adapter, so they are passed on via the `WGPU_BACKEND` and `WGPU_POWER_PREF` environment variables. wgpu itself doesn't read these when creating the instance or requesting an adapter; they only take effect if wginit reads them via [`wgpu::util::backend_bits_from_env`] and [`wgpu::util::power_preference_from_env`]. Each variable set is logged, and [`check_adapter`] warns if the backend of the adapter that was picked doesn't match.
+///
+/// As changing the environment isn't thread-safe, this must be called before any other threads are started, as [`crate::run`] does before starting the event loop. The `backend` and `power` launch options and variables already set by the user take precedence. This has no effect on WASM, where the browser picks the adapter.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn request_adapter(backends: Backends, power_preference: PowerPreference) {
+    let launch_options = crate::launch::launch_options();
+
+    if std::env::var_os("WGPU_BACKEND").is_none() {
+        if let Some(backend) = launch_options.get("backend") {
+            log::info!("requesting {backend} backend via WGPU_BACKEND");
+            std::env::set_var("WGPU_BACKEND", backend);
+        } else if backends != Backends::all() {
+            let names = [
+                (Backends::VULKAN, "vulkan"),
+                (Backends::METAL, "metal"),
+                (Backends::DX12, "dx12"),
+                (Backends::GL, "gl"),
+                (Backends::BROWSER_WEBGPU, "webgpu"),
+            ]
+            .into_iter()
+            .filter(|(backend, _)| backends.contains(*backend))
+            .map(|(_, name)| name)
+            .collect::<Vec<_>>();
+            let names = names.join(",");
+            log::info!("requesting {names} backends via WGPU_BACKEND");
+            std::env::set_var("WGPU_BACKEND", names);
+        }
+    }
+
+    if std::env::var_os("WGPU_POWER_PREF").is_none() {
+        let power = match power_preference {
+            PowerPreference::None => None,
+            PowerPreference::LowPower => Some("low"),
+            PowerPreference::HighPerformance => Some("high"),
+        };
+        if let Some(power) = launch_options.get("power").or(power) {
+            log::info!("requesting {power} power preference via WGPU_POWER_PREF");
+            std::env::set_var("WGPU_POWER_PREF", power);
+        }
+    }
+}
+
+/// Checks that the adapter wginit picked matches what was requested via [`request_adapter`], warning otherwise.
+///
+/// The power preference is only a hint, so it can't be checked: e.g. a high-performance adapter may be integrated if there is no discrete one.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn check_adapter(adapter: &wgpu::Adapter) {
+    let info = adapter.get_info();
+    if let Some(backends) = wgpu::util::backend_bits_from_env() {
+        if !backends.contains(info.backend.into()) {
+            log::warn!(
+                "requested {backends:?} backends via WGPU_BACKEND, but got a {:?} adapter ({})",
+                info.backend,
+                info.name
+            );
+        }
+    }
 }
 
 /// Window.
-pub struct Window<'a>(&'a winit::window::Window);
+///
+/// When rendering offscreen, e.g. when running a game headlessly in tests, this stands in for a window of a fixed size with a scale factor of 1. Requests to change it are ignored there.
+#[derive(Clone, Copy)]
+pub struct Window<'a>(WindowInner<'a>);
+
+#[derive(Clone, Copy)]
+enum WindowInner<'a> {
+    Winit(&'a winit::window::Window),
+    Offscreen(math::UVec2),
+}
 
 impl<'a> Window<'a> {
+    pub(crate) fn new(window: &'a winit::window::Window) -> Self {
+        Self(WindowInner::Winit(window))
+    }
+
+    pub(crate) fn offscreen(size: math::UVec2) -> Self {
+        Self(WindowInner::Offscreen(size))
+    }
+
+    fn winit(&self) -> Option<&'a winit::window::Window> {
+        match self.0 {
+            WindowInner::Winit(window) => Some(window),
+            WindowInner::Offscreen(_) => None,
+        }
+    }
+
     /// Sets the title of the window.
     pub fn set_title(&self, title: &str) {
-        self.0.set_title(title);
+        if let Some(window) = self.winit() {
+            window.set_title(title);
+        }
     }
 
     /// Requests the size of the window to be a given size.
     pub fn set_size(&self, size: math::UVec2, resizable: bool) {
-        self.0.set_resizable(resizable);
-        let _ = self.0.request_inner_size(PhysicalSize::new(size.x, size.y));
+        if let Some(window) = self.winit() {
+            window.set_resizable(resizable);
+            let _ = window.request_inner_size(PhysicalSize::new(size.x, size.y));
+        }
     }
 
     /// Gets the current size of the window.
     pub fn size(&self) -> math::UVec2 {
-        let size = self.0.inner_size();
-        math::UVec2::new(size.width, size.height)
+        match self.0 {
+            WindowInner::Winit(window) => {
+                let size = window.inner_size();
+                math::UVec2::new(size.width, size.height)
+            }
+            WindowInner::Offscreen(size) => size,
+        }
     }
 
     /// Gets the scale factor of the window.
+    pub fn scale_factor(&self) -> f64 {
+        self.winit().map_or(1.0, |window| window.scale_factor())
+    }
+
+    /// Gets the area of the window not obscured by e.g. notches, rounded corners or browser UI, in physical pixels.
+    ///
+    /// HUD elements should be placed within this area. On WASM, this requires the page to set `viewport-fit=cover` in its viewport meta tag. On other platforms, this is currently the whole window.
+    pub fn safe_area(&self) -> math::URect {
+        #[cfg(target_arch = "wasm32")]
+        if let Some(safe_area) = self.winit().and_then(crate::web::safe_area) {
+            return safe_area;
+        }
+
+        math::URect::from_pos_size(math::UVec2::ZERO, self.size())
+    }
+
+    /// Requests the window to become fullscreen.
+    ///
+    /// On WASM, this uses the browser's Fullscreen API, which only honors requests made in response to user input (e.g. from [`crate::Game::update`] on the tick a button is clicked). [`crate::Game::fullscreen_changed`] is called once the request is granted.
+    pub fn request_fullscreen(&self) {
+        if let Some(window) = self.winit() {
+            window.set_fullscreen(Some(Fullscreen::Borderless(None)));
+        }
+    }
+
+    /// Requests the window to leave fullscreen.
+    pub fn exit_fullscreen(&self) {
+        if let Some(window) = self.winit() {
+            window.set_fullscreen(None);
+        }
+    }
+
+    /// Requests the window to become fullscreen on the given monitor.
+    pub fn request_fullscreen_on(&self, monitor: &Monitor) {
+        if let Some(window) = self.winit() {
+            window.set_fullscreen(Some(Fullscreen::Borderless(Some(monitor.0.clone()))));
+        }
+    }
+
+    /// Checks if the window is fullscreen.
+    pub fn is_fullscreen(&self) -> bool {
+        self.winit()
+            .is_some_and(|window| window.fullscreen().is_some())
+    }
+
+    /// Gets the system's color theme, if the platform reports one.
+    pub fn theme(&self) -> Option<crate::system::Theme> {
+        self.winit().and_then(|window| window.theme())
+    }
+
+    /// Gets the monitor the window is currently on, if known.
+    pub fn current_monitor(&self) -> Option<Monitor> {
+        self.winit()
+            .and_then(|window| window.current_monitor())
+            .map(Monitor)
+    }
+
+    /// Gets the primary monitor, if known.
+    pub fn primary_monitor(&self) -> Option<Monitor> {
+        self.winit()
+            .and_then(|window| window.primary_monitor())
+            .map(Monitor)
+    }
+
+    /// Gets all available monitors.
+    pub fn monitors(&self) -> Vec<Monitor> {
+        self.winit().map_or(vec![], |window| {
+            window.available_monitors().map(Monitor).collect()
+        })
+    }
+}
+
+/// A monitor.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Monitor(winit::monitor::MonitorHandle);
+
+impl Monitor {
+    /// Gets the name of the monitor, if known.
+    pub fn name(&self) -> Option<String> {
+        self.0.name()
+    }
+
+    /// Gets the size of the monitor, in physical pixels.
+    pub fn size(&self) -> math::UVec2 {
+        let size = self.0.size();
+        math::UVec2::new(size.width, size.height)
+    }
+
+    /// Gets the position of the monitor's top-left corner on the desktop, in physical pixels.
+    pub fn position(&self) -> math::IVec2 {
+        let position = self.0.position();
+        math::IVec2::new(position.x, position.y)
+    }
+
+    /// Gets the scale factor of the monitor.
     pub fn scale_factor(&self) -> f64 {
         self.0.scale_factor()
     }
+
+    /// Gets the refresh rate of the monitor in Hz, if known.
+    pub fn refresh_rate(&self) -> Option<f32> {
+        self.0
+            .refresh_rate_millihertz()
+            .map(|millihertz| millihertz as f32 / 1000.0)
+    }
+}
+
+/// Extra methods for [`Canvas`].
+pub trait CanvasExt<'a> {
+    /// Creates a sub-canvas whose draws are transformed by the given transform.
+    fn child(&mut self, transform: math::Affine2) -> node::ChildCanvas<'_, 'a>;
+
+    /// Fills a rectangle with a solid color, by stretching a single white pixel such as [`Graphics::white_pixel`].
+    fn fill_rect(&mut self, pixel: TextureSlice<'a>, rect: math::Rect, color: Color);
+
+    /// Draws a texture stretched to fill a rectangle, e.g. for gradients.
+    fn draw_stretched(&mut self, texture: TextureSlice<'a>, rect: math::Rect);
+
+    /// Fills a rectangle by repeating a texture, e.g. for scrolling backgrounds.
+    ///
+    /// The texture is drawn scaled by `scale` and shifted by `offset`, which wraps around such that it may grow indefinitely. Tiles are not cut off at the edges of the rectangle, so they may extend past it by up to one tile.
+    fn draw_tiled(
+        &mut self,
+        texture: TextureSlice<'a>,
+        rect: math::Rect,
+        offset: math::Vec2,
+        scale: math::Vec2,
+    );
+}
+
+impl<'a> CanvasExt<'a> for Canvas<'a> {
+    fn child(&mut self, transform: math::Affine2) -> node::ChildCanvas<'_, 'a> {
+        node::ChildCanvas {
+            canvas: self,
+            transform,
+        }
+    }
+
+    fn fill_rect(&mut self, pixel: TextureSlice<'a>, rect: math::Rect, color: Color) {
+        self.draw(
+            solid(pixel, color),
+            math::Affine2::from_translation(rect.min) * math::Affine2::from_scale(rect.size()),
+        );
+    }
+
+    fn draw_stretched(&mut self, texture: TextureSlice<'a>, rect: math::Rect) {
+        let size = texture.size().as_vec2();
+        if size.x <= 0.0 || size.y <= 0.0 {
+            return;
+        }
+        self.draw(
+            texture,
+            math::Affine2::from_translation(rect.min)
+                * math::Affine2::from_scale(rect.size() / size),
+        );
+    }
+
+    fn draw_tiled(
+        &mut self,
+        texture: TextureSlice<'a>,
+        rect: math::Rect,
+        offset: math::Vec2,
+        scale: math::Vec2,
+    ) {
+        let tile = texture.size().as_vec2() * scale;
+        if tile.x <= 0.0 || tile.y <= 0.0 {
+            return;
+        }
+        let start = rect.min - (tile - offset.rem_euclid(tile)) % tile;
+        let count = ((rect.max - start) / tile).ceil().as_uvec2();
+        for y in 0..count.y {
+            for x in 0..count.x {
+                self.draw(
+                    texture,
+                    math::Affine2::from_translation(
+                        start + math::Vec2::new(x as f32, y as f32) * tile,
+                    ) * math::Affine2::from_scale(scale),
+                );
+            }
+        }
+    }
+}
+
+/// Creates an image of a single white pixel.
+pub(crate) fn white_pixel_img() -> crate::image::Img<Vec<Color>> {
+    crate::image::Img::new(
+        vec![Color::new(0xff, 0xff, 0xff, 0xff)],
+        math::uvec2(1, 1),
+        1,
+    )
+}
+
+/// Tints a single white pixel with the given color, such that it may be scaled to draw solid rectangles.
+///
+/// See [`CanvasExt::fill_rect`] for drawing rectangles directly.
+pub fn solid(pixel: TextureSlice<'_>, color: Color) -> impl Drawable<'_> {
+    pixel.tinted(color)
+}
+
+/// Options for loading a texture.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct TextureOptions {
+    /// Whether to multiply the color channels by alpha before uploading.
+    pub premultiply_alpha: bool,
+}
+
+/// A pixel format that textures may be loaded from.
+///
+/// Pixels are converted to 8-bit RGBA when uploaded, except for [`Color`], which is uploaded as is.
+pub trait TexturePixel: Copy {
+    /// Converts the pixel to 8-bit RGBA.
+    fn to_rgba8(self) -> Color;
+
+    /// Reinterprets pixels as 8-bit RGBA without copying, if they already are.
+    fn as_rgba8(pixels: &[Self]) -> Option<&[Color]> {
+        _ = pixels;
+        None
+    }
+}
+
+/// Narrows a 16-bit channel to 8 bits.
+fn narrow(c: u16) -> u8 {
+    (c >> 8) as u8
+}
+
+impl TexturePixel for Color {
+    fn to_rgba8(self) -> Color {
+        self
+    }
+
+    fn as_rgba8(pixels: &[Self]) -> Option<&[Color]> {
+        Some(pixels)
+    }
+}
+
+impl TexturePixel for rgb::Rgba<u16> {
+    fn to_rgba8(self) -> Color {
+        Color::new(
+            narrow(self.r),
+            narrow(self.g),
+            narrow(self.b),
+            narrow(self.a),
+        )
+    }
+}
+
+impl TexturePixel for rgb::Rgb<u8> {
+    fn to_rgba8(self) -> Color {
+        Color::new(self.r, self.g, self.b, 0xff)
+    }
+}
+
+impl TexturePixel for rgb::Rgb<u16> {
+    fn to_rgba8(self) -> Color {
+        Color::new(narrow(self.r), narrow(self.g), narrow(self.b), 0xff)
+    }
+}
+
+impl TexturePixel for rgb::alt::Gray<u8> {
+    fn to_rgba8(self) -> Color {
+        let v = self.value();
+        Color::new(v, v, v, 0xff)
+    }
+}
+
+impl TexturePixel for rgb::alt::Gray<u16> {
+    fn to_rgba8(self) -> Color {
+        let v = narrow(self.value());
+        Color::new(v, v, v, 0xff)
+    }
+}
+
+impl TexturePixel for rgb::alt::GrayAlpha<u8> {
+    fn to_rgba8(self) -> Color {
+        Color::new(self.v, self.v, self.v, self.a)
+    }
+}
+
+impl TexturePixel for rgb::alt::GrayAlpha<u16> {
+    fn to_rgba8(self) -> Color {
+        let v = narrow(self.v);
+        Color::new(v, v, v, narrow(self.a))
+    }
 }
 
 /// A texture.
-pub struct Texture(wgpu::Texture);
+///
+/// Textures are cheap to clone, and clones refer to the same underlying texture, which is freed once all clones are dropped. On native platforms, textures may be sent to and shared between threads.
+#[derive(Clone)]
+pub struct Texture(Arc<TextureInner>);
+
+struct TextureInner(wgpu::Texture);
+
+impl Drop for TextureInner {
+    fn drop(&mut self) {
+        crate::stats::TEXTURES.sub(texture_bytes(&self.0));
+    }
+}
 
 impl Texture {
+    pub(crate) fn create<P>(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        img: impl AsImgRef<P>,
+        options: TextureOptions,
+    ) -> Self
+    where
+        P: TexturePixel,
+    {
+        let img = img.as_ref();
+        let size = img.size();
+
+        // Only convert if needed, such that RGBA8 images are uploaded without copying.
+        let converted;
+        let pixels = match P::as_rgba8(img.as_buf()) {
+            Some(pixels) if !options.premultiply_alpha => pixels,
+            _ => {
+                converted = img
+                    .as_buf()
+                    .iter()
+                    .map(|pixel| {
+                        let color = pixel.to_rgba8();
+                        if options.premultiply_alpha {
+                            color.premultiply()
+                        } else {
+                            color
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                converted.as_slice()
+            }
+        };
+
+        let texture = device.create_texture_with_data(
+            queue,
+            &wgpu::TextureDescriptor {
+                label: Some("teenygame: Texture"),
+                size: wgpu::Extent3d {
+                    width: size.x,
+                    height: size.y,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Rgba8UnormSrgb,
+                usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_SRC,
+                view_formats: &[],
+            },
+            wgpu::util::TextureDataOrder::default(),
+            bytemuck::cast_slice(pixels),
+        );
+        crate::stats::TEXTURES.add(texture_bytes(&texture));
+        Self(Arc::new(TextureInner(texture)))
+    }
+
     /// Gets a slice of the texture at the given layer.
     pub fn layer(&self, layer: u32) -> Option<TextureSlice> {
-        if layer >= self.0.size().depth_or_array_layers {
+        let texture = &self.0 .0;
+        if layer >= texture.size().depth_or_array_layers {
             return None;
         }
-        Some(TextureSlice::new(&self.0, layer))
+        Some(TextureSlice::new(texture, layer))
     }
 }
 
 /// A lazily loaded resource.
+///
+/// The raw resource may be prepared on another thread (e.g. by decoding an image in an async loader) and handed to the game to be loaded into the graphics state when first used.
 pub struct Lazy<Resource>
 where
     Resource: LazyLoadable,
@@ -206,11 +1115,25 @@ where
     loaded: Option<LazyLoaded<Resource>>,
 }
 
+#[derive(Clone)]
 struct LazyLoaded<Resource> {
     ready: Resource,
     device_id: DeviceId,
 }
 
+impl<Resource> Clone for Lazy<Resource>
+where
+    Resource: LazyLoadable + Clone,
+    Resource::Raw: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            raw: self.raw.clone(),
+            loaded: self.loaded.clone(),
+        }
+    }
+}
+
 /// A resource that can be lazily loaded.
 pub trait LazyLoadable {
     /// The raw resource.
@@ -236,6 +1159,13 @@ impl LazyLoadable for Vec<font::Attrs> {
     }
 }
 
+impl Lazy<Texture> {
+    /// Creates a lazily loaded texture containing a single white pixel, for drawing solid shapes, e.g. via [`CanvasExt::fill_rect`].
+    pub fn white_pixel() -> Self {
+        Self::new(white_pixel_img())
+    }
+}
+
 impl<Resource> Lazy<Resource>
 where
     Resource: LazyLoadable,
@@ -272,3 +1202,82 @@ where
         self.loaded = None;
     }
 }
+
+/// Text that is only prepared again when it changes.
+///
+/// Preparing text shapes it and uploads its glyphs, which is wasteful to do every frame for text that rarely changes (e.g. a score).
+///
+/// ```ignore
+/// self.score_text.set_contents(format!("Score: {}", self.score));
+/// canvas.draw(self.score_text.get_or_prepare(ctxt.gfx), translate(16.0, 56.0));
+/// ```
+pub struct Text {
+    contents: String,
+    metrics: font::Metrics,
+    attrs: font::Attrs,
+    prepared: Option<(PreparedText, DeviceId)>,
+}
+
+impl Text {
+    /// Creates new text.
+    pub fn new(contents: impl Into<String>, metrics: font::Metrics, attrs: font::Attrs) -> Self {
+        Self {
+            contents: contents.into(),
+            metrics,
+            attrs,
+            prepared: None,
+        }
+    }
+
+    /// Gets the contents of the text.
+    pub fn contents(&self) -> &str {
+        &self.contents
+    }
+
+    /// Sets the contents of the text. The text is only prepared again if the contents differ.
+    pub fn set_contents(&mut self, contents: impl AsRef<str>) {
+        let contents = contents.as_ref();
+        if self.contents == contents {
+            return;
+        }
+        self.contents.clear();
+        self.contents.push_str(contents);
+        self.prepared = None;
+    }
+
+    /// Sets the metrics of the text.
+    pub fn set_metrics(&mut self, metrics: font::Metrics) {
+        self.metrics = metrics;
+        self.prepared = None;
+    }
+
+    /// Sets the attributes of the text.
+    pub fn set_attrs(&mut self, attrs: font::Attrs) {
+        self.attrs = attrs;
+        self.prepared = None;
+    }
+
+    /// Gets the prepared text, preparing it if it changed since it was last prepared.
+    ///
+    /// Like [`Lazy`], the text is also prepared again if the graphics device was invalidated.
+    pub fn get_or_prepare(&mut self, graphics: &mut Graphics) -> &PreparedText {
+        let device_id = graphics.device_id();
+        if self
+            .prepared
+            .as_ref()
+            .is_some_and(|(_, prepared_device_id)| *prepared_device_id != device_id)
+        {
+            self.prepared = None;
+        }
+
+        &self
+            .prepared
+            .get_or_insert_with(|| {
+                (
+                    graphics.prepare_text(&self.contents, self.metrics, self.attrs.clone()),
+                    device_id,
+                )
+            })
+            .0
+    }
+}
@@ -0,0 +1,105 @@
+//! Texture atlases.
+//!
+//! Packing many small images into one texture lets them be drawn in a single batch. When atlas sprites are scaled, rotated or drawn at fractional positions, filtering may sample pixels just outside of them, which shows up as colored seams from neighboring sprites. [`pack`] separates sprites by a transparent gutter and extrudes their edge pixels outwards, such that those samples pick up the sprite's own colors:
+//!
+//! ```ignore
+//! let atlas = atlas::pack(&[player_img, tree_img, rock_img], AtlasOptions::default());
+//! self.atlas = Lazy::new(atlas.img);
+//! self.rects = atlas.rects;
+//!
+//! // In draw:
+//! let texture = self.atlas.get_or_load(ctxt.gfx).layer(0).unwrap();
+//! canvas.draw(atlas::slice(texture, self.rects[0]).unwrap(), transform);
+//! ```
+
+use super::{Color, TextureSlice};
+use crate::image::Img;
+use crate::math::{URect, UVec2};
+
+/// Options for packing an atlas.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct AtlasOptions {
+    /// Width of the atlas image, in pixels. Images wider than this get a row of their own, widening the atlas.
+    pub width: u32,
+
+    /// Transparent pixels between sprites, and around the edges of the atlas.
+    pub padding: u32,
+
+    /// Number of times each sprite's edge pixels are repeated outwards, within the padding.
+    ///
+    /// One pixel is enough for linear filtering at any scale, as long as sprites are drawn at whole-pixel slices.
+    pub extrude: u32,
+}
+
+impl Default for AtlasOptions {
+    fn default() -> Self {
+        Self {
+            width: 1024,
+            padding: 1,
+            extrude: 1,
+        }
+    }
+}
+
+/// Images packed into a single image.
+#[derive(Clone)]
+pub struct Atlas {
+    /// The packed image.
+    pub img: Img<Vec<Color>>,
+
+    /// Where each of the packed images ended up, in the order they were given, excluding extruded pixels.
+    pub rects: Vec<URect>,
+}
+
+/// Packs the first layer of each image into an atlas, in rows of images sorted by height.
+pub fn pack(images: &[Img<Vec<Color>>], options: AtlasOptions) -> Atlas {
+    let margin = options.extrude * 2 + options.padding;
+
+    let mut order = (0..images.len()).collect::<Vec<_>>();
+    order.sort_by_key(|i| std::cmp::Reverse(images[*i].size().y));
+
+    // Place images into rows, starting a new row whenever the current one is full.
+    let mut positions = vec![UVec2::ZERO; images.len()];
+    let mut pos = UVec2::splat(options.padding);
+    let mut row_height = 0;
+    let mut width = options.width;
+    for i in order {
+        let cell = images[i].size() + margin;
+        if pos.x > options.padding && pos.x + cell.x > options.width {
+            pos = UVec2::new(options.padding, pos.y + row_height);
+            row_height = 0;
+        }
+        positions[i] = pos;
+        pos.x += cell.x;
+        row_height = row_height.max(cell.y);
+        width = width.max(pos.x);
+    }
+
+    let mut img = Img::filled(
+        UVec2::new(width, pos.y + row_height),
+        Color::new(0, 0, 0, 0),
+    );
+    let mut rects = Vec::with_capacity(images.len());
+    for (src, pos) in images.iter().zip(positions) {
+        let size = src.size();
+        rects.push(URect::from_pos_size(pos + options.extrude, size));
+        if size.x == 0 || size.y == 0 {
+            continue;
+        }
+
+        let extruded = Img::from_fn(size + options.extrude * 2, |p| {
+            let p = p
+                .saturating_sub(UVec2::splat(options.extrude))
+                .min(size - 1);
+            src.get_pixel(p).unwrap()
+        });
+        img.blit(&extruded, pos.as_ivec2());
+    }
+
+    Atlas { img, rects }
+}
+
+/// Gets the slice of an atlas texture covering the given rectangle, e.g. one of [`Atlas::rects`].
+pub fn slice(texture: TextureSlice, rect: URect) -> Option<TextureSlice> {
+    texture.slice(rect.min.as_ivec2(), rect.size())
+}
@@ -0,0 +1,143 @@
+//! 2D cameras.
+//!
+//! A [`Camera2D`] maps world coordinates to canvas coordinates, such that a game can be drawn in its own units and scrolled, zoomed and rotated as a whole:
+//!
+//! ```ignore
+//! let mut camera = Camera2D::new(ctxt.gfx.window().size().as_vec2());
+//! camera.position = self.player.pos;
+//! camera.zoom = 4.0;
+//! canvas.draw(sprite, camera.transform() * math::translate(x, y));
+//!
+//! let cursor_in_world = ctxt.input.mouse.position_in(&camera);
+//! ```
+//!
+//! For pixel art, [`Camera2D::pixel_perfect`] snaps the view to whole pixels such that sprites never shimmer. Smooth scrolling is still possible by rendering at the virtual resolution and shifting the upscaled result by the [`Camera2D::subpixel_offset`]:
+//!
+//! ```ignore
+//! let mut camera = Camera2D::new(VIRTUAL_SIZE.as_vec2());
+//! camera.pixel_perfect = true;
+//! camera.position = self.player.pos;
+//! world_canvas.draw(sprite, camera.transform() * math::translate(x, y));
+//! ctxt.gfx.render_to_framebuffer(&world_canvas, &self.framebuffer);
+//!
+//! let window_size = ctxt.gfx.window().size().as_vec2();
+//! let offset = camera.subpixel_offset();
+//! canvas.draw(
+//!     self.framebuffer.as_texture_slice(),
+//!     camera::integer_scale_transform(VIRTUAL_SIZE.as_vec2(), window_size) * math::translate(-offset.x, -offset.y),
+//! );
+//! ```
+
+use crate::math::{Affine2, Rect, Vec2};
+
+/// A 2D camera.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Camera2D {
+    /// World position shown at the center of the viewport.
+    pub position: Vec2,
+
+    /// Number of canvas pixels per world unit.
+    ///
+    /// This may be used as a virtual resolution scaler, e.g. a zoom of 4 draws each world unit as 4x4 pixels.
+    pub zoom: f32,
+
+    /// Rotation of the view, in radians.
+    pub rotation: f32,
+
+    /// Size of the viewport in canvas pixels, usually the size of the window.
+    pub viewport: Vec2,
+
+    /// Whether the view is snapped to whole pixels, for pixel art.
+    ///
+    /// When enabled, world units are virtual pixels: the position is rounded to whole world units, zoom is rounded to a whole number of at least 1, and the viewport center is rounded to a whole canvas pixel.
+    pub pixel_perfect: bool,
+}
+
+impl Camera2D {
+    /// Creates a new camera centered on the origin with no zoom or rotation.
+    pub fn new(viewport: Vec2) -> Self {
+        Self {
+            position: Vec2::ZERO,
+            zoom: 1.0,
+            rotation: 0.0,
+            viewport,
+            pixel_perfect: false,
+        }
+    }
+
+    /// Gets the zoom actually used, which is rounded if [`Camera2D::pixel_perfect`] is enabled.
+    pub fn effective_zoom(&self) -> f32 {
+        if self.pixel_perfect {
+            self.zoom.round().max(1.0)
+        } else {
+            self.zoom
+        }
+    }
+
+    /// Snaps a world position to the virtual pixel grid if [`Camera2D::pixel_perfect`] is enabled, e.g. for sprite positions.
+    pub fn snap(&self, pos: Vec2) -> Vec2 {
+        if self.pixel_perfect {
+            pos.round()
+        } else {
+            pos
+        }
+    }
+
+    /// Gets how far the snapped view is from the camera's exact position, in canvas pixels.
+    ///
+    /// Shifting the rendered view by the negation of this offset compensates for snapping, such that scrolling stays smooth. This is zero unless [`Camera2D::pixel_perfect`] is enabled.
+    pub fn subpixel_offset(&self) -> Vec2 {
+        (self.position - self.snap(self.position)) * self.effective_zoom()
+    }
+
+    /// Gets the transform from world coordinates to canvas coordinates.
+    pub fn transform(&self) -> Affine2 {
+        let center = if self.pixel_perfect {
+            (self.viewport / 2.0).floor()
+        } else {
+            self.viewport / 2.0
+        };
+        Affine2::from_translation(center)
+            * Affine2::from_angle(-self.rotation)
+            * Affine2::from_scale(Vec2::splat(self.effective_zoom()))
+            * Affine2::from_translation(-self.snap(self.position))
+    }
+
+    /// Converts a position in canvas coordinates to world coordinates.
+    pub fn screen_to_world(&self, pos: Vec2) -> Vec2 {
+        self.transform().inverse().transform_point2(pos)
+    }
+
+    /// Converts a position in world coordinates to canvas coordinates.
+    pub fn world_to_screen(&self, pos: Vec2) -> Vec2 {
+        self.transform().transform_point2(pos)
+    }
+
+    /// Gets the bounding box of the visible area in world coordinates.
+    ///
+    /// If the camera is rotated, this covers the whole rotated viewport.
+    pub fn visible_rect(&self) -> Rect {
+        let corners = [
+            Vec2::ZERO,
+            Vec2::new(self.viewport.x, 0.0),
+            Vec2::new(0.0, self.viewport.y),
+            self.viewport,
+        ]
+        .map(|corner| self.screen_to_world(corner));
+        let min = corners.into_iter().reduce(Vec2::min).unwrap();
+        let max = corners.into_iter().reduce(Vec2::max).unwrap();
+        Rect::from_min_max(min, max)
+    }
+}
+
+/// Gets the largest whole scale at which content of the given virtual size fits in the window, which is at least 1.
+pub fn integer_scale(virtual_size: Vec2, window_size: Vec2) -> f32 {
+    (window_size / virtual_size).min_element().floor().max(1.0)
+}
+
+/// Gets the transform that draws content of the given virtual size at its [`integer_scale`], centered in the window on whole pixels.
+pub fn integer_scale_transform(virtual_size: Vec2, window_size: Vec2) -> Affine2 {
+    let scale = integer_scale(virtual_size, window_size);
+    Affine2::from_translation(((window_size - virtual_size * scale) / 2.0).floor())
+        * Affine2::from_scale(Vec2::splat(scale))
+}
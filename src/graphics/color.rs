@@ -0,0 +1,262 @@
+//! Color utilities.
+//!
+//! [`Color`] is a plain 8-bit sRGB color, so conversions are provided by the [`ColorExt`] extension trait:
+//!
+//! ```ignore
+//! use teenygame::graphics::color::{ColorExt as _, Hsl};
+//!
+//! let orange = Color::from_hex("#ff8800").unwrap();
+//! let hue = Color::from_hsl(Hsl { h: 120.0, s: 1.0, l: 0.5 });
+//! let mix = orange.lerp_oklab(hue, 0.5);
+//! ```
+
+use super::Color;
+
+/// A color in HSL (hue, saturation, lightness) space.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct Hsl {
+    /// Hue in degrees, from 0 to 360.
+    pub h: f32,
+
+    /// Saturation, from 0 to 1.
+    pub s: f32,
+
+    /// Lightness, from 0 to 1.
+    pub l: f32,
+}
+
+/// A color in HSV (hue, saturation, value) space.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct Hsv {
+    /// Hue in degrees, from 0 to 360.
+    pub h: f32,
+
+    /// Saturation, from 0 to 1.
+    pub s: f32,
+
+    /// Value, from 0 to 1.
+    pub v: f32,
+}
+
+/// A color in the [Oklab](https://bottosson.github.io/posts/oklab/) perceptual color space.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct Oklab {
+    /// Perceived lightness, from 0 to 1.
+    pub l: f32,
+
+    /// Green to red.
+    pub a: f32,
+
+    /// Blue to yellow.
+    pub b: f32,
+}
+
+pub(crate) fn srgb_to_linear(c: u8) -> f32 {
+    let c = c as f32 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(c: f32) -> u8 {
+    let c = c.clamp(0.0, 1.0);
+    let c = if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    };
+    (c * 255.0).round() as u8
+}
+
+fn unit_to_u8(c: f32) -> u8 {
+    (c.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// Computes RGB channels from 0 to 1 given a hue in degrees, chroma and the amount to add to each channel.
+fn hue_to_rgb(h: f32, chroma: f32, m: f32) -> [f32; 3] {
+    let h = h.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+    let [r, g, b] = match h as u32 {
+        0 => [chroma, x, 0.0],
+        1 => [x, chroma, 0.0],
+        2 => [0.0, chroma, x],
+        3 => [0.0, x, chroma],
+        4 => [x, 0.0, chroma],
+        _ => [chroma, 0.0, x],
+    };
+    [r + m, g + m, b + m]
+}
+
+/// Computes the hue in degrees, max channel and chroma of RGB channels from 0 to 1.
+fn rgb_to_hue(r: f32, g: f32, b: f32) -> (f32, f32, f32) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let chroma = max - min;
+    let h = if chroma == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / chroma).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / chroma + 2.0)
+    } else {
+        60.0 * ((r - g) / chroma + 4.0)
+    };
+    (h, max, chroma)
+}
+
+/// Extra methods for [`Color`].
+pub trait ColorExt: Sized {
+    /// Parses a color from a hex string in the form `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`. The `#` is optional.
+    fn from_hex(hex: &str) -> Option<Self>;
+
+    /// Formats the color as a hex string in the form `#rrggbbaa`.
+    fn to_hex(&self) -> String;
+
+    /// Converts a color from HSL, with full opacity.
+    fn from_hsl(hsl: Hsl) -> Self;
+
+    /// Converts the color to HSL, ignoring alpha.
+    fn to_hsl(&self) -> Hsl;
+
+    /// Converts a color from HSV, with full opacity.
+    fn from_hsv(hsv: Hsv) -> Self;
+
+    /// Converts the color to HSV, ignoring alpha.
+    fn to_hsv(&self) -> Hsv;
+
+    /// Converts a color from Oklab, with full opacity.
+    fn from_oklab(oklab: Oklab) -> Self;
+
+    /// Converts the color to Oklab, ignoring alpha.
+    fn to_oklab(&self) -> Oklab;
+
+    /// Linearly interpolates between two colors by t in Oklab space, which gives perceptually even gradients.
+    fn lerp_oklab(&self, other: Self, t: f32) -> Self;
+
+    /// Multiplies the color channels by alpha.
+    fn premultiply(&self) -> Self;
+
+    /// Divides the color channels by alpha, undoing [`ColorExt::premultiply`].
+    fn unpremultiply(&self) -> Self;
+}
+
+impl ColorExt for Color {
+    fn from_hex(hex: &str) -> Option<Self> {
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        if !hex.is_ascii() {
+            return None;
+        }
+        let channel = |i: usize, len: usize| {
+            let v = u8::from_str_radix(&hex[i * len..(i + 1) * len], 16).ok()?;
+            Some(if len == 1 { v * 0x11 } else { v })
+        };
+        let (len, has_alpha) = match hex.len() {
+            3 => (1, false),
+            4 => (1, true),
+            6 => (2, false),
+            8 => (2, true),
+            _ => return None,
+        };
+        Some(Color::new(
+            channel(0, len)?,
+            channel(1, len)?,
+            channel(2, len)?,
+            if has_alpha { channel(3, len)? } else { 0xff },
+        ))
+    }
+
+    fn to_hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}{:02x}", self.r, self.g, self.b, self.a)
+    }
+
+    fn from_hsl(hsl: Hsl) -> Self {
+        let s = hsl.s.clamp(0.0, 1.0);
+        let l = hsl.l.clamp(0.0, 1.0);
+        let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        let [r, g, b] = hue_to_rgb(hsl.h, chroma, l - chroma / 2.0);
+        Color::new(unit_to_u8(r), unit_to_u8(g), unit_to_u8(b), 0xff)
+    }
+
+    fn to_hsl(&self) -> Hsl {
+        let [r, g, b] = [self.r, self.g, self.b].map(|c| c as f32 / 255.0);
+        let (h, max, chroma) = rgb_to_hue(r, g, b);
+        let l = max - chroma / 2.0;
+        let s = if l == 0.0 || l == 1.0 {
+            0.0
+        } else {
+            chroma / (1.0 - (2.0 * l - 1.0).abs())
+        };
+        Hsl { h, s, l }
+    }
+
+    fn from_hsv(hsv: Hsv) -> Self {
+        let s = hsv.s.clamp(0.0, 1.0);
+        let v = hsv.v.clamp(0.0, 1.0);
+        let chroma = v * s;
+        let [r, g, b] = hue_to_rgb(hsv.h, chroma, v - chroma);
+        Color::new(unit_to_u8(r), unit_to_u8(g), unit_to_u8(b), 0xff)
+    }
+
+    fn to_hsv(&self) -> Hsv {
+        let [r, g, b] = [self.r, self.g, self.b].map(|c| c as f32 / 255.0);
+        let (h, max, chroma) = rgb_to_hue(r, g, b);
+        Hsv {
+            h,
+            s: if max == 0.0 { 0.0 } else { chroma / max },
+            v: max,
+        }
+    }
+
+    fn from_oklab(oklab: Oklab) -> Self {
+        let l = oklab.l + 0.396_337_78 * oklab.a + 0.215_803_76 * oklab.b;
+        let m = oklab.l - 0.105_561_346 * oklab.a - 0.063_854_17 * oklab.b;
+        let s = oklab.l - 0.089_484_18 * oklab.a - 1.291_485_5 * oklab.b;
+        let [l, m, s] = [l, m, s].map(|c| c * c * c);
+        Color::new(
+            linear_to_srgb(4.076_741_7 * l - 3.307_711_6 * m + 0.230_969_94 * s),
+            linear_to_srgb(-1.268_438 * l + 2.609_757_4 * m - 0.341_319_38 * s),
+            linear_to_srgb(-0.004_196_086_3 * l - 0.703_418_6 * m + 1.707_614_7 * s),
+            0xff,
+        )
+    }
+
+    fn to_oklab(&self) -> Oklab {
+        let [r, g, b] = [self.r, self.g, self.b].map(srgb_to_linear);
+        let l = 0.412_221_46 * r + 0.536_332_55 * g + 0.051_445_995 * b;
+        let m = 0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b;
+        let s = 0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b;
+        let [l, m, s] = [l, m, s].map(f32::cbrt);
+        Oklab {
+            l: 0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+            a: 1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+            b: 0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+        }
+    }
+
+    fn lerp_oklab(&self, other: Self, t: f32) -> Self {
+        let from = self.to_oklab();
+        let to = other.to_oklab();
+        let mut color = Color::from_oklab(Oklab {
+            l: from.l + (to.l - from.l) * t,
+            a: from.a + (to.a - from.a) * t,
+            b: from.b + (to.b - from.b) * t,
+        });
+        color.a = (self.a as f32 + (other.a as f32 - self.a as f32) * t).round() as u8;
+        color
+    }
+
+    fn premultiply(&self) -> Self {
+        let mul = |c: u8| ((c as u16 * self.a as u16 + 127) / 255) as u8;
+        Color::new(mul(self.r), mul(self.g), mul(self.b), self.a)
+    }
+
+    fn unpremultiply(&self) -> Self {
+        if self.a == 0 {
+            return Color::new(0, 0, 0, 0);
+        }
+        let div = |c: u8| ((c as u16 * 255 + self.a as u16 / 2) / self.a as u16).min(255) as u8;
+        Color::new(div(self.r), div(self.g), div(self.b), self.a)
+    }
+}
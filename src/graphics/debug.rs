@@ -0,0 +1,282 @@
+//! Debug drawing of shapes.
+//!
+//! Shapes are drawn as outlines by stretching a single white pixel, such as one loaded via [`Lazy::white_pixel`](super::Lazy::white_pixel):
+//!
+//! ```ignore
+//! let pixel = self.pixel.get_or_load(ctxt.gfx).layer(0).unwrap();
+//! let mut debug = DebugCanvas::new(canvas, pixel);
+//! self.hitbox.debug_draw(&mut debug, Color::new(0xff, 0x00, 0x00, 0xff));
+//! ```
+//!
+//! [`Gizmos`] may also be used from [`Game::update`](crate::Game::update) via [`Context::debug`](crate::Context::debug), and are drawn on top of everything else at the end of the frame:
+//!
+//! ```ignore
+//! ctxt.debug.text(Vec2::new(8.0, 16.0), format!("velocity: {}", self.player.velocity));
+//! ctxt.debug.rect(&self.player.hitbox, Color::new(0xff, 0x00, 0x00, 0xff));
+//! ```
+
+use super::{font, Canvas, Color, Drawable as _, Graphics, TextureSlice};
+use crate::math::{
+    collision::{Circle, Polygon, Segment, Shape},
+    pathfinding::Path,
+    raycast::{Ray, RayHit},
+    Affine2, Rect, Vec2,
+};
+
+/// Number of segments used to approximate circles.
+const CIRCLE_SEGMENTS: usize = 32;
+
+/// A canvas for drawing debug outlines.
+pub struct DebugCanvas<'c, 'a> {
+    canvas: &'c mut Canvas<'a>,
+    pixel: TextureSlice<'a>,
+    thickness: f32,
+    transform: Affine2,
+}
+
+impl<'c, 'a> DebugCanvas<'c, 'a> {
+    /// Creates a debug canvas that draws onto the given canvas.
+    ///
+    /// `pixel` must be a slice containing a single white pixel, e.g. from [`Lazy::white_pixel`](super::Lazy::white_pixel).
+    pub fn new(canvas: &'c mut Canvas<'a>, pixel: TextureSlice<'a>) -> Self {
+        Self {
+            canvas,
+            pixel,
+            thickness: 1.0,
+            transform: Affine2::IDENTITY,
+        }
+    }
+
+    /// Sets the thickness of lines, in pixels.
+    pub fn with_thickness(mut self, thickness: f32) -> Self {
+        self.thickness = thickness;
+        self
+    }
+
+    /// Sets a transform applied to all shapes, e.g. to convert from world space to screen space.
+    ///
+    /// Line thickness is not affected by the transform.
+    pub fn with_transform(mut self, transform: Affine2) -> Self {
+        self.transform = transform;
+        self
+    }
+
+    /// Draws a line.
+    pub fn line(&mut self, a: Vec2, b: Vec2, color: Color) {
+        let a = self.transform.transform_point2(a);
+        let b = self.transform.transform_point2(b);
+        let delta = b - a;
+        self.canvas.draw(
+            self.pixel.tinted(color),
+            Affine2::from_translation(a)
+                * Affine2::from_angle(delta.to_angle())
+                * Affine2::from_scale(Vec2::new(delta.length(), self.thickness))
+                * Affine2::from_translation(Vec2::new(0.0, -0.5)),
+        );
+    }
+
+    /// Draws lines between consecutive points, optionally connecting the last point back to the first.
+    pub fn polyline(&mut self, points: &[Vec2], closed: bool, color: Color) {
+        for w in points.windows(2) {
+            self.line(w[0], w[1], color);
+        }
+        if closed && points.len() > 2 {
+            self.line(points[points.len() - 1], points[0], color);
+        }
+    }
+
+    /// Draws the outline of a rectangle.
+    pub fn rect(&mut self, rect: &Rect, color: Color) {
+        self.polyline(&Polygon::from_rect(rect).points, true, color);
+    }
+
+    /// Draws the outline of a circle.
+    pub fn circle(&mut self, center: Vec2, radius: f32, color: Color) {
+        let points = (0..CIRCLE_SEGMENTS)
+            .map(|i| {
+                center
+                    + Vec2::from_angle(i as f32 / CIRCLE_SEGMENTS as f32 * std::f32::consts::TAU)
+                        * radius
+            })
+            .collect::<Vec<_>>();
+        self.polyline(&points, true, color);
+    }
+
+    /// Draws a small cross marking a point.
+    pub fn point(&mut self, point: Vec2, size: f32, color: Color) {
+        let half = size / 2.0;
+        self.line(
+            point - Vec2::new(half, half),
+            point + Vec2::new(half, half),
+            color,
+        );
+        self.line(
+            point - Vec2::new(half, -half),
+            point + Vec2::new(half, -half),
+            color,
+        );
+    }
+
+    /// Draws a ray up to the given distance.
+    pub fn ray(&mut self, ray: &Ray, max_distance: f32, color: Color) {
+        self.line(ray.origin, ray.at(max_distance), color);
+    }
+
+    /// Draws a ray hit as a cross at the hit point along with its normal.
+    pub fn ray_hit(&mut self, hit: &RayHit, size: f32, color: Color) {
+        self.point(hit.point, size, color);
+        self.line(hit.point, hit.point + hit.normal * size * 2.0, color);
+    }
+
+    /// Draws a path through the centers of grid cells of the given size.
+    pub fn path(&mut self, path: &Path, cell_size: Vec2, color: Color) {
+        self.polyline(&path.to_world(cell_size), false, color);
+    }
+}
+
+/// A type that may be drawn for debugging.
+pub trait DebugDraw {
+    /// Draws the outline of the value.
+    fn debug_draw(&self, canvas: &mut DebugCanvas, color: Color);
+}
+
+impl DebugDraw for Rect {
+    fn debug_draw(&self, canvas: &mut DebugCanvas, color: Color) {
+        canvas.rect(self, color);
+    }
+}
+
+impl DebugDraw for Circle {
+    fn debug_draw(&self, canvas: &mut DebugCanvas, color: Color) {
+        canvas.circle(self.center, self.radius, color);
+    }
+}
+
+impl DebugDraw for Polygon {
+    fn debug_draw(&self, canvas: &mut DebugCanvas, color: Color) {
+        canvas.polyline(&self.points, true, color);
+    }
+}
+
+impl DebugDraw for Segment {
+    fn debug_draw(&self, canvas: &mut DebugCanvas, color: Color) {
+        canvas.line(self.start, self.end, color);
+    }
+}
+
+impl DebugDraw for Shape {
+    fn debug_draw(&self, canvas: &mut DebugCanvas, color: Color) {
+        match self {
+            Shape::Rect(rect) => rect.debug_draw(canvas, color),
+            Shape::Circle(circle) => circle.debug_draw(canvas, color),
+            Shape::Polygon(polygon) => polygon.debug_draw(canvas, color),
+        }
+    }
+}
+
+impl DebugDraw for RayHit {
+    fn debug_draw(&self, canvas: &mut DebugCanvas, color: Color) {
+        canvas.ray_hit(self, 8.0, color);
+    }
+}
+
+impl DebugDraw for [Vec2] {
+    fn debug_draw(&self, canvas: &mut DebugCanvas, color: Color) {
+        canvas.polyline(self, false, color);
+    }
+}
+
+/// Font size of gizmo text, in pixels.
+const GIZMO_TEXT_SIZE: f32 = 16.0;
+
+enum Gizmo {
+    Text(Vec2, String, Color),
+    Line(Vec2, Vec2, Color),
+    Rect(Rect, Color),
+    Circle(Vec2, f32, Color),
+    Point(Vec2, Color),
+}
+
+/// Debug text and shapes that are drawn in screen space on top of everything else at the end of the frame.
+///
+/// Gizmos added during [`Game::update`](crate::Game::update) are kept until the next update, and gizmos added during [`Game::draw`](crate::Game::draw) are only drawn for that frame. Text uses the default font, so at least one font must have been added via [`Graphics::add_font`].
+pub struct Gizmos {
+    gizmos: Vec<Gizmo>,
+}
+
+impl Gizmos {
+    pub(crate) fn new() -> Self {
+        Self { gizmos: vec![] }
+    }
+
+    /// Draws white text, with the given position being the top-left corner.
+    pub fn text(&mut self, pos: Vec2, text: impl Into<String>) {
+        self.colored_text(pos, text, Color::new(0xff, 0xff, 0xff, 0xff));
+    }
+
+    /// Draws text in the given color, with the given position being the top-left corner.
+    pub fn colored_text(&mut self, pos: Vec2, text: impl Into<String>, color: Color) {
+        self.gizmos.push(Gizmo::Text(pos, text.into(), color));
+    }
+
+    /// Draws a line.
+    pub fn line(&mut self, a: Vec2, b: Vec2, color: Color) {
+        self.gizmos.push(Gizmo::Line(a, b, color));
+    }
+
+    /// Draws the outline of a rectangle.
+    pub fn rect(&mut self, rect: &Rect, color: Color) {
+        self.gizmos.push(Gizmo::Rect(*rect, color));
+    }
+
+    /// Draws the outline of a circle.
+    pub fn circle(&mut self, center: Vec2, radius: f32, color: Color) {
+        self.gizmos.push(Gizmo::Circle(center, radius, color));
+    }
+
+    /// Draws a small cross marking a point.
+    pub fn point(&mut self, point: Vec2, color: Color) {
+        self.gizmos.push(Gizmo::Point(point, color));
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.gizmos.len()
+    }
+
+    pub(crate) fn truncate(&mut self, len: usize) {
+        self.gizmos.truncate(len);
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.gizmos.clear();
+    }
+
+    /// Draws all gizmos onto the canvas, using the given white pixel.
+    pub(crate) fn flush<'a>(
+        &self,
+        gfx: &mut Graphics,
+        canvas: &mut Canvas<'a>,
+        pixel: TextureSlice<'a>,
+    ) {
+        let mut debug = DebugCanvas::new(canvas, pixel);
+        for gizmo in self.gizmos.iter() {
+            match gizmo {
+                Gizmo::Text(pos, text, color) => {
+                    let prepared = gfx.prepare_text(
+                        text,
+                        font::Metrics::relative(GIZMO_TEXT_SIZE, 1.2),
+                        font::Attrs::new(),
+                    );
+                    debug.canvas.draw(
+                        prepared.tinted(*color),
+                        Affine2::from_translation(*pos + Vec2::new(0.0, GIZMO_TEXT_SIZE)),
+                    );
+                }
+                Gizmo::Line(a, b, color) => debug.line(*a, *b, *color),
+                Gizmo::Rect(rect, color) => debug.rect(rect, *color),
+                Gizmo::Circle(center, radius, color) => debug.circle(*center, *radius, *color),
+                Gizmo::Point(point, color) => debug.point(*point, 8.0, *color),
+            }
+        }
+    }
+}
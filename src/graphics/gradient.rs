@@ -0,0 +1,102 @@
+//! Gradients.
+//!
+//! Gradients are baked into small images that are stretched over the area they fill, so they are drawn like any other texture:
+//!
+//! ```ignore
+//! let sky = Gradient::new(vec![(0.0, Color::new(0x20, 0x30, 0x80, 0xff)), (1.0, Color::new(0xff, 0xa0, 0x60, 0xff))]);
+//! self.sky = Lazy::new(sky.linear(UVec2::new(1, 256), std::f32::consts::FRAC_PI_2));
+//! self.vignette = Lazy::new(gradient::vignette(Color::new(0, 0, 0, 0xc0), 0.5));
+//!
+//! // In draw:
+//! let screen = Rect::from_pos_size(Vec2::ZERO, ctxt.gfx.window().size().as_vec2());
+//! canvas.draw_stretched(self.sky.get_or_load(ctxt.gfx).layer(0).unwrap(), screen);
+//! canvas.draw_stretched(self.vignette.get_or_load(ctxt.gfx).layer(0).unwrap(), screen);
+//! ```
+
+use super::color::ColorExt as _;
+use super::Color;
+use crate::image::Img;
+use crate::math::{UVec2, Vec2};
+
+/// Resolution of images created by [`vignette`].
+const VIGNETTE_SIZE: u32 = 128;
+
+/// Colors blended between along a line.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Gradient {
+    stops: Vec<(f32, Color)>,
+}
+
+impl Gradient {
+    /// Creates a gradient from stops of position from 0 to 1 and color.
+    ///
+    /// Positions before the first stop and after the last stop take the color of that stop.
+    pub fn new(mut stops: Vec<(f32, Color)>) -> Self {
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self { stops }
+    }
+
+    /// Gets the color at the given position, blended in Oklab space.
+    pub fn sample(&self, t: f32) -> Color {
+        let Some(next) = self.stops.iter().position(|(pos, _)| *pos > t) else {
+            return self
+                .stops
+                .last()
+                .map_or(Color::new(0, 0, 0, 0), |(_, color)| *color);
+        };
+        if next == 0 {
+            return self.stops[0].1;
+        }
+        let (from_pos, from) = self.stops[next - 1];
+        let (to_pos, to) = self.stops[next];
+        from.lerp_oklab(to, (t - from_pos) / (to_pos - from_pos))
+    }
+
+    /// Creates an image of the gradient running across it at the given angle in radians, where 0 runs from left to right.
+    ///
+    /// Gradients along an axis only need to be one pixel wide or tall, as the image is stretched when drawn.
+    pub fn linear(&self, size: UVec2, angle: f32) -> Img<Vec<Color>> {
+        let dir = Vec2::from_angle(angle);
+        let size_f = size.as_vec2();
+
+        // Project the corners onto the direction, such that the gradient spans exactly from one corner to the opposite one.
+        let corners = [
+            Vec2::ZERO,
+            Vec2::new(size_f.x, 0.0),
+            Vec2::new(0.0, size_f.y),
+            size_f,
+        ];
+        let (min, max) = corners
+            .iter()
+            .map(|corner| corner.dot(dir))
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), d| {
+                (min.min(d), max.max(d))
+            });
+        let span = (max - min).max(f32::EPSILON);
+
+        Img::from_fn(size, |pos| {
+            self.sample(((pos.as_vec2() + 0.5).dot(dir) - min) / span)
+        })
+    }
+
+    /// Creates an image of the gradient running from the center to the edges, where the edges are at position 1.
+    ///
+    /// The gradient is elliptical if the image is not square, and is stretched to fit when drawn.
+    pub fn radial(&self, size: UVec2) -> Img<Vec<Color>> {
+        let half = size.as_vec2() / 2.0;
+        Img::from_fn(size, |pos| {
+            self.sample(((pos.as_vec2() + 0.5 - half) / half).length())
+        })
+    }
+}
+
+/// Creates an image that darkens the edges of the screen towards the given color when stretched over it.
+///
+/// `radius` is the distance from the center where darkening starts, as a fraction from the center to the edges.
+pub fn vignette(color: Color, radius: f32) -> Img<Vec<Color>> {
+    Gradient::new(vec![
+        (radius, Color::new(color.r, color.g, color.b, 0)),
+        (std::f32::consts::SQRT_2, color),
+    ])
+    .radial(UVec2::splat(VIGNETTE_SIZE))
+}
@@ -0,0 +1,81 @@
+//! Hit testing of drawn sprites.
+//!
+//! Sprites are hit tested with the same transform they are drawn with, so clickable sprites don't need their own copy of the transform math:
+//!
+//! ```ignore
+//! let transform = math::translate(x, y) * math::rotate(theta);
+//! let hovered = ctxt
+//!     .input
+//!     .mouse
+//!     .position()
+//!     .is_some_and(|pos| hit::slice(&button, transform, pos));
+//! canvas.draw(button, transform);
+//! ```
+
+use super::{Color, Drawable as _, TextureSlice};
+use crate::image::{AsImgRef, Img};
+use crate::math::{Affine2, UVec2, Vec2};
+
+/// Converts a point into the local space of a sprite drawn with the given transform, where the sprite spans from the origin to its size.
+pub fn local_point(transform: Affine2, point: Vec2) -> Vec2 {
+    transform.inverse().transform_point2(point)
+}
+
+/// Checks if a point is within a rectangle of the given size drawn with the given transform.
+pub fn rect(size: Vec2, transform: Affine2, point: Vec2) -> bool {
+    let local = local_point(transform, point);
+    local.cmpge(Vec2::ZERO).all() && local.cmplt(size).all()
+}
+
+/// Checks if a point is within the bounds of a texture slice drawn with the given transform.
+pub fn slice(slice: &TextureSlice, transform: Affine2, point: Vec2) -> bool {
+    rect(slice.size().as_vec2(), transform, point)
+}
+
+/// A mask of which pixels of an image are opaque enough to be hit.
+///
+/// This is built from the image a texture was loaded from, as textures can't be read back from the GPU cheaply.
+#[derive(Clone, Debug)]
+pub struct AlphaMask {
+    size: UVec2,
+    bits: Vec<u64>,
+}
+
+impl AlphaMask {
+    /// Creates a mask from the first layer of an image, where pixels with alpha at least the given threshold can be hit.
+    pub fn new(img: impl AsImgRef<Color>, threshold: u8) -> Self {
+        let img: Img<&[Color]> = img.as_ref();
+        let size = img.size();
+        let len = (size.x * size.y) as usize;
+        let mut bits = vec![0u64; len.div_ceil(64)];
+        for (i, pixel) in img.as_buf()[..len].iter().enumerate() {
+            if pixel.a >= threshold {
+                bits[i / 64] |= 1 << (i % 64);
+            }
+        }
+        Self { size, bits }
+    }
+
+    /// Gets the size of the mask.
+    pub fn size(&self) -> UVec2 {
+        self.size
+    }
+
+    /// Checks if the pixel at the given position can be hit. Positions outside the mask can't be hit.
+    pub fn get(&self, pos: UVec2) -> bool {
+        if pos.x >= self.size.x || pos.y >= self.size.y {
+            return false;
+        }
+        let i = (pos.y * self.size.x + pos.x) as usize;
+        self.bits[i / 64] & (1 << (i % 64)) != 0
+    }
+
+    /// Checks if a point hits an opaque pixel of a sprite drawn with the given transform, where the sprite is the same size as the mask.
+    pub fn hit(&self, transform: Affine2, point: Vec2) -> bool {
+        let local = local_point(transform, point);
+        if local.x < 0.0 || local.y < 0.0 {
+            return false;
+        }
+        self.get(local.as_uvec2())
+    }
+}
@@ -0,0 +1,136 @@
+//! 2D lighting.
+//!
+//! [`Lighting`] multiplies the scene by an ambient color plus the contribution of point lights, and is applied as a [`PostEffect`](super::post::PostEffect). The ambient color may follow an [`AmbientCurve`] over the time of day, so overworld games can fade between day and night:
+//!
+//! ```ignore
+//! let curve = AmbientCurve::day_night();
+//!
+//! // In draw:
+//! let mut lighting = Lighting::new();
+//! lighting.set_ambient_from(&curve, self.time_of_day);
+//! lighting.lights.push(PointLight::new(camera.world_to_screen(self.torch.pos), 96.0, Color::new(0xff, 0xb0, 0x60, 0xff)));
+//! self.post.effects = vec![PostEffect::Lighting(lighting)];
+//! ```
+
+use super::color::ColorExt as _;
+use super::Color;
+use crate::math::Vec2;
+
+/// Maximum number of point lights in a [`Lighting`].
+pub const MAX_LIGHTS: usize = 7;
+
+/// A light that falls off with distance from a point.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PointLight {
+    /// Position of the light, in pixels of the framebuffer the lighting is applied to.
+    pub position: Vec2,
+
+    /// Distance at which the light has fully fallen off, in pixels.
+    pub radius: f32,
+
+    /// Color of the light.
+    pub color: Color,
+
+    /// Multiplier of the light's color.
+    pub intensity: f32,
+}
+
+impl PointLight {
+    /// Creates a light with an intensity of 1.
+    pub fn new(position: Vec2, radius: f32, color: Color) -> Self {
+        Self {
+            position,
+            radius,
+            color,
+            intensity: 1.0,
+        }
+    }
+}
+
+/// Ambient color over the time of day, as keyframes that are blended between.
+#[derive(Clone, PartialEq, Debug)]
+pub struct AmbientCurve {
+    keyframes: Vec<(f32, Color, f32)>,
+}
+
+impl AmbientCurve {
+    /// Creates a curve from keyframes of time of day from 0 to 1, ambient color and intensity.
+    ///
+    /// The curve wraps around, such that the last keyframe blends into the first.
+    pub fn new(mut keyframes: Vec<(f32, Color, f32)>) -> Self {
+        keyframes.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self { keyframes }
+    }
+
+    /// Creates a curve going from a dark blue night through an orange dawn to a white day and back, where 0 is midnight and 0.5 is noon.
+    pub fn day_night() -> Self {
+        Self::new(vec![
+            (0.0, Color::new(0x30, 0x40, 0x80, 0xff), 0.35),
+            (0.25, Color::new(0xff, 0xa0, 0x70, 0xff), 0.8),
+            (0.5, Color::new(0xff, 0xff, 0xff, 0xff), 1.0),
+            (0.75, Color::new(0xff, 0x90, 0x60, 0xff), 0.8),
+        ])
+    }
+
+    /// Gets the ambient color and intensity at the given time of day, which wraps around at 1.
+    pub fn sample(&self, time: f32) -> (Color, f32) {
+        let (Some(first), Some(last)) = (self.keyframes.first(), self.keyframes.last()) else {
+            return (Color::new(0xff, 0xff, 0xff, 0xff), 1.0);
+        };
+        let time = time.rem_euclid(1.0);
+
+        // Find the keyframes around the time, wrapping around midnight.
+        let next = self
+            .keyframes
+            .iter()
+            .position(|(t, _, _)| *t > time)
+            .unwrap_or(0);
+        let (prev, next) = if next == 0 {
+            (last, first)
+        } else {
+            (&self.keyframes[next - 1], &self.keyframes[next])
+        };
+        let span = (next.0 - prev.0).rem_euclid(1.0);
+        let t = if span > 0.0 {
+            (time - prev.0).rem_euclid(1.0) / span
+        } else {
+            0.0
+        };
+        (prev.1.lerp_oklab(next.1, t), prev.2 + (next.2 - prev.2) * t)
+    }
+}
+
+/// Ambient light and point lights applied to a scene.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Lighting {
+    /// Color of light everywhere in the scene.
+    pub ambient: Color,
+
+    /// Multiplier of the ambient color.
+    pub ambient_intensity: f32,
+
+    /// Point lights added on top of the ambient light. Only the first [`MAX_LIGHTS`] are used.
+    pub lights: Vec<PointLight>,
+}
+
+impl Default for Lighting {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Lighting {
+    /// Creates lighting with white ambient light and no point lights, which leaves the scene unchanged.
+    pub fn new() -> Self {
+        Self {
+            ambient: Color::new(0xff, 0xff, 0xff, 0xff),
+            ambient_intensity: 1.0,
+            lights: vec![],
+        }
+    }
+
+    /// Sets the ambient light from a curve at the given time of day.
+    pub fn set_ambient_from(&mut self, curve: &AmbientCurve, time: f32) {
+        (self.ambient, self.ambient_intensity) = curve.sample(time);
+    }
+}
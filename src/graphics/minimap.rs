@@ -0,0 +1,95 @@
+//! Minimaps and picture-in-picture views.
+//!
+//! A [`Minimap`] renders part of the world into a small framebuffer with its own camera, which is then drawn like any other texture:
+//!
+//! ```ignore
+//! self.minimap.camera.position = self.player.pos;
+//! let minimap = self.minimap.render(ctxt.gfx, |canvas, transform| {
+//!     for wall in self.walls.iter() {
+//!         canvas.draw(wall_pixel, transform * math::translate(wall.x, wall.y));
+//!     }
+//!     canvas.draw(player_marker, transform * math::translate(self.player.pos.x, self.player.pos.y));
+//! });
+//! canvas.draw(minimap, math::translate(16.0, 16.0));
+//! ```
+//!
+//! Small versions of sprites usually read poorly at minimap scale, so drawing simplified shapes or a pixel per tile tends to look better.
+
+use super::camera::Camera2D;
+use super::{Canvas, DeviceId, Framebuffer, Graphics, TextureSlice};
+use crate::math::{Affine2, UVec2, Vec2};
+
+/// A view of the world rendered to its own texture.
+pub struct Minimap {
+    /// Camera the view is rendered with. Its viewport is kept at the size of the minimap.
+    pub camera: Camera2D,
+
+    size: UVec2,
+
+    /// Framebuffer rendered to, keyed by the device and its size.
+    framebuffer: Option<((DeviceId, UVec2), Framebuffer)>,
+}
+
+impl Minimap {
+    /// Creates a minimap of the given size in pixels, showing the given number of pixels per world unit.
+    pub fn new(size: UVec2, zoom: f32) -> Self {
+        let mut camera = Camera2D::new(size.as_vec2());
+        camera.zoom = zoom;
+        Self {
+            camera,
+            size,
+            framebuffer: None,
+        }
+    }
+
+    /// Gets the size of the minimap in pixels.
+    pub fn size(&self) -> UVec2 {
+        self.size
+    }
+
+    /// Sets the size of the minimap in pixels, e.g. when the window is resized.
+    pub fn set_size(&mut self, size: UVec2) {
+        self.size = size;
+    }
+
+    /// Converts a position on the minimap to world coordinates, e.g. for clicking on the minimap.
+    pub fn minimap_to_world(&self, pos: Vec2) -> Vec2 {
+        self.camera().screen_to_world(pos)
+    }
+
+    /// Converts a position in world coordinates to a position on the minimap, e.g. for placing markers around it.
+    pub fn world_to_minimap(&self, pos: Vec2) -> Vec2 {
+        self.camera().world_to_screen(pos)
+    }
+
+    /// Gets the camera with its viewport matching the minimap.
+    fn camera(&self) -> Camera2D {
+        Camera2D {
+            viewport: self.size.as_vec2(),
+            ..self.camera
+        }
+    }
+
+    /// Renders the view, drawn by the given function with the transform from world coordinates to minimap pixels.
+    ///
+    /// The view is cleared to black first. Call this once per frame, before drawing the returned texture.
+    pub fn render<'s, 'a>(
+        &'s mut self,
+        gfx: &mut Graphics,
+        draw: impl FnOnce(&mut Canvas<'a>, Affine2),
+    ) -> TextureSlice<'s> {
+        let size = self.size.max(UVec2::ONE);
+        let key = (gfx.device_id(), size);
+        if !matches!(&self.framebuffer, Some((k, _)) if *k == key) {
+            self.framebuffer = Some((key, gfx.create_framebuffer(size)));
+        }
+        self.camera.viewport = size.as_vec2();
+
+        let mut canvas = Canvas::new();
+        draw(&mut canvas, self.camera.transform());
+
+        let (_, framebuffer) = self.framebuffer.as_ref().unwrap();
+        gfx.render_to_framebuffer(&canvas, framebuffer);
+        framebuffer.as_texture_slice()
+    }
+}
@@ -0,0 +1,92 @@
+//! Nine-patch images.
+//!
+//! A [`NinePatch`] stretches an image to any size while keeping its corners intact, e.g. for dialog boxes and buttons:
+//!
+//! ```ignore
+//! let panel = NinePatch::new(self.panel.get_or_load(ctxt.gfx).layer(0).unwrap(), 6);
+//! panel.draw(canvas, Rect::from_pos_size(Vec2::new(16.0, 16.0), Vec2::new(200.0, 80.0)));
+//! ```
+//!
+//! Nine-patches from an atlas should be packed with extrusion (see [`super::atlas`]), as their edges are stretched and would otherwise pick up neighboring sprites.
+
+use super::{Canvas, CanvasExt as _, TextureSlice};
+use crate::math::{IVec2, Rect, UVec2, Vec2};
+
+/// An image split into corners that are drawn as is, edges that are stretched along one axis and a center that is stretched along both.
+#[derive(Clone, Copy)]
+pub struct NinePatch<'a> {
+    /// The image.
+    pub texture: TextureSlice<'a>,
+
+    /// Sizes of the left, top, right and bottom edges, in pixels, in that order.
+    pub border: [u32; 4],
+}
+
+impl<'a> NinePatch<'a> {
+    /// Creates a nine-patch whose edges all have the same size.
+    pub fn new(texture: TextureSlice<'a>, border: u32) -> Self {
+        Self {
+            texture,
+            border: [border; 4],
+        }
+    }
+
+    /// Draws the nine-patch stretched over a rectangle.
+    ///
+    /// If the rectangle is smaller than the borders, the borders are shrunk to fit.
+    pub fn draw(&self, canvas: &mut Canvas<'a>, rect: Rect) {
+        let [left, top, right, bottom] = self.border;
+        let size = self.texture.size();
+        let (left, right) = (left.min(size.x), right.min(size.x - left.min(size.x)));
+        let (top, bottom) = (top.min(size.y), bottom.min(size.y - top.min(size.y)));
+
+        // Split points along each axis, in the texture and in the rectangle.
+        let src_x = [0, left, size.x - right, size.x];
+        let src_y = [0, top, size.y - bottom, size.y];
+        let fit = |start: u32, end: u32, available: f32| {
+            let total = (start + end) as f32;
+            let scale = if total > available {
+                available.max(0.0) / total
+            } else {
+                1.0
+            };
+            (start as f32 * scale, end as f32 * scale)
+        };
+        let (left_w, right_w) = fit(left, right, rect.width());
+        let (top_h, bottom_h) = fit(top, bottom, rect.height());
+        let dest_x = [
+            rect.min.x,
+            rect.min.x + left_w,
+            rect.max.x - right_w,
+            rect.max.x,
+        ];
+        let dest_y = [
+            rect.min.y,
+            rect.min.y + top_h,
+            rect.max.y - bottom_h,
+            rect.max.y,
+        ];
+
+        for y in 0..3 {
+            for x in 0..3 {
+                let src_size = UVec2::new(src_x[x + 1] - src_x[x], src_y[y + 1] - src_y[y]);
+                if src_size.x == 0 || src_size.y == 0 {
+                    continue;
+                }
+                let Some(slice) = self
+                    .texture
+                    .slice(IVec2::new(src_x[x] as i32, src_y[y] as i32), src_size)
+                else {
+                    continue;
+                };
+                canvas.draw_stretched(
+                    slice,
+                    Rect::from_min_max(
+                        Vec2::new(dest_x[x], dest_y[y]),
+                        Vec2::new(dest_x[x + 1], dest_y[y + 1]),
+                    ),
+                );
+            }
+        }
+    }
+}
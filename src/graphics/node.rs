@@ -0,0 +1,44 @@
+//! Hierarchical drawing.
+//!
+//! [`CanvasExt::child`](super::CanvasExt::child) creates a scoped sub-canvas whose draws are transformed by a parent transform, so nested hierarchies (e.g. a weapon attached to a hand attached to an arm) don't need to compose every transform by hand:
+//!
+//! ```ignore
+//! use teenygame::graphics::CanvasExt as _;
+//!
+//! let mut body = canvas.child(math::translate(x, y));
+//! body.draw(torso, Affine2::IDENTITY);
+//! let mut arm = body.child(math::translate(12.0, 4.0) * math::rotate(arm_angle));
+//! arm.draw(upper_arm, Affine2::IDENTITY);
+//! arm.child(math::translate(0.0, 16.0)).draw(sword, Affine2::IDENTITY);
+//! ```
+
+use super::{Canvas, Drawable};
+use crate::math::Affine2;
+
+/// A sub-canvas whose draws are transformed by a parent transform.
+///
+/// Created by [`CanvasExt::child`](super::CanvasExt::child).
+pub struct ChildCanvas<'c, 'a> {
+    pub(super) canvas: &'c mut Canvas<'a>,
+    pub(super) transform: Affine2,
+}
+
+impl<'a> ChildCanvas<'_, 'a> {
+    /// Gets the transform applied to all draws, i.e. the composition of all parent transforms.
+    pub fn transform(&self) -> Affine2 {
+        self.transform
+    }
+
+    /// Draws a drawable with the given transform, relative to this node.
+    pub fn draw(&mut self, drawable: impl Drawable<'a>, transform: Affine2) {
+        self.canvas.draw(drawable, self.transform * transform);
+    }
+
+    /// Creates a nested child whose draws are transformed by the given transform relative to this node.
+    pub fn child(&mut self, transform: Affine2) -> ChildCanvas<'_, 'a> {
+        ChildCanvas {
+            canvas: self.canvas,
+            transform: self.transform * transform,
+        }
+    }
+}
@@ -0,0 +1,93 @@
+//! Sprite outlines.
+//!
+//! Outlines are generated from a sprite's image by detecting the edges of its opaque pixels, then drawn behind the sprite, e.g. for highlighting selected units:
+//!
+//! ```ignore
+//! let outline = Outline::new(2, Color::new(0xff, 0xd7, 0x00, 0xff));
+//! self.unit_outline = Lazy::new(outline.image(&unit_img));
+//!
+//! // In draw:
+//! let unit = self.unit.get_or_load(ctxt.gfx).layer(0).unwrap();
+//! let unit_outline = self.unit_outline.get_or_load(ctxt.gfx).layer(0).unwrap();
+//! outline.draw(canvas, unit, unit_outline, transform);
+//! ```
+
+use super::{Canvas, Color, TextureSlice};
+use crate::image::{AsImgRef, Img};
+use crate::math::{self, Affine2, IVec2, UVec2};
+
+/// An outline around the opaque pixels of a sprite.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Outline {
+    /// Thickness of the outline, in pixels.
+    pub thickness: u32,
+
+    /// Color of the outline.
+    pub color: Color,
+
+    /// Minimum alpha for a pixel to count as part of the sprite.
+    pub threshold: u8,
+}
+
+impl Outline {
+    /// Creates an outline of the given thickness and color around pixels that are at least half opaque.
+    pub fn new(thickness: u32, color: Color) -> Self {
+        Self {
+            thickness,
+            color,
+            threshold: 0x80,
+        }
+    }
+
+    /// Creates an image of the outline around each layer of the given image.
+    ///
+    /// The outline image is larger than the given image by the thickness on each side, and contains only the outline, such that it may be drawn behind the sprite.
+    pub fn image(&self, img: impl AsImgRef<Color>) -> Img<Vec<Color>> {
+        let img: Img<&[Color]> = img.as_ref();
+        let src_size = img.size();
+        let t = self.thickness as i32;
+        let size = src_size + UVec2::splat(self.thickness * 2);
+        let transparent = Color::new(0, 0, 0, 0);
+
+        let is_opaque = |layer: u32, pos: IVec2| {
+            if pos.x < 0 || pos.y < 0 || pos.x >= src_size.x as i32 || pos.y >= src_size.y as i32 {
+                return false;
+            }
+            let i = ((layer * src_size.y + pos.y as u32) * src_size.x + pos.x as u32) as usize;
+            img.as_buf()[i].a >= self.threshold
+        };
+
+        let mut pixels = Vec::with_capacity((size.x * size.y * img.layers()) as usize);
+        for layer in 0..img.layers() {
+            for y in 0..size.y {
+                for x in 0..size.x {
+                    let src = math::ivec2(x as i32 - t, y as i32 - t);
+                    let is_outline = !is_opaque(layer, src)
+                        && (-t..=t).any(|dy| {
+                            (-t..=t).any(|dx| {
+                                dx * dx + dy * dy <= t * t
+                                    && is_opaque(layer, src + math::ivec2(dx, dy))
+                            })
+                        });
+                    pixels.push(if is_outline { self.color } else { transparent });
+                }
+            }
+        }
+        Img::new(pixels, size, img.layers())
+    }
+
+    /// Draws a sprite with its outline behind it.
+    ///
+    /// `outline` must be the sprite's outline image created by [`Outline::image`] with this outline's thickness. The transform is that of the sprite, as if it were drawn on its own.
+    pub fn draw<'a>(
+        &self,
+        canvas: &mut Canvas<'a>,
+        sprite: TextureSlice<'a>,
+        outline: TextureSlice<'a>,
+        transform: Affine2,
+    ) {
+        let offset = -(self.thickness as f32);
+        canvas.draw(outline, transform * math::translate(offset, offset));
+        canvas.draw(sprite, transform);
+    }
+}
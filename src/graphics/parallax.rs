@@ -0,0 +1,132 @@
+//! Parallax backgrounds.
+//!
+//! [`ParallaxLayers`] draws background layers that scroll slower than the world as a [`Camera2D`] moves, giving an illusion of depth:
+//!
+//! ```ignore
+//! let mut layers = ParallaxLayers::new();
+//! layers.push(sky, ParallaxLayer::new(Vec2::ZERO));
+//! layers.push(mountains, ParallaxLayer::new(Vec2::new(0.25, 0.0)).repeat_x());
+//! layers.push(trees, ParallaxLayer::new(Vec2::new(0.5, 0.5)).repeat_x());
+//! layers.draw(canvas, &self.camera);
+//! ```
+
+use super::camera::Camera2D;
+use super::{Canvas, Drawable as _, TextureSlice};
+use crate::math::{Affine2, Vec2};
+
+/// How a background layer scrolls.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ParallaxLayer {
+    /// How far the layer scrolls relative to the camera on each axis, where 0 is fixed in place and 1 moves with the world.
+    pub factor: Vec2,
+
+    /// Position of the layer's texture in world units, before scrolling.
+    pub offset: Vec2,
+
+    /// Whether the texture is tiled infinitely horizontally.
+    pub repeat_x: bool,
+
+    /// Whether the texture is tiled infinitely vertically.
+    pub repeat_y: bool,
+}
+
+impl ParallaxLayer {
+    /// Creates a layer with the given scroll factor that is not tiled.
+    pub fn new(factor: Vec2) -> Self {
+        Self {
+            factor,
+            offset: Vec2::ZERO,
+            repeat_x: false,
+            repeat_y: false,
+        }
+    }
+
+    /// Sets the position of the layer's texture.
+    pub fn with_offset(mut self, offset: Vec2) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Tiles the layer infinitely horizontally.
+    pub fn repeat_x(mut self) -> Self {
+        self.repeat_x = true;
+        self
+    }
+
+    /// Tiles the layer infinitely vertically.
+    pub fn repeat_y(mut self) -> Self {
+        self.repeat_y = true;
+        self
+    }
+}
+
+/// A stack of background layers, drawn back to front.
+pub struct ParallaxLayers<'a> {
+    layers: Vec<(TextureSlice<'a>, ParallaxLayer)>,
+}
+
+impl Default for ParallaxLayers<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> ParallaxLayers<'a> {
+    /// Creates an empty stack of layers.
+    pub fn new() -> Self {
+        Self { layers: vec![] }
+    }
+
+    /// Adds a layer in front of all previously added layers.
+    pub fn push(&mut self, texture: TextureSlice<'a>, layer: ParallaxLayer) {
+        self.layers.push((texture, layer));
+    }
+
+    /// Draws all layers as seen by the given camera.
+    pub fn draw(&self, canvas: &mut Canvas<'a>, camera: &Camera2D) {
+        for (texture, layer) in self.layers.iter() {
+            let camera = Camera2D {
+                position: camera.position * layer.factor,
+                ..*camera
+            };
+            let transform = camera.transform();
+            let size = texture.size().as_vec2();
+            let visible = camera.visible_rect();
+
+            // Find the range of tiles covering the visible area on each axis.
+            let range = |repeat: bool, min: f32, max: f32, offset: f32, size: f32| {
+                if repeat && size > 0.0 {
+                    ((min - offset) / size).floor() as i32..((max - offset) / size).ceil() as i32
+                } else {
+                    0..1
+                }
+            };
+            let xs = range(
+                layer.repeat_x,
+                visible.min.x,
+                visible.max.x,
+                layer.offset.x,
+                size.x,
+            );
+            let ys = range(
+                layer.repeat_y,
+                visible.min.y,
+                visible.max.y,
+                layer.offset.y,
+                size.y,
+            );
+
+            for y in ys {
+                for x in xs.clone() {
+                    canvas.draw(
+                        *texture,
+                        transform
+                            * Affine2::from_translation(
+                                layer.offset + Vec2::new(x as f32, y as f32) * size,
+                            ),
+                    );
+                }
+            }
+        }
+    }
+}
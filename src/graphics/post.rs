@@ -0,0 +1,717 @@
+//! Post-processing effects.
+//!
+//! A [`PostChain`] applies a sequence of full-screen effects to a framebuffer the scene was rendered to, e.g. for a retro look:
+//!
+//! ```ignore
+//! self.post.effects = vec![
+//!     PostEffect::Pixelate { block_size: 4 },
+//!     PostEffect::Dither { palette: GAME_BOY_PALETTE.to_vec(), spread: 0.25 },
+//!     PostEffect::Scanlines { intensity: 0.3, spacing: 2 },
+//! ];
+//!
+//! // In draw:
+//! ctxt.gfx.render_to_framebuffer(&scene_canvas, &self.scene);
+//! canvas.draw(self.post.apply(ctxt.gfx, &self.scene), Affine2::IDENTITY);
+//! ```
+//!
+//! Effects may be changed every frame, e.g. to ramp up pixelation during a transition.
+//!
+//! An [`Upscaler`] renders a scene at a fraction of the window's resolution and scales it up, which is useful as a performance option for weak GPUs and high-DPI screens:
+//!
+//! ```ignore
+//! self.upscaler.scale = 0.5;
+//!
+//! // In draw:
+//! let mut scene = Canvas::new();
+//! draw_scene(&mut scene.child(self.upscaler.transform(ctxt.gfx)));
+//! canvas.draw(self.upscaler.render(ctxt.gfx, &scene), Affine2::IDENTITY);
+//! ```
+//!
+//! Custom effects are written in WGSL as a function `fn effect(pos: vec2<f32>) -> vec4<f32>` returning the color at a pixel position. They may call `sample(pos)` to read the previous pass, and read their parameters from `params.palette` and per-frame values from `frame`, which is filled from [`FrameUniforms`] without being passed to each effect:
+//!
+//! ```ignore
+//! self.wave = PostShader::new(r#"
+//!     fn effect(pos: vec2<f32>) -> vec4<f32> {
+//!         return sample(pos + vec2<f32>(sin(pos.y / 8.0 + frame.time * 4.0) * params.palette[0].x, 0.0));
+//!     }
+//! "#);
+//! self.post.effects = vec![PostEffect::Custom { shader: self.wave.clone(), params: vec![[3.0, 0.0, 0.0, 0.0]] }];
+//!
+//! // In draw:
+//! ctxt.gfx.frame_uniforms().set_camera(&self.camera);
+//! ```
+
+use super::camera::Camera2D;
+use super::color::srgb_to_linear;
+use super::lighting::{Lighting, MAX_LIGHTS};
+use super::{Canvas, Color, DeviceId, Framebuffer, Graphics, TextureSlice};
+use crate::math::{Affine2, Mat3, UVec2, Vec2};
+use std::sync::Arc;
+
+/// Maximum number of colors in a [`PostEffect::Dither`] palette.
+pub const MAX_PALETTE_LEN: usize = 16;
+
+/// A full-screen effect.
+#[derive(Clone, PartialEq, Debug)]
+pub enum PostEffect {
+    /// Reduces colors to the given palette with ordered dithering.
+    ///
+    /// `spread` controls how strongly the dither pattern offsets colors before the nearest palette color is picked, where 0 disables dithering. Only the first [`MAX_PALETTE_LEN`] colors of the palette are used.
+    Dither { palette: Vec<Color>, spread: f32 },
+
+    /// Pixelates into square blocks of the given size in pixels.
+    Pixelate { block_size: u32 },
+
+    /// Darkens alternating bands of rows of the given height in pixels, like a CRT.
+    ///
+    /// `intensity` ranges from 0 (no effect) to 1 (black bands).
+    Scanlines { intensity: f32, spacing: u32 },
+
+    /// Multiplies the scene by ambient light and point lights.
+    Lighting(Lighting),
+
+    /// Shows the scene as seen with a color vision deficiency, for testing that it stays readable.
+    ///
+    /// `severity` ranges from 0 (no effect) to 1 (complete loss of the affected cone type).
+    SimulateColorBlindness { kind: ColorBlindness, severity: f32 },
+
+    /// Shifts colors that are hard to tell apart with a color vision deficiency towards colors that are easier to tell apart.
+    ///
+    /// `strength` ranges from 0 (no effect) to 1.
+    Daltonize { kind: ColorBlindness, strength: f32 },
+
+    /// Runs a custom shader, with parameters available to it as `params.palette`.
+    ///
+    /// Only the first [`MAX_PALETTE_LEN`] parameters are used.
+    Custom {
+        shader: PostShader,
+        params: Vec<[f32; 4]>,
+    },
+}
+
+/// A kind of color vision deficiency.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ColorBlindness {
+    /// Missing red cones.
+    Protanopia,
+
+    /// Missing green cones.
+    Deuteranopia,
+
+    /// Missing blue cones.
+    Tritanopia,
+}
+
+impl ColorBlindness {
+    /// Gets the matrix approximating how colors are seen, from Machado et al. (2009).
+    fn simulation(self) -> Mat3 {
+        let rows = match self {
+            ColorBlindness::Protanopia => [
+                0.152286, 1.052583, -0.204868, //
+                0.114503, 0.786281, 0.099216, //
+                -0.003882, -0.048116, 1.051998,
+            ],
+            ColorBlindness::Deuteranopia => [
+                0.367322, 0.860646, -0.227968, //
+                0.280085, 0.672501, 0.047413, //
+                -0.011820, 0.042940, 0.968881,
+            ],
+            ColorBlindness::Tritanopia => [
+                1.255528, -0.076749, -0.178779, //
+                -0.078411, 0.930809, 0.147602, //
+                0.004733, 0.691367, 0.303900,
+            ],
+        };
+        Mat3::from_cols_array(&rows).transpose()
+    }
+
+    /// Gets the matrix that adds the color information lost to the deficiency back into channels that are still seen.
+    fn daltonization(self) -> Mat3 {
+        let redistribute = match self {
+            ColorBlindness::Protanopia | ColorBlindness::Deuteranopia => [
+                0.0, 0.0, 0.0, //
+                0.7, 1.0, 0.0, //
+                0.7, 0.0, 1.0,
+            ],
+            ColorBlindness::Tritanopia => [
+                1.0, 0.0, 0.7, //
+                0.0, 1.0, 0.7, //
+                0.0, 0.0, 0.0,
+            ],
+        };
+        Mat3::IDENTITY
+            + Mat3::from_cols_array(&redistribute).transpose()
+                * (Mat3::IDENTITY - self.simulation())
+    }
+}
+
+/// Blends a color matrix with the identity, such that 0 has no effect.
+fn blend_matrix(matrix: Mat3, amount: f32) -> Mat3 {
+    let amount = amount.clamp(0.0, 1.0);
+    Mat3::IDENTITY * (1.0 - amount) + matrix * amount
+}
+
+/// WGSL source of a custom post effect.
+///
+/// Shaders are compiled when first applied, and cloning a shader shares the compiled pipeline.
+#[derive(Clone, Debug)]
+pub struct PostShader(Arc<str>);
+
+impl PostShader {
+    /// Creates a shader from WGSL source defining `fn effect(pos: vec2<f32>) -> vec4<f32>`.
+    pub fn new(source: &str) -> Self {
+        Self(source.into())
+    }
+}
+
+impl PartialEq for PostShader {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// Values available to all post effects of a frame as `frame`, e.g. for animating custom effects.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct FrameUniforms {
+    /// Seconds of game time since the game started, as `frame.time`.
+    ///
+    /// This is advanced automatically each frame, and follows [`crate::time::Stepping`].
+    pub time: f32,
+
+    /// Seconds of game time since the last frame, as `frame.delta_time`. This is set automatically.
+    pub delta_time: f32,
+
+    /// Position of the camera, as `frame.camera.xy`.
+    pub camera_position: Vec2,
+
+    /// Zoom of the camera, as `frame.camera.z`.
+    pub camera_zoom: f32,
+
+    /// Rotation of the camera in radians, as `frame.camera.w`.
+    pub camera_rotation: f32,
+
+    /// Game-specific values, as `frame.params[0]` and `frame.params[1]`.
+    pub params: [f32; 8],
+}
+
+impl FrameUniforms {
+    /// Sets the camera values from a camera.
+    pub fn set_camera(&mut self, camera: &Camera2D) {
+        self.camera_position = camera.position;
+        self.camera_zoom = camera.zoom;
+        self.camera_rotation = camera.rotation;
+    }
+
+    /// Advances time by the given frame time.
+    pub(crate) fn advance(&mut self, frame_time: std::time::Duration) {
+        self.delta_time = frame_time.as_secs_f32();
+        self.time += self.delta_time;
+    }
+
+    /// Packs the values into the shader's uniform layout.
+    fn params(&self) -> [f32; 16] {
+        let mut params = [0.0; 16];
+        params[0] = self.time;
+        params[1] = self.delta_time;
+        params[4..8].copy_from_slice(&[
+            self.camera_position.x,
+            self.camera_position.y,
+            self.camera_zoom,
+            self.camera_rotation,
+        ]);
+        params[8..].copy_from_slice(&self.params);
+        params
+    }
+}
+
+/// Packs a color scaled by a multiplier into floats for the shader.
+///
+/// Shaders see sRGB targets in linear space, so the color channels are linearized to match when
+/// `srgb` is set. Alpha is always linear.
+fn color_params(color: Color, intensity: f32, srgb: bool) -> [u32; 4] {
+    let channel = |c: u8| {
+        if srgb {
+            srgb_to_linear(c)
+        } else {
+            c as f32 / 255.0
+        }
+    };
+    [
+        channel(color.r) * intensity,
+        channel(color.g) * intensity,
+        channel(color.b) * intensity,
+        color.a as f32 / 255.0 * intensity,
+    ]
+    .map(f32::to_bits)
+}
+
+/// Packs a color matrix into the first three palette slots, one column per slot.
+fn color_matrix_params(params: &mut [u32; 8 + MAX_PALETTE_LEN * 4], matrix: Mat3) {
+    for (i, column) in [matrix.x_axis, matrix.y_axis, matrix.z_axis]
+        .into_iter()
+        .enumerate()
+    {
+        params[8 + i * 4..][..3].copy_from_slice(&column.to_array().map(f32::to_bits));
+    }
+}
+
+impl PostEffect {
+    /// Packs the effect into the shader's uniform layout, for a target that is sRGB if `srgb`.
+    fn params(&self, size: UVec2, srgb: bool) -> [u32; 8 + MAX_PALETTE_LEN * 4] {
+        let mut params = [0u32; 8 + MAX_PALETTE_LEN * 4];
+        params[0] = (size.x as f32).to_bits();
+        params[1] = (size.y as f32).to_bits();
+        match self {
+            PostEffect::Dither { palette, spread } => {
+                let palette = &palette[..palette.len().min(MAX_PALETTE_LEN)];
+                params[2] = 0;
+                params[3] = palette.len() as u32;
+                params[5] = spread.to_bits();
+                for (i, color) in palette.iter().enumerate() {
+                    params[8 + i * 4..][..4].copy_from_slice(&color_params(*color, 1.0, srgb));
+                }
+            }
+            PostEffect::Pixelate { block_size } => {
+                params[2] = 1;
+                params[4] = (*block_size as f32).to_bits();
+            }
+            PostEffect::Scanlines { intensity, spacing } => {
+                params[2] = 2;
+                params[5] = intensity.to_bits();
+                params[6] = (*spacing as f32).to_bits();
+            }
+            PostEffect::Lighting(lighting) => {
+                // The ambient light takes the first palette slot, followed by two slots per light.
+                let lights = &lighting.lights[..lighting.lights.len().min(MAX_LIGHTS)];
+                params[2] = 3;
+                params[3] = lights.len() as u32;
+                params[8..][..4].copy_from_slice(&color_params(
+                    lighting.ambient,
+                    lighting.ambient_intensity,
+                    srgb,
+                ));
+                for (i, light) in lights.iter().enumerate() {
+                    let base = 8 + (1 + i * 2) * 4;
+                    params[base..][..4].copy_from_slice(
+                        &[
+                            light.position.x,
+                            light.position.y,
+                            light.radius,
+                            light.intensity,
+                        ]
+                        .map(f32::to_bits),
+                    );
+                    params[base + 4..][..4].copy_from_slice(&color_params(light.color, 1.0, srgb));
+                }
+            }
+            PostEffect::SimulateColorBlindness { kind, severity } => {
+                params[2] = 4;
+                color_matrix_params(&mut params, blend_matrix(kind.simulation(), *severity));
+            }
+            PostEffect::Daltonize { kind, strength } => {
+                params[2] = 4;
+                color_matrix_params(&mut params, blend_matrix(kind.daltonization(), *strength));
+            }
+            PostEffect::Custom { params: custom, .. } => {
+                let custom = &custom[..custom.len().min(MAX_PALETTE_LEN)];
+                params[3] = custom.len() as u32;
+                for (i, values) in custom.iter().enumerate() {
+                    params[8 + i * 4..][..4].copy_from_slice(&values.map(f32::to_bits));
+                }
+            }
+        }
+        params
+    }
+}
+
+struct Pipeline {
+    device_id: DeviceId,
+    format: wgpu::TextureFormat,
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    uniforms: wgpu::Buffer,
+    frame_uniforms: wgpu::Buffer,
+}
+
+impl Pipeline {
+    fn new(
+        device: &wgpu::Device,
+        device_id: DeviceId,
+        format: wgpu::TextureFormat,
+        source: &str,
+    ) -> Self {
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("teenygame: post"),
+            source: wgpu::ShaderSource::Wgsl(
+                format!("{}\n{source}", include_str!("post_common.wgsl")).into(),
+            ),
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("teenygame: post"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("teenygame: post"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("teenygame: post"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &module,
+                entry_point: Some("vs_main"),
+                compilation_options: Default::default(),
+                buffers: &[],
+            },
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &module,
+                entry_point: Some("fs_main"),
+                compilation_options: Default::default(),
+                targets: &[Some(format.into())],
+            }),
+            multiview: None,
+            cache: None,
+        });
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("teenygame: post"),
+            ..Default::default()
+        });
+
+        let uniforms = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("teenygame: post"),
+            size: std::mem::size_of::<[u32; 8 + MAX_PALETTE_LEN * 4]>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let frame_uniforms = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("teenygame: post frame"),
+            size: std::mem::size_of::<[f32; 16]>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            device_id,
+            format,
+            pipeline,
+            bind_group_layout,
+            sampler,
+            uniforms,
+            frame_uniforms,
+        }
+    }
+
+    /// Renders a full-screen pass from one framebuffer to another.
+    fn run(
+        &self,
+        gfx: &Graphics,
+        params: &[u32; 8 + MAX_PALETTE_LEN * 4],
+        frame_params: &[f32; 16],
+        src: &Framebuffer,
+        dest: &Framebuffer,
+    ) {
+        let device = &gfx.device;
+        let queue = &gfx.queue;
+
+        // Each pass is submitted separately, such that it sees its own uniforms.
+        queue.write_buffer(&self.uniforms, 0, bytemuck::cast_slice(params));
+        queue.write_buffer(&self.frame_uniforms, 0, bytemuck::cast_slice(frame_params));
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("teenygame: post"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&src.view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: self.uniforms.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: self.frame_uniforms.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("teenygame: post"),
+        });
+        {
+            let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &dest.view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                ..Default::default()
+            });
+            rpass.set_pipeline(&self.pipeline);
+            rpass.set_bind_group(0, &bind_group, &[]);
+            rpass.draw(0..3, 0..1);
+        }
+        queue.submit(Some(encoder.finish()));
+    }
+}
+
+/// A sequence of [`PostEffect`]s applied in order.
+pub struct PostChain {
+    /// The effects to apply, in order.
+    pub effects: Vec<PostEffect>,
+
+    pipeline: Option<Pipeline>,
+
+    /// Pipelines of custom shaders, compiled when first applied.
+    custom_pipelines: Vec<(PostShader, Pipeline)>,
+
+    /// Framebuffers that passes alternate between rendering to, keyed by their size.
+    targets: Option<(UVec2, [Framebuffer; 2])>,
+}
+
+impl Default for PostChain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PostChain {
+    /// Creates an empty chain.
+    pub fn new() -> Self {
+        Self {
+            effects: vec![],
+            pipeline: None,
+            custom_pipelines: vec![],
+            targets: None,
+        }
+    }
+
+    /// Applies all effects to the given framebuffer, returning the result.
+    ///
+    /// If there are no effects, the framebuffer itself is returned.
+    pub fn apply<'s>(&'s mut self, gfx: &mut Graphics, input: &'s Framebuffer) -> TextureSlice<'s> {
+        if self.effects.is_empty() {
+            return input.as_texture_slice();
+        }
+
+        let device = &gfx.device;
+        let device_id = gfx.device_id();
+        let format = input.texture.format();
+        let size = input.texture.size();
+        let size = UVec2::new(size.width, size.height);
+
+        if !matches!(&self.pipeline, Some(p) if p.device_id == device_id && p.format == format) {
+            self.pipeline = Some(Pipeline::new(
+                device,
+                device_id,
+                format,
+                include_str!("post.wgsl"),
+            ));
+            self.custom_pipelines.clear();
+            self.targets = None;
+        }
+
+        // Compile newly used custom shaders, and drop those no longer used.
+        self.custom_pipelines.retain(|(shader, _)| {
+            self.effects
+                .iter()
+                .any(|effect| matches!(effect, PostEffect::Custom { shader: s, .. } if s == shader))
+        });
+        for effect in self.effects.iter() {
+            if let PostEffect::Custom { shader, .. } = effect {
+                if !self.custom_pipelines.iter().any(|(s, _)| s == shader) {
+                    let source = format!("{}\n{}", include_str!("post_custom.wgsl"), shader.0);
+                    self.custom_pipelines.push((
+                        shader.clone(),
+                        Pipeline::new(device, device_id, format, &source),
+                    ));
+                }
+            }
+        }
+        let frame_params = gfx.frame_uniforms.params();
+
+        if !matches!(&self.targets, Some((s, _)) if *s == size) {
+            self.targets = Some((
+                size,
+                [gfx.create_framebuffer(size), gfx.create_framebuffer(size)],
+            ));
+        }
+        let (_, targets) = self.targets.as_ref().unwrap();
+        let srgb = format.is_srgb();
+
+        for (i, effect) in self.effects.iter().enumerate() {
+            let src = if i == 0 { input } else { &targets[(i - 1) % 2] };
+            let dest = &targets[i % 2];
+            let pipeline = match effect {
+                PostEffect::Custom { shader, .. } => self
+                    .custom_pipelines
+                    .iter()
+                    .find(|(s, _)| s == shader)
+                    .map(|(_, pipeline)| pipeline)
+                    .unwrap(),
+                _ => self.pipeline.as_ref().unwrap(),
+            };
+
+            pipeline.run(gfx, &effect.params(size, srgb), &frame_params, src, dest);
+        }
+
+        targets[(self.effects.len() - 1) % 2].as_texture_slice()
+    }
+}
+
+/// How an [`Upscaler`] fills in pixels between those it rendered.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum UpscaleFilter {
+    /// Repeats pixels, which keeps edges crisp but blocky.
+    Nearest,
+
+    /// Blends between neighboring pixels, which is smooth but blurry.
+    Bilinear,
+
+    /// Blends between neighboring pixels, then restores edges by increasing local contrast.
+    ///
+    /// `sharpness` ranges from 0 (same as [`UpscaleFilter::Bilinear`]) to 1.
+    Sharpen { sharpness: f32 },
+}
+
+/// Renders scenes at a lower resolution than the window and scales them up to it.
+pub struct Upscaler {
+    /// Fraction of the window's resolution to render at, from 0 to 1.
+    pub scale: f32,
+
+    /// Filter to scale up with.
+    pub filter: UpscaleFilter,
+
+    pipeline: Option<Pipeline>,
+
+    /// Framebuffers to render to and scale up to, keyed by the device, the window size and the rendered size.
+    targets: Option<((DeviceId, UVec2, UVec2), [Framebuffer; 2])>,
+}
+
+impl Upscaler {
+    /// Creates an upscaler rendering at the given fraction of the window's resolution.
+    pub fn new(scale: f32, filter: UpscaleFilter) -> Self {
+        Self {
+            scale,
+            filter,
+            pipeline: None,
+            targets: None,
+        }
+    }
+
+    /// Gets the resolution scenes are rendered at for the given window size.
+    pub fn render_size(&self, window_size: UVec2) -> UVec2 {
+        (window_size.as_vec2() * self.scale.clamp(0.0, 1.0))
+            .ceil()
+            .as_uvec2()
+            .max(UVec2::ONE)
+    }
+
+    /// Gets the transform from window pixels to rendered pixels, which scenes should be drawn with such that they may be drawn as if at full resolution.
+    pub fn transform(&self, gfx: &Graphics) -> Affine2 {
+        let window_size = gfx.window().size().max(UVec2::ONE);
+        Affine2::from_scale(self.render_size(window_size).as_vec2() / window_size.as_vec2())
+    }
+
+    /// Renders a canvas at the lower resolution, returning it scaled up to the window's resolution.
+    pub fn render<'s>(&'s mut self, gfx: &mut Graphics, canvas: &Canvas) -> TextureSlice<'s> {
+        let window_size = gfx.window().size().max(UVec2::ONE);
+        let device_id = gfx.device_id();
+
+        let render_size = self.render_size(window_size);
+        let key = (device_id, window_size, render_size);
+        if !matches!(&self.targets, Some((k, _)) if *k == key) {
+            self.targets = Some((
+                key,
+                [
+                    gfx.create_framebuffer(render_size),
+                    gfx.create_framebuffer(window_size),
+                ],
+            ));
+        }
+        let (_, [low, high]) = self.targets.as_ref().unwrap();
+
+        let format = high.texture.format();
+        if !matches!(&self.pipeline, Some(p) if p.device_id == device_id && p.format == format) {
+            self.pipeline = Some(Pipeline::new(
+                &gfx.device,
+                device_id,
+                format,
+                include_str!("upscale.wgsl"),
+            ));
+        }
+        let pipeline = self.pipeline.as_ref().unwrap();
+
+        gfx.render_to_framebuffer(canvas, low);
+
+        let mut params = [0u32; 8 + MAX_PALETTE_LEN * 4];
+        params[0] = (window_size.x as f32).to_bits();
+        params[1] = (window_size.y as f32).to_bits();
+        match self.filter {
+            UpscaleFilter::Nearest => params[2] = 0,
+            UpscaleFilter::Bilinear => params[2] = 1,
+            UpscaleFilter::Sharpen { sharpness } => {
+                params[2] = 2;
+                params[5] = sharpness.to_bits();
+            }
+        }
+        pipeline.run(gfx, &params, &gfx.frame_uniforms.params(), low, high);
+
+        high.as_texture_slice()
+    }
+}
@@ -0,0 +1,69 @@
+// Built-in post effects.
+
+const KIND_DITHER: u32 = 0u;
+const KIND_PIXELATE: u32 = 1u;
+const KIND_SCANLINES: u32 = 2u;
+const KIND_LIGHTING: u32 = 3u;
+const KIND_COLOR_MATRIX: u32 = 4u;
+
+// 4x4 Bayer matrix for ordered dithering.
+const BAYER: array<f32, 16> = array<f32, 16>(
+    0.0, 8.0, 2.0, 10.0,
+    12.0, 4.0, 14.0, 6.0,
+    3.0, 11.0, 1.0, 9.0,
+    15.0, 7.0, 13.0, 5.0,
+);
+
+@fragment
+fn fs_main(@builtin(position) frag_pos: vec4<f32>) -> @location(0) vec4<f32> {
+    let pos = frag_pos.xy;
+    switch params.kind {
+        case KIND_DITHER: {
+            let color = sample(pos);
+            var bayer = BAYER;
+            let cell = vec2<u32>(pos) % 4u;
+            let offset = ((bayer[cell.y * 4u + cell.x] + 0.5) / 16.0 - 0.5) * params.intensity;
+            let target_color = color.rgb + offset;
+
+            var best = color.rgb;
+            var best_distance = 1e9;
+            for (var i = 0u; i < params.palette_len; i++) {
+                let d = distance(target_color, params.palette[i].rgb);
+                if d < best_distance {
+                    best_distance = d;
+                    best = params.palette[i].rgb;
+                }
+            }
+            return vec4<f32>(best, color.a);
+        }
+        case KIND_PIXELATE: {
+            let block_size = max(params.block_size, 1.0);
+            return sample((floor(pos / block_size) + 0.5) * block_size);
+        }
+        case KIND_SCANLINES: {
+            let color = sample(pos);
+            let dark = floor(pos.y / max(params.spacing, 1.0)) % 2.0;
+            return vec4<f32>(color.rgb * (1.0 - params.intensity * dark), color.a);
+        }
+        case KIND_LIGHTING: {
+            // The palette holds the ambient light, followed by the position, radius, intensity and color of each light.
+            let color = sample(pos);
+            var light = params.palette[0].rgb;
+            for (var i = 0u; i < params.palette_len; i++) {
+                let shape = params.palette[1u + i * 2u];
+                let falloff = clamp(1.0 - distance(pos, shape.xy) / max(shape.z, 1.0), 0.0, 1.0);
+                light += params.palette[2u + i * 2u].rgb * shape.w * falloff * falloff;
+            }
+            return vec4<f32>(color.rgb * light, color.a);
+        }
+        case KIND_COLOR_MATRIX: {
+            // The palette holds the columns of the matrix.
+            let color = sample(pos);
+            let matrix = mat3x3<f32>(params.palette[0].xyz, params.palette[1].xyz, params.palette[2].xyz);
+            return vec4<f32>(clamp(matrix * color.rgb, vec3<f32>(0.0), vec3<f32>(1.0)), color.a);
+        }
+        default: {
+            return sample(pos);
+        }
+    }
+}
@@ -0,0 +1,37 @@
+// Shared by all post effects, which are applied as a full-screen triangle sampling the previous pass.
+
+struct Params {
+    size: vec2<f32>,
+    kind: u32,
+    palette_len: u32,
+    block_size: f32,
+    intensity: f32,
+    spacing: f32,
+    _padding: f32,
+    palette: array<vec4<f32>, 16>,
+}
+
+// Values shared by all passes of a frame.
+struct Frame {
+    time: f32,
+    delta_time: f32,
+    _padding: vec2<f32>,
+    // Position, zoom and rotation of the camera.
+    camera: vec4<f32>,
+    params: array<vec4<f32>, 2>,
+}
+
+@group(0) @binding(0) var src: texture_2d<f32>;
+@group(0) @binding(1) var src_sampler: sampler;
+@group(0) @binding(2) var<uniform> params: Params;
+@group(0) @binding(3) var<uniform> frame: Frame;
+
+@vertex
+fn vs_main(@builtin(vertex_index) i: u32) -> @builtin(position) vec4<f32> {
+    let uv = vec2<f32>(f32((i << 1u) & 2u), f32(i & 2u));
+    return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+
+fn sample(pos: vec2<f32>) -> vec4<f32> {
+    return textureSampleLevel(src, src_sampler, pos / params.size, 0.0);
+}
@@ -0,0 +1,6 @@
+// Entry point of custom post effects, which define `fn effect(pos: vec2<f32>) -> vec4<f32>`.
+
+@fragment
+fn fs_main(@builtin(position) frag_pos: vec4<f32>) -> @location(0) vec4<f32> {
+    return effect(frag_pos.xy);
+}
@@ -0,0 +1,67 @@
+//! Depth-sorted drawing.
+//!
+//! A [`SortedLayer`] collects draws with a sort key and submits them to the canvas in key order, e.g. by world y for top-down games where characters walk behind and in front of trees:
+//!
+//! ```ignore
+//! let mut layer = SortedLayer::new();
+//! for tree in self.trees.iter() {
+//!     layer.draw(tree.pos.y, tree_sprite, camera.transform() * math::translate(tree.pos.x, tree.pos.y));
+//! }
+//! layer.draw(self.player.pos.y, player_sprite, camera.transform() * math::translate(self.player.pos.x, self.player.pos.y));
+//! layer.flush(canvas);
+//! ```
+
+use super::{Canvas, Drawable};
+use crate::math::Affine2;
+
+type Draw<'a> = Box<dyn FnOnce(&mut Canvas<'a>) + 'a>;
+
+/// Draws collected with sort keys, submitted in ascending key order.
+///
+/// Draws with equal keys keep the order they were added in.
+pub struct SortedLayer<'a> {
+    draws: Vec<(f32, Draw<'a>)>,
+}
+
+impl Default for SortedLayer<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> SortedLayer<'a> {
+    /// Creates an empty layer.
+    pub fn new() -> Self {
+        Self { draws: vec![] }
+    }
+
+    /// Adds a drawable with the given transform, drawn in order of the given key.
+    pub fn draw(&mut self, key: f32, drawable: impl Drawable<'a> + 'a, transform: Affine2) {
+        self.draw_with(key, move |canvas| canvas.draw(drawable, transform));
+    }
+
+    /// Adds a group of draws that are kept together, drawn in order of the given key.
+    ///
+    /// This is useful for sprites made of multiple parts, such as a character and its held item.
+    pub fn draw_with(&mut self, key: f32, f: impl FnOnce(&mut Canvas<'a>) + 'a) {
+        self.draws.push((key, Box::new(f)));
+    }
+
+    /// Gets the number of draws collected.
+    pub fn len(&self) -> usize {
+        self.draws.len()
+    }
+
+    /// Checks if no draws were collected.
+    pub fn is_empty(&self) -> bool {
+        self.draws.is_empty()
+    }
+
+    /// Submits all collected draws to the canvas in key order, leaving the layer empty.
+    pub fn flush(&mut self, canvas: &mut Canvas<'a>) {
+        self.draws.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        for (_, draw) in self.draws.drain(..) {
+            draw(canvas);
+        }
+    }
+}
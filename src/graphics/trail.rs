@@ -0,0 +1,145 @@
+//! Trails behind moving points.
+//!
+//! A [`TrailRenderer`] records the recent positions of a moving point and draws a ribbon through them that narrows and fades as it ages, e.g. for sword slashes, bullet streaks and ball trails:
+//!
+//! ```ignore
+//! self.trail.push(self.ball.pos);
+//! self.trail.update(dt);
+//! self.trail.draw(canvas, streak_texture.layer(0).unwrap());
+//! ```
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use super::{Canvas, Color, Drawable as _, TextureSlice};
+use crate::math::{Affine2, Vec2};
+
+struct Point {
+    pos: Vec2,
+    age: Duration,
+}
+
+/// Draws a fading ribbon behind a moving point.
+///
+/// Each segment of the ribbon is drawn as the given texture stretched between two consecutive points, so segments using the same texture are batched together.
+pub struct TrailRenderer {
+    points: VecDeque<Point>,
+
+    /// How long a point stays in the trail.
+    pub lifetime: Duration,
+
+    /// Minimum distance the point must move before a new point is recorded.
+    pub min_distance: f32,
+
+    /// Width of the trail at its newest point.
+    pub start_width: f32,
+
+    /// Width of the trail at its oldest point, once it reaches the end of its lifetime.
+    pub end_width: f32,
+
+    /// Tint of the trail at its newest point.
+    pub start_color: Color,
+
+    /// Tint of the trail at the end of its lifetime. Usually transparent, such that the trail fades out.
+    pub end_color: Color,
+}
+
+fn lerp_color(a: Color, b: Color, t: f32) -> Color {
+    let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+    Color::new(
+        lerp(a.r, b.r),
+        lerp(a.g, b.g),
+        lerp(a.b, b.b),
+        lerp(a.a, b.a),
+    )
+}
+
+impl TrailRenderer {
+    /// Creates an empty trail that fades from white to transparent over the given lifetime.
+    pub fn new(lifetime: Duration, width: f32) -> Self {
+        Self {
+            points: VecDeque::new(),
+            lifetime,
+            min_distance: 1.0,
+            start_width: width,
+            end_width: 0.0,
+            start_color: Color::new(0xff, 0xff, 0xff, 0xff),
+            end_color: Color::new(0xff, 0xff, 0xff, 0x00),
+        }
+    }
+
+    /// Records the current position of the moving point.
+    ///
+    /// The position is only recorded if it is at least [`TrailRenderer::min_distance`] away from the last recorded one.
+    pub fn push(&mut self, pos: Vec2) {
+        if self
+            .points
+            .front()
+            .is_some_and(|head| head.pos.distance(pos) < self.min_distance)
+        {
+            return;
+        }
+        self.points.push_front(Point {
+            pos,
+            age: Duration::ZERO,
+        });
+    }
+
+    /// Ages the trail, dropping points older than the lifetime.
+    pub fn update(&mut self, dt: Duration) {
+        for point in self.points.iter_mut() {
+            point.age += dt;
+        }
+        while self.points.back().is_some_and(|p| p.age >= self.lifetime) {
+            self.points.pop_back();
+        }
+    }
+
+    /// Removes all points, e.g. when the moving point teleports.
+    pub fn clear(&mut self) {
+        self.points.clear();
+    }
+
+    /// Checks if the trail has no points.
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Gets how far along its lifetime a point is, from 0 to 1.
+    fn progress(&self, point: &Point) -> f32 {
+        (point.age.as_secs_f32() / self.lifetime.as_secs_f32().max(f32::EPSILON)).min(1.0)
+    }
+
+    /// Draws the trail with the given texture stretched along each segment.
+    ///
+    /// The texture's width runs along the trail, and its height across it. A single white pixel gives a solid ribbon.
+    pub fn draw<'a>(&self, canvas: &mut Canvas<'a>, texture: TextureSlice<'a>) {
+        self.draw_with_transform(canvas, texture, Affine2::IDENTITY);
+    }
+
+    /// Draws the trail with a transform applied to all points, e.g. from a [`super::camera::Camera2D`].
+    ///
+    /// The width of the trail is not affected by the transform.
+    pub fn draw_with_transform<'a>(
+        &self,
+        canvas: &mut Canvas<'a>,
+        texture: TextureSlice<'a>,
+        transform: Affine2,
+    ) {
+        let size = texture.size().as_vec2();
+        for (newer, older) in self.points.iter().zip(self.points.iter().skip(1)) {
+            let a = transform.transform_point2(older.pos);
+            let b = transform.transform_point2(newer.pos);
+            let delta = b - a;
+            let t = (self.progress(newer) + self.progress(older)) / 2.0;
+            let width = self.start_width + (self.end_width - self.start_width) * t;
+            canvas.draw(
+                texture.tinted(lerp_color(self.start_color, self.end_color, t)),
+                Affine2::from_translation(a)
+                    * Affine2::from_angle(delta.to_angle())
+                    * Affine2::from_scale(Vec2::new(delta.length() / size.x, width / size.y))
+                    * Affine2::from_translation(Vec2::new(0.0, -size.y / 2.0)),
+            );
+        }
+    }
+}
@@ -0,0 +1,199 @@
+//! Screen transitions.
+//!
+//! A [`Transition`] blends between two full-screen images, usually [`Framebuffer`]s that the outgoing and incoming screens were rendered to:
+//!
+//! ```ignore
+//! self.transition = Some(Transition::new(Effect::Fade(Color::new(0, 0, 0, 0xff)), Duration::from_millis(500)));
+//!
+//! // In update:
+//! transition.update(dt);
+//!
+//! // In draw:
+//! ctxt.gfx.render_to_framebuffer(&title_canvas, &self.from);
+//! ctxt.gfx.render_to_framebuffer(&level_canvas, &self.to);
+//! transition.draw(ctxt.gfx, canvas, self.from.as_texture_slice(), self.to.as_texture_slice(), pixel);
+//! ```
+
+use std::time::Duration;
+
+use super::{Canvas, Color, Drawable as _, Framebuffer, Graphics, TextureSlice};
+use crate::math::{self, Affine2, UVec2, Vec2};
+
+/// The direction a wipe travels in.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum WipeDirection {
+    /// From the left edge to the right edge.
+    Right,
+
+    /// From the right edge to the left edge.
+    Left,
+
+    /// From the top edge to the bottom edge.
+    Down,
+
+    /// From the bottom edge to the top edge.
+    Up,
+}
+
+/// The visual effect of a [`Transition`].
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Effect {
+    /// Fades out to the given color, then fades in.
+    Fade(Color),
+
+    /// Blends directly from one image to the other.
+    Crossfade,
+
+    /// Covers the screen with a band of the given color moving in the given direction, then uncovers it.
+    Wipe(Color, WipeDirection),
+
+    /// Pixelates until blocks reach the given size in pixels, then unpixelates.
+    Pixelate(u32),
+}
+
+/// A transition between two full-screen images.
+pub struct Transition {
+    effect: Effect,
+    duration: Duration,
+    elapsed: Duration,
+
+    /// Reused between frames for [`Effect::Pixelate`], keyed by its size.
+    pixelate_framebuffer: Option<(UVec2, Framebuffer)>,
+}
+
+impl Transition {
+    /// Creates a transition with the given effect that takes the given duration.
+    pub fn new(effect: Effect, duration: Duration) -> Self {
+        Self {
+            effect,
+            duration,
+            elapsed: Duration::ZERO,
+            pixelate_framebuffer: None,
+        }
+    }
+
+    /// Advances the transition.
+    pub fn update(&mut self, dt: Duration) {
+        self.elapsed = (self.elapsed + dt).min(self.duration);
+    }
+
+    /// Gets how far along the transition is, from 0 to 1.
+    pub fn progress(&self) -> f32 {
+        if self.duration.is_zero() {
+            return 1.0;
+        }
+        self.elapsed.as_secs_f32() / self.duration.as_secs_f32()
+    }
+
+    /// Checks if the transition has finished, after which only the incoming image is shown.
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    /// Checks if the transition is showing the incoming image, e.g. to decide when to start updating the incoming screen.
+    ///
+    /// For [`Effect::Crossfade`], this is true from the start.
+    pub fn is_showing_incoming(&self) -> bool {
+        self.effect == Effect::Crossfade || self.progress() >= 0.5
+    }
+
+    /// Draws the transition.
+    ///
+    /// `from` and `to` are the outgoing and incoming images, which are drawn at the origin and should be the same size. `pixel` must be a slice containing a single white pixel, which is used for solid colors.
+    pub fn draw<'a>(
+        &'a mut self,
+        gfx: &mut Graphics,
+        canvas: &mut Canvas<'a>,
+        from: TextureSlice<'a>,
+        to: TextureSlice<'a>,
+        pixel: TextureSlice<'a>,
+    ) {
+        let t = self.progress();
+        let size = to.size().as_vec2();
+
+        // Effects other than crossfade show one image at a time, and are symmetric around the midpoint.
+        let (image, amount) = if t < 0.5 {
+            (from, t * 2.0)
+        } else {
+            (to, (1.0 - t) * 2.0)
+        };
+
+        match self.effect {
+            Effect::Fade(color) => {
+                canvas.draw(image, Affine2::IDENTITY);
+                canvas.draw(
+                    pixel.tinted(Color::new(
+                        color.r,
+                        color.g,
+                        color.b,
+                        (color.a as f32 * amount).round() as u8,
+                    )),
+                    math::scale(size.x, size.y),
+                );
+            }
+            Effect::Crossfade => {
+                canvas.draw(from, Affine2::IDENTITY);
+                canvas.draw(
+                    to.tinted(Color::new(0xff, 0xff, 0xff, (t * 255.0).round() as u8)),
+                    Affine2::IDENTITY,
+                );
+            }
+            Effect::Wipe(color, direction) => {
+                canvas.draw(image, Affine2::IDENTITY);
+
+                // The band grows from the leading edge while covering, and shrinks towards the trailing edge while uncovering.
+                let (min, max) = if t < 0.5 {
+                    (0.0, amount)
+                } else {
+                    (1.0 - amount, 1.0)
+                };
+                let (pos, band) = match direction {
+                    WipeDirection::Right => (
+                        Vec2::new(min * size.x, 0.0),
+                        Vec2::new((max - min) * size.x, size.y),
+                    ),
+                    WipeDirection::Left => (
+                        Vec2::new((1.0 - max) * size.x, 0.0),
+                        Vec2::new((max - min) * size.x, size.y),
+                    ),
+                    WipeDirection::Down => (
+                        Vec2::new(0.0, min * size.y),
+                        Vec2::new(size.x, (max - min) * size.y),
+                    ),
+                    WipeDirection::Up => (
+                        Vec2::new(0.0, (1.0 - max) * size.y),
+                        Vec2::new(size.x, (max - min) * size.y),
+                    ),
+                };
+                canvas.draw(
+                    pixel.tinted(color),
+                    Affine2::from_translation(pos) * Affine2::from_scale(band),
+                );
+            }
+            Effect::Pixelate(max_block_size) => {
+                let block_size = (1.0 + (max_block_size.max(1) - 1) as f32 * amount).round();
+                if block_size <= 1.0 {
+                    canvas.draw(image, Affine2::IDENTITY);
+                    return;
+                }
+
+                // Render the image downscaled, then draw it upscaled such that each pixel becomes a block.
+                let small_size = (size / block_size).ceil().as_uvec2().max(UVec2::ONE);
+                if !matches!(&self.pixelate_framebuffer, Some((s, _)) if *s == small_size) {
+                    self.pixelate_framebuffer =
+                        Some((small_size, gfx.create_framebuffer(small_size)));
+                }
+                let (_, framebuffer) = self.pixelate_framebuffer.as_ref().unwrap();
+
+                let mut small_canvas = Canvas::new();
+                small_canvas.draw(image, math::uniform_scale(1.0 / block_size));
+                gfx.render_to_framebuffer(&small_canvas, framebuffer);
+
+                canvas.draw(
+                    framebuffer.as_texture_slice(),
+                    math::uniform_scale(block_size),
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,46 @@
+// Upscaling filters, sampling a pass rendered at a lower resolution.
+
+const FILTER_NEAREST: u32 = 0u;
+const FILTER_BILINEAR: u32 = 1u;
+const FILTER_SHARPEN: u32 = 2u;
+
+fn texel(pos: vec2<i32>) -> vec4<f32> {
+    let max_pos = vec2<i32>(textureDimensions(src)) - 1;
+    return textureLoad(src, clamp(pos, vec2<i32>(0), max_pos), 0);
+}
+
+// Blends the four texels around a position in texels of the source.
+fn bilinear(pos: vec2<f32>) -> vec4<f32> {
+    let p = pos - 0.5;
+    let base = vec2<i32>(floor(p));
+    let f = fract(p);
+    let top = mix(texel(base), texel(base + vec2<i32>(1, 0)), f.x);
+    let bottom = mix(texel(base + vec2<i32>(0, 1)), texel(base + vec2<i32>(1, 1)), f.x);
+    return mix(top, bottom, f.y);
+}
+
+@fragment
+fn fs_main(@builtin(position) frag_pos: vec4<f32>) -> @location(0) vec4<f32> {
+    let pos = frag_pos.xy * vec2<f32>(textureDimensions(src)) / params.size;
+    switch params.kind {
+        case FILTER_NEAREST: {
+            return texel(vec2<i32>(floor(pos)));
+        }
+        case FILTER_BILINEAR: {
+            return bilinear(pos);
+        }
+        default: {
+            // Push the color away from its neighbors, less so where contrast is already high, and keep it within the neighbors' range to avoid halos.
+            let color = bilinear(pos);
+            let n = bilinear(pos + vec2<f32>(0.0, -1.0)).rgb;
+            let s = bilinear(pos + vec2<f32>(0.0, 1.0)).rgb;
+            let w = bilinear(pos + vec2<f32>(-1.0, 0.0)).rgb;
+            let e = bilinear(pos + vec2<f32>(1.0, 0.0)).rgb;
+            let lo = min(color.rgb, min(min(n, s), min(w, e)));
+            let hi = max(color.rgb, max(max(n, s), max(w, e)));
+            let weight = params.intensity * clamp(1.0 - (hi - lo), vec3<f32>(0.0), vec3<f32>(1.0));
+            let sharpened = color.rgb + (color.rgb - (n + s + w + e) * 0.25) * weight;
+            return vec4<f32>(clamp(sharpened, lo, hi), color.a);
+        }
+    }
+}
@@ -0,0 +1,84 @@
+//! Device vibration, e.g. for feedback on hits in mobile games.
+//!
+//! - On Android, the device's vibrator is used, which requires the `android.permission.VIBRATE` permission in the app's manifest.
+//! - On WASM, the browser's Vibration API is used, which is mostly supported by mobile browsers, and only after the user has interacted with the page.
+//! - Elsewhere, vibrating does nothing.
+//!
+//! ```ignore
+//! if self.player.was_hit {
+//!     ctxt.haptics.vibrate(&[Duration::from_millis(40)]);
+//! }
+//! ```
+
+use std::time::Duration;
+
+#[cfg(target_os = "android")]
+mod android;
+
+#[cfg(target_os = "android")]
+use android as imp;
+
+#[cfg(target_arch = "wasm32")]
+mod web;
+
+#[cfg(target_arch = "wasm32")]
+use web as imp;
+
+#[cfg(not(any(target_os = "android", target_arch = "wasm32")))]
+mod imp {
+    pub const SUPPORTED: bool = false;
+
+    pub fn vibrate(_pattern_ms: &[u64]) {}
+
+    pub fn cancel() {}
+}
+
+/// Controls the device's vibration.
+pub struct Haptics {
+    enabled: bool,
+}
+
+impl Haptics {
+    pub(crate) fn new() -> Self {
+        Self { enabled: true }
+    }
+
+    /// Checks if vibration is supported on this platform.
+    ///
+    /// Even if supported, the device may not have a vibrator, or the user may have turned vibration off.
+    pub fn is_supported(&self) -> bool {
+        imp::SUPPORTED
+    }
+
+    /// Checks if vibration is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Enables or disables vibration, e.g. from a settings menu. Disabling also stops any ongoing vibration.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if self.enabled && !enabled {
+            imp::cancel();
+        }
+        self.enabled = enabled;
+    }
+
+    /// Vibrates with a pattern of alternating vibration and pause durations, starting with a vibration.
+    ///
+    /// This replaces any ongoing vibration.
+    pub fn vibrate(&mut self, pattern: &[Duration]) {
+        if !self.enabled || pattern.is_empty() {
+            return;
+        }
+        let pattern_ms = pattern
+            .iter()
+            .map(|d| d.as_millis().min(u64::MAX as u128) as u64)
+            .collect::<Vec<_>>();
+        imp::vibrate(&pattern_ms);
+    }
+
+    /// Stops any ongoing vibration.
+    pub fn cancel(&mut self) {
+        imp::cancel();
+    }
+}
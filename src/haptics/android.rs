@@ -0,0 +1,40 @@
+use jni::objects::JValue;
+
+use crate::android::with_system_service;
+
+pub const SUPPORTED: bool = true;
+
+pub fn vibrate(pattern_ms: &[u64]) {
+    let result = with_system_service("VIBRATOR_SERVICE", |env, vibrator| {
+        // Android patterns start with a pause, so start with no pause to begin vibrating right away.
+        let pattern = std::iter::once(0)
+            .chain(
+                pattern_ms
+                    .iter()
+                    .map(|ms| (*ms).min(i64::MAX as u64) as i64),
+            )
+            .collect::<Vec<_>>();
+        let array = env.new_long_array(pattern.len() as i32)?;
+        env.set_long_array_region(&array, 0, &pattern)?;
+        env.call_method(
+            vibrator,
+            "vibrate",
+            "([JI)V",
+            &[JValue::Object(&array), JValue::Int(-1)],
+        )?;
+        Ok(())
+    });
+    if let Err(e) = result {
+        log::warn!("failed to vibrate: {e}");
+    }
+}
+
+pub fn cancel() {
+    let result = with_system_service("VIBRATOR_SERVICE", |env, vibrator| {
+        env.call_method(vibrator, "cancel", "()V", &[])?;
+        Ok(())
+    });
+    if let Err(e) = result {
+        log::warn!("failed to cancel vibration: {e}");
+    }
+}
@@ -0,0 +1,20 @@
+use wasm_bindgen::JsValue;
+
+pub const SUPPORTED: bool = true;
+
+pub fn vibrate(pattern_ms: &[u64]) {
+    let Some(navigator) = web_sys::window().map(|w| w.navigator()) else {
+        return;
+    };
+    let pattern = pattern_ms
+        .iter()
+        .map(|ms| JsValue::from_f64(*ms as f64))
+        .collect::<js_sys::Array>();
+    _ = navigator.vibrate_with_pattern(&pattern);
+}
+
+pub fn cancel() {
+    if let Some(navigator) = web_sys::window().map(|w| w.navigator()) {
+        _ = navigator.vibrate_with_duration(0);
+    }
+}
@@ -0,0 +1,280 @@
+//! Hot reloading of game logic during development.
+//!
+//! The game is built as a dynamic library that exports its [`Game`] implementation, and run by a small host binary that reloads the library whenever it is rebuilt:
+//!
+//! ```ignore
+//! // In the game crate, built with `crate-type = ["dylib"]`:
+//! teenygame::export_hot_reload!(MyGame);
+//!
+//! // In the host binary:
+//! fn main() {
+//!     teenygame::hot_reload::run("target/debug/libmy_game.so");
+//! }
+//! ```
+//!
+//! Rebuilding the library (e.g. with `cargo watch -x build`) then swaps the game logic without restarting: the state is saved with [`Game::save_state`], the new library is loaded, the state is restored with [`Game::load_state`], and the new game is resumed with [`Game::resumed`]. The window and GPU context persist, while resources owned by the game (e.g. [`Lazy`](crate::graphics::Lazy) textures) are loaded again by the new game.
+//!
+//! Types are passed between the host and the library without a stable ABI, so both must be built with the same compiler and the same teenygame features. Global state such as [`loading`](crate::loading) progress is not shared between them. Only [`Game::update`], [`Game::draw`], [`Game::resumed`], [`Game::suspended`], [`Game::resized`], [`Game::theme_changed`] and state saving are forwarded to the library, and the host's [`Game`] constants are the defaults. Games with a custom [`Game::Event`] type can't be hot reloaded, as the host doesn't know it.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::graphics::Canvas;
+use crate::math::UVec2;
+use crate::system::Theme;
+use crate::time::SystemTime;
+use crate::{Context, Game};
+
+/// Name of the symbol exported by [`export_hot_reload!`](crate::export_hot_reload).
+const NEW_GAME_SYMBOL: &[u8] = b"teenygame_hot_reload_new_game";
+
+/// Prefix of the names of the copies of the library that are loaded, in the temporary directory.
+const COPY_PREFIX: &str = "teenygame-hot-reload-";
+
+/// Path to the library to load, set by [`run`].
+static LIBRARY_PATH: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// The parts of [`Game`] that are called across the library boundary.
+///
+/// This is implemented for all games without custom events, and only needs to be named by [`export_hot_reload!`](crate::export_hot_reload).
+pub trait DynGame {
+    /// See [`Game::update`].
+    fn update(&mut self, ctxt: &mut Context);
+
+    /// See [`Game::draw`].
+    fn draw<'a>(&'a mut self, ctxt: &mut Context, canvas: &mut Canvas<'a>);
+
+    /// See [`Game::resumed`].
+    fn resumed(&mut self, ctxt: &mut Context);
+
+    /// See [`Game::suspended`].
+    fn suspended(&mut self);
+
+    /// See [`Game::resized`].
+    fn resized(&mut self, size: UVec2, scale_factor: f64);
+
+    /// See [`Game::theme_changed`].
+    fn theme_changed(&mut self, theme: Theme);
+
+    /// See [`Game::save_state`].
+    fn save_state(&self) -> Vec<u8>;
+
+    /// See [`Game::load_state`].
+    fn load_state(&mut self, state: &[u8]);
+}
+
+impl<G> DynGame for G
+where
+    G: Game<Event = std::convert::Infallible>,
+{
+    fn update(&mut self, ctxt: &mut Context) {
+        Game::update(self, ctxt);
+    }
+
+    fn draw<'a>(&'a mut self, ctxt: &mut Context, canvas: &mut Canvas<'a>) {
+        Game::draw(self, ctxt, canvas);
+    }
+
+    fn resumed(&mut self, ctxt: &mut Context) {
+        Game::resumed(self, ctxt);
+    }
+
+    fn suspended(&mut self) {
+        Game::suspended(self);
+    }
+
+    fn resized(&mut self, size: UVec2, scale_factor: f64) {
+        Game::resized(self, size, scale_factor);
+    }
+
+    fn theme_changed(&mut self, theme: Theme) {
+        Game::theme_changed(self, theme);
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        Game::save_state(self)
+    }
+
+    fn load_state(&mut self, state: &[u8]) {
+        Game::load_state(self, state);
+    }
+}
+
+/// Exports a [`Game`] implementation from a dynamic library, such that it can be loaded by [`run`].
+#[macro_export]
+macro_rules! export_hot_reload {
+    ($game:ty) => {
+        #[no_mangle]
+        pub fn teenygame_hot_reload_new_game() -> Box<dyn $crate::hot_reload::DynGame> {
+            Box::new(<$game as $crate::Game>::new())
+        }
+    };
+}
+
+/// A game that forwards to a game loaded from a dynamic library, reloading it when the library changes.
+struct HotReload {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    generation: u32,
+
+    /// The last size passed to [`Game::resized`], which is passed on to reloaded games.
+    size: Option<(UVec2, f64)>,
+
+    /// The last theme passed to [`Game::theme_changed`], which is passed on to reloaded games.
+    theme: Option<Theme>,
+
+    // The game must be dropped before the library containing its code.
+    game: Option<Box<dyn DynGame>>,
+    library: Option<libloading::Library>,
+}
+
+impl HotReload {
+    fn modified(&self) -> Option<SystemTime> {
+        std::fs::metadata(&self.path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+
+    fn load(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let modified = self.modified();
+
+        // Load a copy, such that the next build may overwrite the library while it is loaded, and the OS doesn't hand back the already loaded library.
+        let copy = std::env::temp_dir().join(format!(
+            "{COPY_PREFIX}{}-{}{}",
+            std::process::id(),
+            self.generation,
+            self.path
+                .extension()
+                .map(|ext| format!(".{}", ext.to_string_lossy()))
+                .unwrap_or_default()
+        ));
+        std::fs::copy(&self.path, &copy)?;
+        self.generation += 1;
+
+        // SAFETY: The library is expected to be built by the same compiler against the same teenygame as the host, which is documented as a requirement of hot reloading.
+        let library = unsafe { libloading::Library::new(&copy)? };
+        let mut game = {
+            let new_game = unsafe { library.get::<fn() -> Box<dyn DynGame>>(NEW_GAME_SYMBOL)? };
+            new_game()
+        };
+
+        if let Some(old_game) = self.game.take() {
+            game.load_state(&old_game.save_state());
+        }
+        if let Some((size, scale_factor)) = self.size {
+            game.resized(size, scale_factor);
+        }
+        if let Some(theme) = self.theme {
+            game.theme_changed(theme);
+        }
+        self.game = Some(game);
+        self.library = Some(library);
+        self.modified = modified;
+
+        // Windows doesn't allow deleting libraries while they are loaded, so copies are only deleted once replaced. This includes copies left behind by earlier runs, while copies still loaded by other running hosts fail to be deleted there.
+        remove_stale_copies(&copy);
+        Ok(())
+    }
+
+    fn reload_if_changed(&mut self, ctxt: &mut Context) {
+        let modified = self.modified();
+        if modified.is_none() || modified == self.modified {
+            return;
+        }
+        match self.load() {
+            Ok(()) => {
+                log::info!("reloaded {}", self.path.display());
+
+                // Updates only happen while resumed, so the new game has missed being resumed.
+                self.game().resumed(ctxt);
+            }
+            Err(e) => {
+                // The library may still be being written, so try again later.
+                log::warn!("failed to reload {}: {e}", self.path.display());
+            }
+        }
+    }
+
+    fn game(&mut self) -> &mut dyn DynGame {
+        self.game.as_deref_mut().unwrap()
+    }
+}
+
+impl Game for HotReload {
+    type Event = std::convert::Infallible;
+
+    fn new() -> Self {
+        let path = LIBRARY_PATH
+            .lock()
+            .unwrap()
+            .take()
+            .expect("hot reloading must be started via hot_reload::run");
+        let mut hot_reload = Self {
+            path,
+            modified: None,
+            generation: 0,
+            size: None,
+            theme: None,
+            game: None,
+            library: None,
+        };
+        if let Err(e) = hot_reload.load() {
+            panic!("failed to load {}: {e}", hot_reload.path.display());
+        }
+        hot_reload
+    }
+
+    fn resumed(&mut self, ctxt: &mut Context) {
+        self.game().resumed(ctxt);
+    }
+
+    fn suspended(&mut self) {
+        self.game().suspended();
+    }
+
+    fn resized(&mut self, size: UVec2, scale_factor: f64) {
+        self.size = Some((size, scale_factor));
+        self.game().resized(size, scale_factor);
+    }
+
+    fn theme_changed(&mut self, theme: Theme) {
+        self.theme = Some(theme);
+        self.game().theme_changed(theme);
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        self.game.as_ref().unwrap().save_state()
+    }
+
+    fn load_state(&mut self, state: &[u8]) {
+        self.game().load_state(state);
+    }
+
+    fn update(&mut self, ctxt: &mut Context) {
+        self.reload_if_changed(ctxt);
+        self.game().update(ctxt);
+    }
+
+    fn draw<'a>(&'a mut self, ctxt: &mut Context, canvas: &mut Canvas<'a>) {
+        self.game().draw(ctxt, canvas);
+    }
+}
+
+/// Deletes copies of the library in the temporary directory, except the given one.
+fn remove_stale_copies(current: &Path) {
+    let Ok(entries) = std::fs::read_dir(std::env::temp_dir()) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path != current && entry.file_name().to_string_lossy().starts_with(COPY_PREFIX) {
+            _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Runs the game exported from the dynamic library at the given path, reloading it whenever the library is rebuilt.
+pub fn run(path: impl AsRef<Path>) {
+    *LIBRARY_PATH.lock().unwrap() = Some(path.as_ref().to_path_buf());
+    crate::run::<HotReload>();
+}
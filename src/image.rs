@@ -45,12 +45,184 @@ impl<Pixel> AsImgRef<Pixel> for Img<Vec<Pixel>> {
     }
 }
 
+impl<Pixel, T> AsImgRef<Pixel> for &T
+where
+    T: AsImgRef<Pixel>,
+{
+    fn as_ref(&self) -> Img<&[Pixel]> {
+        (*self).as_ref()
+    }
+}
+
 impl<Pixel> Img<&[Pixel]> {
     pub fn as_buf(&self) -> &[Pixel] {
         self.pixels
     }
 }
 
+impl<Pixel> Img<Vec<Pixel>>
+where
+    Pixel: Copy,
+{
+    /// Creates a single-layer image of the given size, filled with a pixel.
+    pub fn filled(size: glam::UVec2, pixel: Pixel) -> Self {
+        Self::new(vec![pixel; (size.x * size.y) as usize], size, 1)
+    }
+
+    /// Creates a single-layer image of the given size, with each pixel computed from its position.
+    pub fn from_fn(size: glam::UVec2, mut f: impl FnMut(glam::UVec2) -> Pixel) -> Self {
+        Self::new(
+            (0..size.y)
+                .flat_map(|y| (0..size.x).map(move |x| glam::uvec2(x, y)))
+                .map(&mut f)
+                .collect(),
+            size,
+            1,
+        )
+    }
+
+    fn index(&self, layer: u32, pos: glam::UVec2) -> Option<usize> {
+        if layer >= self.layers || pos.x >= self.size.x || pos.y >= self.size.y {
+            return None;
+        }
+        Some(((layer * self.size.y + pos.y) * self.size.x + pos.x) as usize)
+    }
+
+    /// Gets the pixel at the given position in the first layer, if it is in bounds.
+    pub fn get_pixel(&self, pos: glam::UVec2) -> Option<Pixel> {
+        self.get_layer_pixel(0, pos)
+    }
+
+    /// Gets the pixel at the given position in the given layer, if it is in bounds.
+    pub fn get_layer_pixel(&self, layer: u32, pos: glam::UVec2) -> Option<Pixel> {
+        self.index(layer, pos).map(|i| self.pixels[i])
+    }
+
+    /// Sets the pixel at the given position in the first layer. Out of bounds positions are ignored.
+    pub fn set_pixel(&mut self, pos: glam::UVec2, pixel: Pixel) {
+        self.set_layer_pixel(0, pos, pixel);
+    }
+
+    /// Sets the pixel at the given position in the given layer. Out of bounds positions are ignored.
+    pub fn set_layer_pixel(&mut self, layer: u32, pos: glam::UVec2, pixel: Pixel) {
+        if let Some(i) = self.index(layer, pos) {
+            self.pixels[i] = pixel;
+        }
+    }
+
+    /// Creates a new image with the same number of layers, with each pixel computed from its layer and position.
+    fn map_layers(&self, size: glam::UVec2, mut f: impl FnMut(u32, glam::UVec2) -> Pixel) -> Self {
+        let mut pixels = Vec::with_capacity((size.x * size.y * self.layers) as usize);
+        for layer in 0..self.layers {
+            for y in 0..size.y {
+                for x in 0..size.x {
+                    pixels.push(f(layer, glam::uvec2(x, y)));
+                }
+            }
+        }
+        Self::new(pixels, size, self.layers)
+    }
+
+    /// Crops out a rectangle of the image. The rectangle is clamped to the bounds of the image.
+    pub fn crop(&self, rect: crate::math::URect) -> Self {
+        let min = rect.min.min(self.size);
+        let max = rect.max.min(self.size).max(min);
+        self.map_layers(max - min, |layer, pos| {
+            self.pixels[self.index(layer, min + pos).unwrap()]
+        })
+    }
+
+    /// Copies another image onto this one, with its top-left corner at the given position.
+    ///
+    /// Layers are copied pairwise, and pixels that fall outside of this image are skipped. Pixels are replaced rather than blended.
+    pub fn blit(&mut self, src: &Img<Vec<Pixel>>, pos: glam::IVec2) {
+        for layer in 0..self.layers.min(src.layers) {
+            for y in 0..src.size.y {
+                for x in 0..src.size.x {
+                    let dest = pos + glam::uvec2(x, y).as_ivec2();
+                    if dest.x < 0 || dest.y < 0 {
+                        continue;
+                    }
+                    if let Some(i) = self.index(layer, dest.as_uvec2()) {
+                        self.pixels[i] = src.pixels[src.index(layer, glam::uvec2(x, y)).unwrap()];
+                    }
+                }
+            }
+        }
+    }
+
+    /// Flips the image horizontally.
+    pub fn flip_horizontal(&self) -> Self {
+        self.map_layers(self.size, |layer, pos| {
+            self.pixels[self
+                .index(layer, glam::uvec2(self.size.x - 1 - pos.x, pos.y))
+                .unwrap()]
+        })
+    }
+
+    /// Flips the image vertically.
+    pub fn flip_vertical(&self) -> Self {
+        self.map_layers(self.size, |layer, pos| {
+            self.pixels[self
+                .index(layer, glam::uvec2(pos.x, self.size.y - 1 - pos.y))
+                .unwrap()]
+        })
+    }
+
+    /// Rotates the image 90 degrees clockwise.
+    pub fn rotate90(&self) -> Self {
+        self.map_layers(glam::uvec2(self.size.y, self.size.x), |layer, pos| {
+            self.pixels[self
+                .index(layer, glam::uvec2(pos.y, self.size.y - 1 - pos.x))
+                .unwrap()]
+        })
+    }
+
+    /// Resizes the image using nearest neighbor sampling, which keeps pixel art crisp.
+    pub fn resize_nearest(&self, size: glam::UVec2) -> Self {
+        if self.size.x == 0 || self.size.y == 0 {
+            return Self::new(vec![], glam::UVec2::ZERO, self.layers);
+        }
+        self.map_layers(size, |layer, pos| {
+            let src = (pos * self.size / size).min(self.size - 1);
+            self.pixels[self.index(layer, src).unwrap()]
+        })
+    }
+}
+
+impl Img<Vec<crate::graphics::Color>> {
+    /// Resizes the image using bilinear filtering, which gives smoother results than [`Img::resize_nearest`] for photos and large scale factors.
+    pub fn resize_bilinear(&self, size: glam::UVec2) -> Self {
+        if self.size.x == 0 || self.size.y == 0 {
+            return Self::new(vec![], glam::UVec2::ZERO, self.layers);
+        }
+        let max = (self.size - 1).as_vec2();
+        self.map_layers(size, |layer, pos| {
+            // Sample at pixel centers.
+            let src = ((pos.as_vec2() + 0.5) * self.size.as_vec2() / size.as_vec2() - 0.5)
+                .clamp(glam::Vec2::ZERO, max);
+            let p0 = src.floor().as_uvec2();
+            let p1 = (p0 + 1).min(self.size - 1);
+            let t = src - src.floor();
+            let get = |x, y| self.pixels[self.index(layer, glam::uvec2(x, y)).unwrap()];
+            let lerp = |a: crate::graphics::Color, b: crate::graphics::Color, t: f32| {
+                let channel = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+                crate::graphics::Color::new(
+                    channel(a.r, b.r),
+                    channel(a.g, b.g),
+                    channel(a.b, b.b),
+                    channel(a.a, b.a),
+                )
+            };
+            lerp(
+                lerp(get(p0.x, p0.y), get(p1.x, p0.y), t.x),
+                lerp(get(p0.x, p1.y), get(p1.x, p1.y), t.x),
+                t.y,
+            )
+        })
+    }
+}
+
 /// Load an image from in-memory bytes.
 ///
 /// This will perform conversion to RGBA8.
@@ -72,3 +244,91 @@ pub fn load_from_image(
         1,
     ))
 }
+
+/// Errors that can occur while saving an image.
+#[cfg(feature = "image")]
+#[derive(thiserror::Error, Debug)]
+pub enum SaveError {
+    /// Encoding failed.
+    #[error("image: {0}")]
+    Image(#[from] image::ImageError),
+
+    /// Writing failed.
+    #[error("other: {0}")]
+    Other(Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// Converts the first layer of an image to a [`image::RgbaImage`].
+#[cfg(feature = "image")]
+fn to_rgba_image(img: impl AsImgRef<crate::graphics::Color>) -> image::RgbaImage {
+    let img = img.as_ref();
+    let size = img.size();
+    let len = (size.x * size.y) as usize;
+    image::RgbaImage::from_raw(
+        size.x,
+        size.y,
+        bytemuck::cast_slice(&img.as_buf()[..len]).to_vec(),
+    )
+    .unwrap()
+}
+
+/// Encodes the first layer of an image in the given format.
+#[cfg(feature = "image")]
+fn encode(
+    img: impl AsImgRef<crate::graphics::Color>,
+    format: image::ImageFormat,
+) -> Result<Vec<u8>, image::ImageError> {
+    let mut buf = std::io::Cursor::new(vec![]);
+    to_rgba_image(img).write_to(&mut buf, format)?;
+    Ok(buf.into_inner())
+}
+
+/// Encodes the first layer of an image as PNG.
+#[cfg(feature = "image")]
+pub fn encode_png(
+    img: impl AsImgRef<crate::graphics::Color>,
+) -> Result<Vec<u8>, image::ImageError> {
+    encode(img, image::ImageFormat::Png)
+}
+
+/// Encodes the first layer of an image as JPEG with the given quality, from 1 to 100.
+///
+/// JPEG has no alpha channel, so transparency is discarded.
+#[cfg(feature = "image")]
+pub fn encode_jpeg(
+    img: impl AsImgRef<crate::graphics::Color>,
+    quality: u8,
+) -> Result<Vec<u8>, image::ImageError> {
+    let mut buf = vec![];
+    image::codecs::jpeg::JpegEncoder::new_with_quality(&mut buf, quality.clamp(1, 100))
+        .encode_image(&image::DynamicImage::ImageRgba8(to_rgba_image(img)).to_rgb8())?;
+    Ok(buf)
+}
+
+/// Encodes the first layer of an image as lossless WebP.
+#[cfg(feature = "image")]
+pub fn encode_webp(
+    img: impl AsImgRef<crate::graphics::Color>,
+) -> Result<Vec<u8>, image::ImageError> {
+    encode(img, image::ImageFormat::WebP)
+}
+
+/// Saves the first layer of an image as a PNG file.
+///
+/// On WASM, this instead prompts the browser to download the image, named after the last component of the path.
+#[cfg(feature = "image")]
+pub fn save_png(img: impl AsImgRef<crate::graphics::Color>, path: &str) -> Result<(), SaveError> {
+    let bytes = encode_png(img)?;
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::fs::write(path, bytes).map_err(|e| SaveError::Other(e.into()))
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        let filename = path.rsplit(['/', '\\']).next().unwrap_or(path);
+        crate::web::download(filename, &bytes, "image/png")
+            .map_err(|e| SaveError::Other(format!("{e:?}").into()))
+    }
+}
@@ -1,9 +1,20 @@
 //! Input handling.
 
+pub mod gesture;
+pub mod prompts;
+
+use crate::graphics::camera::Camera2D;
 use crate::math;
+use crate::replay::InputFrame;
 use std::collections::{HashMap, HashSet};
 use winit::dpi::PhysicalPosition;
-pub use winit::{event::MouseButton, keyboard::KeyCode};
+pub use winit::{
+    event::{MouseButton, MouseScrollDelta},
+    keyboard::{Key, KeyCode, NamedKey},
+};
+
+/// Touch ID of the contact emulated from the mouse, which real touches are unlikely to use.
+const EMULATED_TOUCH_ID: u64 = u64::MAX;
 
 /// A contact point.
 #[derive(Clone, Copy, Hash, PartialEq, Eq, Debug)]
@@ -15,6 +26,7 @@ pub struct Touch {
     held_contacts: HashMap<Contact, PhysicalPosition<f64>>,
     next_contact_id: u64,
     ids_to_contacts: HashMap<u64, Contact>,
+    scale_factor: f64,
 }
 
 impl Touch {
@@ -24,6 +36,7 @@ impl Touch {
             last_held_contacts: HashSet::new(),
             next_contact_id: 0,
             ids_to_contacts: HashMap::new(),
+            scale_factor: 1.0,
         }
     }
 
@@ -66,6 +79,18 @@ impl Touch {
             .map(|pos| math::Vec2::new(pos.x as f32, pos.y as f32))
     }
 
+    /// Gets the position of a contact in logical coordinates, i.e. divided by the window's scale factor.
+    pub fn contact_position_logical(&self, contact: Contact) -> Option<math::Vec2> {
+        self.contact_position(contact)
+            .map(|pos| pos / self.scale_factor as f32)
+    }
+
+    /// Gets the position of a contact in world coordinates of the given camera.
+    pub fn contact_position_in(&self, contact: Contact, camera: &Camera2D) -> Option<math::Vec2> {
+        self.contact_position(contact)
+            .map(|pos| camera.screen_to_world(pos))
+    }
+
     pub(crate) fn handle_touch_start(&mut self, id: u64, location: PhysicalPosition<f64>) {
         let contact = self.next_contact();
         self.ids_to_contacts.insert(id, contact);
@@ -93,6 +118,13 @@ impl Touch {
 pub struct Keyboard {
     held_keys: HashSet<KeyCode>,
     last_held_keys: HashSet<KeyCode>,
+    repeated_keys: HashSet<KeyCode>,
+    logical_keys: HashMap<KeyCode, Key>,
+
+    /// Number of ticks so far, and the tick each key was last pressed and released on.
+    tick: u64,
+    pressed_ticks: HashMap<KeyCode, u64>,
+    released_ticks: HashMap<KeyCode, u64>,
 }
 
 impl Keyboard {
@@ -100,6 +132,11 @@ impl Keyboard {
         Self {
             held_keys: HashSet::new(),
             last_held_keys: HashSet::new(),
+            repeated_keys: HashSet::new(),
+            logical_keys: HashMap::new(),
+            tick: 0,
+            pressed_ticks: HashMap::new(),
+            released_ticks: HashMap::new(),
         }
     }
 
@@ -139,16 +176,115 @@ impl Keyboard {
         self.held_keys.contains(&key)
     }
 
-    pub(crate) fn handle_key_up(&mut self, key: KeyCode) {
+    /// Checks if the key was pressed this tick or within the given number of ticks before it.
+    ///
+    /// This is useful for accepting inputs slightly early, such as a jump pressed just before landing. See [`InputBuffer`] for consuming such inputs so they only take effect once.
+    pub fn was_pressed_within(&self, key: KeyCode, n_ticks: u64) -> bool {
+        self.is_key_pressed(key)
+            || self
+                .pressed_ticks
+                .get(&key)
+                .is_some_and(|tick| self.tick - tick <= n_ticks)
+    }
+
+    /// Checks if the key was released this tick or within the given number of ticks before it.
+    pub fn was_released_within(&self, key: KeyCode, n_ticks: u64) -> bool {
+        self.is_key_released(key)
+            || self
+                .released_ticks
+                .get(&key)
+                .is_some_and(|tick| self.tick - tick <= n_ticks)
+    }
+
+    /// Checks if the OS sent an auto-repeat for the key, as happens when a key is held down in a text field.
+    ///
+    /// This is useful for controls that should repeat like text input does, such as moving a menu cursor.
+    pub fn is_key_repeated(&self, key: KeyCode) -> bool {
+        self.repeated_keys.contains(&key)
+    }
+
+    /// Gets the logical key last produced by the physical key, which depends on the keyboard layout.
+    ///
+    /// For example, [`KeyCode::KeyW`] produces `"z"` on AZERTY layouts. This is useful for showing key names in rebinding UIs. Returns [`None`] if the key hasn't been pressed yet.
+    pub fn logical_key(&self, key: KeyCode) -> Option<&Key> {
+        self.logical_keys.get(&key)
+    }
+
+    pub(crate) fn handle_key_up(&mut self, key: KeyCode, logical_key: Key) {
         self.held_keys.remove(&key);
+        self.logical_keys.insert(key, logical_key);
     }
 
-    pub(crate) fn handle_key_down(&mut self, key: KeyCode) {
+    pub(crate) fn handle_key_down(&mut self, key: KeyCode, logical_key: Key, repeat: bool) {
         self.held_keys.insert(key);
+        self.logical_keys.insert(key, logical_key);
+        if repeat {
+            self.repeated_keys.insert(key);
+        }
     }
 
     fn update(&mut self) {
+        for key in self.held_keys.difference(&self.last_held_keys) {
+            self.pressed_ticks.insert(*key, self.tick);
+        }
+        for key in self.last_held_keys.difference(&self.held_keys) {
+            self.released_ticks.insert(*key, self.tick);
+        }
+        self.tick += 1;
+
         self.last_held_keys.clone_from(&self.held_keys);
+        self.repeated_keys.clear();
+    }
+}
+
+/// Remembers that a condition was true for a number of ticks, until consumed.
+///
+/// This implements input buffering and coyote time without per-key tick histories:
+///
+/// ```ignore
+/// // In update:
+/// self.jump_buffer.update(ctxt.input.keyboard.is_key_pressed(KeyCode::Space));
+/// self.coyote_time.update(self.player.is_on_ground());
+/// if self.jump_buffer.is_active() && self.coyote_time.is_active() {
+///     self.jump_buffer.consume();
+///     self.coyote_time.consume();
+///     self.player.jump();
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct InputBuffer {
+    window: u32,
+    ticks_since_active: Option<u32>,
+}
+
+impl InputBuffer {
+    /// Creates a buffer that remembers the condition for the given number of ticks after it was last true.
+    pub fn new(window: u32) -> Self {
+        Self {
+            window,
+            ticks_since_active: None,
+        }
+    }
+
+    /// Updates the buffer with whether the condition is true this tick. Should be called once per tick.
+    pub fn update(&mut self, active: bool) {
+        self.ticks_since_active = if active {
+            Some(0)
+        } else {
+            self.ticks_since_active
+                .map(|ticks| ticks.saturating_add(1))
+                .filter(|ticks| *ticks <= self.window)
+        };
+    }
+
+    /// Checks if the condition was true this tick or within the window before it, and wasn't consumed since.
+    pub fn is_active(&self) -> bool {
+        self.ticks_since_active.is_some()
+    }
+
+    /// Forgets the condition until it is next true, returning whether it was active.
+    pub fn consume(&mut self) -> bool {
+        self.ticks_since_active.take().is_some()
     }
 }
 
@@ -162,6 +298,9 @@ pub struct InputState {
 
     /// Touch state.
     pub touch: Touch,
+
+    /// The touch currently emulating the mouse, if any.
+    emulated_mouse_touch: Option<u64>,
 }
 
 impl InputState {
@@ -170,14 +309,133 @@ impl InputState {
             keyboard: Keyboard::new(),
             mouse: Mouse::new(),
             touch: Touch::new(),
+            emulated_mouse_touch: None,
         }
     }
 
+    /// Presses, moves or releases a contact following the primary mouse button.
+    pub(crate) fn emulate_touch_from_mouse(&mut self) {
+        // The mouse may itself be emulated from a touch, which shouldn't be duplicated.
+        let pos = if self.emulated_mouse_touch.is_none()
+            && self.mouse.is_button_held(MouseButton::Left)
+        {
+            self.mouse.pos
+        } else {
+            None
+        };
+        let active = self.touch.ids_to_contacts.contains_key(&EMULATED_TOUCH_ID);
+        match (pos, active) {
+            (Some(pos), false) => self.touch.handle_touch_start(EMULATED_TOUCH_ID, pos),
+            (Some(pos), true) => self.touch.handle_touch_move(EMULATED_TOUCH_ID, pos),
+            (None, true) => self.touch.handle_touch_end(EMULATED_TOUCH_ID),
+            (None, false) => {}
+        }
+    }
+
+    /// Moves the mouse and presses or releases the primary mouse button following the first held touch.
+    pub(crate) fn emulate_mouse_from_touch(&mut self) {
+        if let Some(id) = self.emulated_mouse_touch {
+            if !self.touch.ids_to_contacts.contains_key(&id) {
+                self.mouse.handle_button_up(MouseButton::Left);
+                self.emulated_mouse_touch = None;
+            }
+        }
+
+        if self.emulated_mouse_touch.is_none() {
+            // Follow the earliest touch that is still held.
+            self.emulated_mouse_touch = self
+                .touch
+                .ids_to_contacts
+                .iter()
+                .filter(|(id, _)| **id != EMULATED_TOUCH_ID)
+                .min_by_key(|(_, contact)| contact.0)
+                .map(|(id, _)| *id);
+            if self.emulated_mouse_touch.is_some() {
+                self.mouse.handle_button_down(MouseButton::Left);
+            }
+        }
+
+        if let Some(id) = self.emulated_mouse_touch {
+            let contact = self.touch.ids_to_contacts[&id];
+            self.mouse.pos = self.touch.held_contacts.get(&contact).copied();
+        }
+    }
+
+    pub(crate) fn set_scale_factor(&mut self, scale_factor: f64) {
+        self.mouse.scale_factor = scale_factor;
+        self.touch.scale_factor = scale_factor;
+    }
+
     pub(crate) fn update(&mut self) {
         self.keyboard.update();
         self.mouse.update();
         self.touch.update();
     }
+
+    /// Captures the currently held input, for recording.
+    pub(crate) fn snapshot(&self) -> InputFrame {
+        let mut keys = self.keyboard.held_keys.iter().cloned().collect::<Vec<_>>();
+        keys.sort();
+        let mut repeated_keys = self
+            .keyboard
+            .repeated_keys
+            .iter()
+            .cloned()
+            .collect::<Vec<_>>();
+        repeated_keys.sort();
+        let mut logical_keys = self
+            .keyboard
+            .logical_keys
+            .iter()
+            .map(|(key, logical_key)| (*key, logical_key.clone()))
+            .collect::<Vec<_>>();
+        logical_keys.sort_by_key(|(key, _)| *key);
+        let mut mouse_buttons = self
+            .mouse
+            .mouse_buttons_held
+            .iter()
+            .cloned()
+            .collect::<Vec<_>>();
+        mouse_buttons.sort();
+        let mut contacts = self
+            .touch
+            .held_contacts
+            .iter()
+            .map(|(contact, pos)| (contact.0, (pos.x, pos.y)))
+            .collect::<Vec<_>>();
+        contacts.sort_by_key(|(id, _)| *id);
+
+        InputFrame {
+            keys,
+            repeated_keys,
+            logical_keys,
+            mouse_buttons,
+            mouse_position: self.mouse.pos.map(|pos| (pos.x, pos.y)),
+            scroll_lines: self.mouse.scroll_lines.into(),
+            scroll_pixels: self.mouse.scroll_pixels.into(),
+            contacts,
+        }
+    }
+
+    /// Replaces the currently held input, for playback.
+    ///
+    /// The previously held input is kept, such that presses and releases are still detected.
+    pub(crate) fn apply(&mut self, frame: &InputFrame) {
+        self.keyboard.held_keys = frame.keys.iter().cloned().collect();
+        self.keyboard.repeated_keys = frame.repeated_keys.iter().cloned().collect();
+        self.keyboard.logical_keys = frame.logical_keys.iter().cloned().collect();
+        self.mouse.mouse_buttons_held = frame.mouse_buttons.iter().cloned().collect();
+        self.mouse.pos = frame
+            .mouse_position
+            .map(|(x, y)| PhysicalPosition::new(x, y));
+        self.mouse.scroll_lines = frame.scroll_lines.into();
+        self.mouse.scroll_pixels = frame.scroll_pixels.into();
+        self.touch.held_contacts = frame
+            .contacts
+            .iter()
+            .map(|(id, (x, y))| (Contact(*id), PhysicalPosition::new(*x, *y)))
+            .collect();
+    }
 }
 
 /// Mouse state.
@@ -185,6 +443,9 @@ pub struct Mouse {
     last_mouse_buttons_held: HashSet<MouseButton>,
     mouse_buttons_held: HashSet<MouseButton>,
     pos: Option<PhysicalPosition<f64>>,
+    scroll_lines: math::Vec2,
+    scroll_pixels: math::Vec2,
+    scale_factor: f64,
 }
 
 impl Mouse {
@@ -193,6 +454,9 @@ impl Mouse {
             last_mouse_buttons_held: HashSet::new(),
             mouse_buttons_held: HashSet::new(),
             pos: None,
+            scroll_lines: math::Vec2::ZERO,
+            scroll_pixels: math::Vec2::ZERO,
+            scale_factor: 1.0,
         }
     }
 
@@ -219,6 +483,34 @@ impl Mouse {
             .map(|pos| math::Vec2::new(pos.x as f32, pos.y as f32))
     }
 
+    /// Gets the current position of the mouse in logical coordinates, i.e. divided by the window's scale factor.
+    ///
+    /// May return [`None`] if the mouse is not within the confines of the window.
+    pub fn position_logical(&self) -> Option<math::Vec2> {
+        self.position().map(|pos| pos / self.scale_factor as f32)
+    }
+
+    /// Gets the current position of the mouse in world coordinates of the given camera.
+    ///
+    /// May return [`None`] if the mouse is not within the confines of the window.
+    pub fn position_in(&self, camera: &Camera2D) -> Option<math::Vec2> {
+        self.position().map(|pos| camera.screen_to_world(pos))
+    }
+
+    /// Gets how far the mouse wheel was scrolled since the last tick, in lines.
+    ///
+    /// Positive values scroll up and to the left, i.e. moving the content down and to the right. This is typically reported by mouse wheels, while touchpads report [`Mouse::scroll_pixels`] instead, so games should usually handle both.
+    pub fn scroll_lines(&self) -> math::Vec2 {
+        self.scroll_lines
+    }
+
+    /// Gets how far the mouse wheel or touchpad was scrolled since the last tick, in physical pixels.
+    ///
+    /// Positive values scroll up and to the left, like [`Mouse::scroll_lines`].
+    pub fn scroll_pixels(&self) -> math::Vec2 {
+        self.scroll_pixels
+    }
+
     pub(crate) fn handle_button_up(&mut self, button: MouseButton) {
         self.mouse_buttons_held.remove(&button);
     }
@@ -231,8 +523,19 @@ impl Mouse {
         self.pos = pos;
     }
 
+    pub(crate) fn handle_scroll(&mut self, delta: MouseScrollDelta) {
+        match delta {
+            MouseScrollDelta::LineDelta(x, y) => self.scroll_lines += math::Vec2::new(x, y),
+            MouseScrollDelta::PixelDelta(pos) => {
+                self.scroll_pixels += math::Vec2::new(pos.x as f32, pos.y as f32)
+            }
+        }
+    }
+
     fn update(&mut self) {
         self.last_mouse_buttons_held
             .clone_from(&self.mouse_buttons_held);
+        self.scroll_lines = math::Vec2::ZERO;
+        self.scroll_pixels = math::Vec2::ZERO;
     }
 }
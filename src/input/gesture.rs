@@ -0,0 +1,162 @@
+//! Multi-touch gestures.
+//!
+//! A [`TransformGesture`] turns the movement of one or two fingers into a translation, scale and rotation, and a [`TouchCamera`] uses it to drive a [`Camera2D`] like a map: drag to pan, pinch to zoom and twist to rotate.
+//!
+//! ```ignore
+//! // In update:
+//! self.touch_camera.update(&ctxt.input.touch, &mut self.camera);
+//! ```
+
+use std::collections::HashMap;
+
+use super::{Contact, Touch};
+use crate::graphics::camera::Camera2D;
+use crate::math::{self, Vec2};
+
+/// Movement of the fingers on the screen since the last update.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Transform {
+    /// Point between the fingers, in canvas pixels, before moving.
+    pub from: Vec2,
+
+    /// Point between the fingers, in canvas pixels, after moving.
+    pub to: Vec2,
+
+    /// Factor the distance between two fingers changed by, or 1 for a single finger.
+    pub scale: f32,
+
+    /// Angle in radians the line between two fingers turned by (clockwise on screen), or 0 for a single finger.
+    pub rotation: f32,
+}
+
+/// Recognizes dragging, pinching and twisting from touch contacts.
+///
+/// Only the two longest held contacts are used, such that resting a third finger on the screen doesn't make the gesture jump.
+#[derive(Default)]
+pub struct TransformGesture {
+    /// Position during the last update and start order of each contact.
+    last_positions: HashMap<Contact, (Vec2, u64)>,
+    next_order: u64,
+}
+
+impl TransformGesture {
+    /// Creates a gesture recognizer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gets how the fingers moved since the last update, or `None` if no finger was held during both updates.
+    ///
+    /// Call this once per tick.
+    pub fn update(&mut self, touch: &Touch) -> Option<Transform> {
+        let mut contacts = touch
+            .held_contacts()
+            .filter_map(|(contact, pos)| {
+                let (from, order) = *self.last_positions.get(&contact)?;
+                Some((order, from, pos))
+            })
+            .collect::<Vec<_>>();
+        contacts.sort_by_key(|(order, _, _)| *order);
+
+        let last_positions = std::mem::take(&mut self.last_positions);
+        for (contact, pos) in touch.held_contacts() {
+            let order = match last_positions.get(&contact) {
+                Some((_, order)) => *order,
+                None => {
+                    self.next_order += 1;
+                    self.next_order
+                }
+            };
+            self.last_positions.insert(contact, (pos, order));
+        }
+
+        match contacts[..] {
+            [] => None,
+            [(_, from, to)] => Some(Transform {
+                from,
+                to,
+                scale: 1.0,
+                rotation: 0.0,
+            }),
+            [(_, from_a, to_a), (_, from_b, to_b), ..] => {
+                let (from_span, to_span) = (from_b - from_a, to_b - to_a);
+                let scale = if from_span.length() > f32::EPSILON {
+                    to_span.length() / from_span.length()
+                } else {
+                    1.0
+                };
+                Some(Transform {
+                    from: (from_a + from_b) / 2.0,
+                    to: (to_a + to_b) / 2.0,
+                    scale,
+                    rotation: math::wrap_angle(to_span.to_angle() - from_span.to_angle()),
+                })
+            }
+        }
+    }
+}
+
+/// Pans, zooms and rotates a camera with touch gestures, keeping the world under the fingers.
+pub struct TouchCamera {
+    /// Whether dragging pans the camera.
+    pub pan: bool,
+
+    /// Whether pinching zooms the camera.
+    pub zoom: bool,
+
+    /// Whether twisting rotates the camera.
+    pub rotate: bool,
+
+    /// Smallest zoom pinching can reach.
+    pub min_zoom: f32,
+
+    /// Largest zoom pinching can reach.
+    pub max_zoom: f32,
+
+    gesture: TransformGesture,
+}
+
+impl Default for TouchCamera {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TouchCamera {
+    /// Creates a controller that pans and zooms, but doesn't rotate.
+    pub fn new() -> Self {
+        Self {
+            pan: true,
+            zoom: true,
+            rotate: false,
+            min_zoom: 0.1,
+            max_zoom: 10.0,
+            gesture: TransformGesture::new(),
+        }
+    }
+
+    /// Applies the fingers' movement since the last update to the camera, returning whether a gesture is in progress.
+    ///
+    /// Call this once per tick.
+    pub fn update(&mut self, touch: &Touch, camera: &mut Camera2D) -> bool {
+        let Some(transform) = self.gesture.update(touch) else {
+            return false;
+        };
+
+        // Zoom and rotate around the point between the fingers, then move the world point that was there to where the fingers are now.
+        let anchor = camera.screen_to_world(transform.from);
+        if self.zoom {
+            camera.zoom = (camera.zoom * transform.scale).clamp(self.min_zoom, self.max_zoom);
+        }
+        if self.rotate {
+            camera.rotation = math::wrap_angle(camera.rotation - transform.rotation);
+        }
+        let target = if self.pan {
+            transform.to
+        } else {
+            transform.from
+        };
+        camera.position += anchor - camera.screen_to_world(target);
+        true
+    }
+}
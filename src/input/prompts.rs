@@ -0,0 +1,257 @@
+//! Gamepad button prompts.
+//!
+//! Button names differ between controller families: the bottom face button is A on Xbox controllers, Cross on PlayStation controllers and B on Nintendo Switch controllers. [`Layout`] maps buttons of the standard gamepad layout to the names and glyphs of each family, such that hints show the button the player actually sees:
+//!
+//! ```ignore
+//! let layout = Layout::detect(&gamepad_name);
+//! ui.label(&format!("Press {} to jump", layout.label(Button::South)));
+//!
+//! // Or with a prompt sprite sheet:
+//! let sheet = self.prompts.get_or_load(ctxt.gfx).layer(0).unwrap();
+//! canvas.draw(prompts::slice(sheet, UVec2::splat(32), layout, Button::South).unwrap(), transform);
+//! ```
+//!
+//! Buttons are identified by position rather than by name, like the [W3C standard gamepad](https://w3c.github.io/gamepad/#remapping), so gamepad libraries map onto them directly.
+
+use crate::graphics::TextureSlice;
+use crate::math::{IVec2, UVec2};
+
+/// A button of the standard gamepad layout, named by position.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Button {
+    /// Bottom face button.
+    South,
+
+    /// Right face button.
+    East,
+
+    /// Left face button.
+    West,
+
+    /// Top face button.
+    North,
+
+    /// Left shoulder button.
+    LeftBumper,
+
+    /// Right shoulder button.
+    RightBumper,
+
+    /// Left trigger.
+    LeftTrigger,
+
+    /// Right trigger.
+    RightTrigger,
+
+    /// Left center button, e.g. Back, View, Share or Minus.
+    Select,
+
+    /// Right center button, e.g. Start, Menu, Options or Plus.
+    Start,
+
+    /// Pressing the left stick.
+    LeftStick,
+
+    /// Pressing the right stick.
+    RightStick,
+
+    /// D-pad up.
+    DPadUp,
+
+    /// D-pad down.
+    DPadDown,
+
+    /// D-pad left.
+    DPadLeft,
+
+    /// D-pad right.
+    DPadRight,
+
+    /// Center logo button, e.g. Guide, PS or Home.
+    Home,
+}
+
+impl Button {
+    /// All buttons, in the order of the standard gamepad layout.
+    pub const ALL: [Button; 17] = [
+        Button::South,
+        Button::East,
+        Button::West,
+        Button::North,
+        Button::LeftBumper,
+        Button::RightBumper,
+        Button::LeftTrigger,
+        Button::RightTrigger,
+        Button::Select,
+        Button::Start,
+        Button::LeftStick,
+        Button::RightStick,
+        Button::DPadUp,
+        Button::DPadDown,
+        Button::DPadLeft,
+        Button::DPadRight,
+        Button::Home,
+    ];
+
+    /// Gets the button's index in the standard gamepad layout, as used by the browser Gamepad API.
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Gets the button at the given index in the standard gamepad layout.
+    pub fn from_index(index: usize) -> Option<Self> {
+        Self::ALL.get(index).copied()
+    }
+}
+
+/// A controller family, which determines how buttons are named and drawn.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum Layout {
+    /// Xbox controllers, and most PC controllers.
+    Xbox,
+
+    /// PlayStation controllers.
+    PlayStation,
+
+    /// Nintendo Switch controllers.
+    Switch,
+
+    /// Unknown controllers, with buttons named by position.
+    #[default]
+    Generic,
+}
+
+impl Layout {
+    /// All layouts, in the order of rows of a prompt sprite sheet.
+    pub const ALL: [Layout; 4] = [
+        Layout::Xbox,
+        Layout::PlayStation,
+        Layout::Switch,
+        Layout::Generic,
+    ];
+
+    /// Guesses the layout from a controller's name, as reported by the platform.
+    pub fn detect(name: &str) -> Self {
+        let name = name.to_lowercase();
+        let matches = |patterns: &[&str]| patterns.iter().any(|p| name.contains(p));
+        if matches(&["xbox", "xinput", "x-box", "045e"]) {
+            Layout::Xbox
+        } else if matches(&[
+            "playstation",
+            "dualshock",
+            "dualsense",
+            "ps3",
+            "ps4",
+            "ps5",
+            "sony",
+            "054c",
+        ]) {
+            Layout::PlayStation
+        } else if matches(&["nintendo", "switch", "joy-con", "pro controller", "057e"]) {
+            Layout::Switch
+        } else {
+            Layout::Generic
+        }
+    }
+
+    /// Gets the name of a button as printed on the controller or used in the platform's own prompts, e.g. "A" or "Cross".
+    pub fn label(self, button: Button) -> &'static str {
+        match (self, button) {
+            (Layout::Xbox, Button::South) => "A",
+            (Layout::Xbox, Button::East) => "B",
+            (Layout::Xbox, Button::West) => "X",
+            (Layout::Xbox, Button::North) => "Y",
+            (Layout::Xbox, Button::LeftBumper) => "LB",
+            (Layout::Xbox, Button::RightBumper) => "RB",
+            (Layout::Xbox, Button::LeftTrigger) => "LT",
+            (Layout::Xbox, Button::RightTrigger) => "RT",
+            (Layout::Xbox, Button::Select) => "View",
+            (Layout::Xbox, Button::Start) => "Menu",
+            (Layout::Xbox, Button::LeftStick) => "LS",
+            (Layout::Xbox, Button::RightStick) => "RS",
+            (Layout::Xbox, Button::Home) => "Xbox",
+
+            (Layout::PlayStation, Button::South) => "Cross",
+            (Layout::PlayStation, Button::East) => "Circle",
+            (Layout::PlayStation, Button::West) => "Square",
+            (Layout::PlayStation, Button::North) => "Triangle",
+            (Layout::PlayStation, Button::LeftBumper) => "L1",
+            (Layout::PlayStation, Button::RightBumper) => "R1",
+            (Layout::PlayStation, Button::LeftTrigger) => "L2",
+            (Layout::PlayStation, Button::RightTrigger) => "R2",
+            (Layout::PlayStation, Button::Select) => "Share",
+            (Layout::PlayStation, Button::Start) => "Options",
+            (Layout::PlayStation, Button::LeftStick) => "L3",
+            (Layout::PlayStation, Button::RightStick) => "R3",
+            (Layout::PlayStation, Button::Home) => "PS",
+
+            // Nintendo swaps the face buttons compared to Xbox controllers.
+            (Layout::Switch, Button::South) => "B",
+            (Layout::Switch, Button::East) => "A",
+            (Layout::Switch, Button::West) => "Y",
+            (Layout::Switch, Button::North) => "X",
+            (Layout::Switch, Button::LeftBumper) => "L",
+            (Layout::Switch, Button::RightBumper) => "R",
+            (Layout::Switch, Button::LeftTrigger) => "ZL",
+            (Layout::Switch, Button::RightTrigger) => "ZR",
+            (Layout::Switch, Button::Select) => "-",
+            (Layout::Switch, Button::Start) => "+",
+            (Layout::Switch, Button::LeftStick) => "L Stick",
+            (Layout::Switch, Button::RightStick) => "R Stick",
+            (Layout::Switch, Button::Home) => "Home",
+
+            (Layout::Generic, Button::South) => "Bottom",
+            (Layout::Generic, Button::East) => "Right",
+            (Layout::Generic, Button::West) => "Left",
+            (Layout::Generic, Button::North) => "Top",
+            (Layout::Generic, Button::LeftBumper) => "L1",
+            (Layout::Generic, Button::RightBumper) => "R1",
+            (Layout::Generic, Button::LeftTrigger) => "L2",
+            (Layout::Generic, Button::RightTrigger) => "R2",
+            (Layout::Generic, Button::Select) => "Select",
+            (Layout::Generic, Button::Start) => "Start",
+            (Layout::Generic, Button::LeftStick) => "L3",
+            (Layout::Generic, Button::RightStick) => "R3",
+            (Layout::Generic, Button::Home) => "Home",
+
+            (_, Button::DPadUp) => "Up",
+            (_, Button::DPadDown) => "Down",
+            (_, Button::DPadLeft) => "Left",
+            (_, Button::DPadRight) => "Right",
+        }
+    }
+
+    /// Gets a standardized glyph name for a button, e.g. `xbox_a` or `playstation_cross`, for looking up prompt images by name.
+    pub fn glyph(self, button: Button) -> String {
+        let layout = match self {
+            Layout::Xbox => "xbox",
+            Layout::PlayStation => "playstation",
+            Layout::Switch => "switch",
+            Layout::Generic => "generic",
+        };
+        let label = match button {
+            Button::DPadUp => "dpad_up",
+            Button::DPadDown => "dpad_down",
+            Button::DPadLeft => "dpad_left",
+            Button::DPadRight => "dpad_right",
+            _ if self == Layout::Switch && button == Button::Select => "minus",
+            _ if self == Layout::Switch && button == Button::Start => "plus",
+            _ => self.label(button),
+        };
+        format!("{layout}_{}", label.to_lowercase().replace(' ', "_"))
+    }
+}
+
+/// Gets the prompt image of a button from a sprite sheet of equally sized cells.
+///
+/// The sheet has a row for each layout in the order of [`Layout::ALL`], and a column for each button in the order of [`Button::ALL`].
+pub fn slice(
+    sheet: TextureSlice,
+    cell_size: UVec2,
+    layout: Layout,
+    button: Button,
+) -> Option<TextureSlice> {
+    let row = Layout::ALL.iter().position(|l| *l == layout).unwrap();
+    let pos = UVec2::new(button.index() as u32, row as u32) * cell_size;
+    sheet.slice(IVec2::new(pos.x as i32, pos.y as i32), cell_size)
+}
@@ -0,0 +1,155 @@
+//! Background jobs for CPU-heavy work.
+//!
+//! [`crate::futures::spawn`] is meant for waiting on I/O, and on WASM runs everything on the main thread. [`Jobs`] instead runs closures on a pool of worker threads shared by the whole game, such as world generation or pathfinding, and collects their results to be drained in [`crate::Game::update`]:
+//!
+//! ```ignore
+//! // When a chunk comes into view:
+//! self.chunk_jobs.spawn(move || (coords, generate_chunk(seed, coords)));
+//!
+//! // In update:
+//! for (coords, chunk) in self.chunk_jobs.completed() {
+//!     self.chunks.insert(coords, chunk);
+//! }
+//! ```
+//!
+//! With the `rayon` feature, jobs run on rayon's global thread pool, such that they share cores with `Context::par_scope` rather than competing for them.
+//!
+//! On WASM, threads are not available without a dedicated build setup, so jobs instead run on the main thread while draining, for up to [`WASM_BUDGET`] per call. Splitting work into many small jobs keeps frames smooth there.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+
+/// How long [`Jobs::completed`] may spend running jobs on the main thread on WASM. At least one job is run per call.
+pub const WASM_BUDGET: Duration = Duration::from_millis(4);
+
+#[cfg(all(not(target_arch = "wasm32"), feature = "rayon"))]
+mod pool {
+    /// Queues a job onto rayon's thread pool.
+    pub fn run(job: Box<dyn FnOnce() + Send>) {
+        rayon::spawn(job);
+    }
+}
+
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "rayon")))]
+mod pool {
+    use std::sync::{mpsc, Arc, Mutex, OnceLock};
+
+    type Job = Box<dyn FnOnce() + Send>;
+
+    static POOL: OnceLock<mpsc::Sender<Job>> = OnceLock::new();
+
+    /// Queues a job onto the worker threads, starting them on first use.
+    pub fn run(job: Job) {
+        POOL.get_or_init(|| {
+            let (sender, receiver) = mpsc::channel::<Job>();
+            let receiver = Arc::new(Mutex::new(receiver));
+
+            // Leave a core for the main thread.
+            let threads = std::thread::available_parallelism()
+                .map_or(1, |n| n.get().saturating_sub(1))
+                .max(1);
+            for i in 0..threads {
+                let receiver = receiver.clone();
+                std::thread::Builder::new()
+                    .name(format!("teenygame-job-{i}"))
+                    .spawn(move || loop {
+                        let Ok(job) = receiver.lock().unwrap().recv() else {
+                            return;
+                        };
+                        job();
+                    })
+                    .expect("failed to spawn job thread");
+            }
+            sender
+        })
+        .send(job)
+        .unwrap();
+    }
+}
+
+/// A queue of jobs whose results are collected in completion order.
+pub struct Jobs<T> {
+    sender: mpsc::Sender<T>,
+    receiver: mpsc::Receiver<T>,
+    pending: Arc<AtomicUsize>,
+
+    #[cfg(target_arch = "wasm32")]
+    queue: std::collections::VecDeque<Box<dyn FnOnce()>>,
+}
+
+impl<T> Default for Jobs<T>
+where
+    T: Send + 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Jobs<T>
+where
+    T: Send + 'static,
+{
+    /// Creates an empty job queue.
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            sender,
+            receiver,
+            pending: Arc::new(AtomicUsize::new(0)),
+
+            #[cfg(target_arch = "wasm32")]
+            queue: Default::default(),
+        }
+    }
+
+    /// Runs a job in the background. Its result is returned by [`Jobs::completed`] once it finishes.
+    ///
+    /// If the job panics, the panic is logged and no result is produced.
+    pub fn spawn(&mut self, f: impl FnOnce() -> T + Send + 'static) {
+        let sender = self.sender.clone();
+        let pending = self.pending.clone();
+        pending.fetch_add(1, Ordering::Relaxed);
+        let job = move || {
+            match std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)) {
+                // The queue may have been dropped, in which case the result is no longer wanted.
+                Ok(result) => _ = sender.send(result),
+                Err(_) => log::error!("background job panicked"),
+            }
+            pending.fetch_sub(1, Ordering::Relaxed);
+        };
+
+        #[cfg(not(target_arch = "wasm32"))]
+        pool::run(Box::new(job));
+
+        #[cfg(target_arch = "wasm32")]
+        self.queue.push_back(Box::new(job));
+    }
+
+    /// Takes the results of all jobs that have finished since the last call.
+    pub fn completed(&mut self) -> impl Iterator<Item = T> + '_ {
+        #[cfg(target_arch = "wasm32")]
+        {
+            let start = crate::time::Instant::now();
+            while let Some(job) = self.queue.pop_front() {
+                job();
+                if start.elapsed() >= WASM_BUDGET {
+                    break;
+                }
+            }
+        }
+
+        self.receiver.try_iter()
+    }
+
+    /// Gets the number of jobs that haven't finished yet.
+    pub fn pending(&self) -> usize {
+        self.pending.load(Ordering::Relaxed)
+    }
+
+    /// Checks if all jobs have finished. Their results may not have been taken yet.
+    pub fn is_idle(&self) -> bool {
+        self.pending() == 0
+    }
+}
@@ -0,0 +1,123 @@
+//! Launch options.
+//!
+//! Options are parsed from command-line arguments on native platforms, and from the page's URL query parameters on WASM:
+//!
+//! - `--windowed` or `?windowed` sets the flag `windowed`.
+//! - `--scale=2` or `?scale=2` sets the option `scale` to `2`.
+//! - Any other command-line arguments are positional.
+//!
+//! This is handy for debug switches and level overrides:
+//!
+//! ```ignore
+//! let level = ctxt.launch_options().get_parsed::<u32>("level").unwrap_or(1);
+//! ```
+
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+/// Options the game was launched with.
+#[derive(Clone, Default, Debug)]
+pub struct LaunchOptions {
+    options: Vec<(String, Option<String>)>,
+    positional: Vec<String>,
+}
+
+impl LaunchOptions {
+    /// Parses options from command-line style arguments, not including the program name.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Self {
+        let mut launch_options = Self::default();
+        for arg in args {
+            let Some(option) = arg.strip_prefix("--").filter(|o| !o.is_empty()) else {
+                launch_options.positional.push(arg);
+                continue;
+            };
+            launch_options.options.push(match option.split_once('=') {
+                Some((name, value)) => (name.to_string(), Some(value.to_string())),
+                None => (option.to_string(), None),
+            });
+        }
+        launch_options
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn from_env() -> Self {
+        Self::parse(std::env::args().skip(1))
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn from_env() -> Self {
+        let Some(href) = web_sys::window().and_then(|w| w.location().href().ok()) else {
+            return Self::default();
+        };
+        let Ok(url) = url::Url::parse(&href) else {
+            return Self::default();
+        };
+        Self {
+            options: url
+                .query_pairs()
+                .map(|(name, value)| {
+                    (
+                        name.into_owned(),
+                        Some(value.into_owned()).filter(|v| !v.is_empty()),
+                    )
+                })
+                .collect(),
+            positional: vec![],
+        }
+    }
+
+    /// Checks if an option or flag was given.
+    pub fn has(&self, name: &str) -> bool {
+        self.options.iter().any(|(n, _)| n == name)
+    }
+
+    /// Gets the value of an option.
+    ///
+    /// If an option was given multiple times, the last value wins.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .rev()
+            .find(|(n, _)| n == name)
+            .and_then(|(_, value)| value.as_deref())
+    }
+
+    /// Gets the value of an option, parsed as the given type.
+    pub fn get_parsed<T>(&self, name: &str) -> Option<T>
+    where
+        T: FromStr,
+    {
+        self.get(name).and_then(|value| value.parse().ok())
+    }
+
+    /// Gets all options and flags, in the order they were given.
+    pub fn options(&self) -> impl Iterator<Item = (&str, Option<&str>)> + '_ {
+        self.options
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_deref()))
+    }
+
+    /// Gets the positional arguments.
+    pub fn positional(&self) -> &[String] {
+        &self.positional
+    }
+
+    /// Checks if the `windowed` flag was given.
+    pub fn windowed(&self) -> bool {
+        self.has("windowed")
+    }
+
+    /// Gets the value of the `scale` option.
+    pub fn scale(&self) -> Option<f32> {
+        self.get_parsed("scale")
+    }
+}
+
+static LAUNCH_OPTIONS: OnceLock<LaunchOptions> = OnceLock::new();
+
+/// Gets the options the game was launched with.
+///
+/// This is also available as [`crate::Context::launch_options`].
+pub fn launch_options() -> &'static LaunchOptions {
+    LAUNCH_OPTIONS.get_or_init(LaunchOptions::from_env)
+}
@@ -14,33 +14,177 @@ const _: () = assert!(
     "must enable one of smol or tokio for non-wasm environments"
 );
 
+const _: () = assert!(
+    cfg!(any(
+        target_arch = "wasm32",
+        not(feature = "matchbox"),
+        feature = "tokio"
+    )),
+    "the matchbox feature requires tokio on non-wasm environments, as its WebRTC stack runs on a tokio runtime"
+);
+
+pub mod accessibility;
+pub mod animation;
 #[cfg(feature = "audio")]
 pub mod audio;
+pub mod bench;
 pub mod file;
 pub mod futures;
+#[cfg(all(feature = "image", not(target_arch = "wasm32")))]
+pub mod golden;
 pub mod graphics;
+pub mod haptics;
+#[cfg(all(not(target_arch = "wasm32"), feature = "hot-reload"))]
+pub mod hot_reload;
 pub mod image;
 pub mod input;
+pub mod jobs;
+pub mod launch;
+pub mod loading;
 pub mod math;
+#[cfg(feature = "net")]
+pub mod net;
+#[cfg(feature = "physics")]
+pub mod physics;
+pub mod power;
+pub mod progress;
+pub mod replay;
+pub mod report;
+pub mod rollback;
+#[cfg(feature = "image")]
+pub mod screenshot;
+#[cfg(feature = "scripting")]
+pub mod scripting;
+pub mod state;
+pub mod stats;
+pub mod storage;
+pub mod streaming;
+pub mod system;
 pub mod time;
+pub mod ui;
+#[cfg(feature = "world")]
+pub mod world;
 
+#[cfg(target_os = "android")]
+mod android;
+#[cfg(target_os = "macos")]
+mod macos;
 mod marker;
+#[cfg(not(any(target_arch = "wasm32", target_os = "android", target_os = "ios")))]
+mod placement;
+#[cfg(target_arch = "wasm32")]
+mod web;
 
 pub use teenygame_macro::game;
 
+/// The underlying windowing library, for use with [`Game::raw_window_event`].
+pub use winit;
+
+#[cfg(feature = "rayon")]
+pub use rayon;
+
 #[cfg(feature = "audio")]
 use audio::Audio;
 use canvasette::Canvas;
 use graphics::Graphics;
 use input::InputState;
+#[cfg(feature = "physics")]
+use physics::PhysicsWorld;
+use replay::{Playback, Replay};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
-use time::Instant;
+use time::{Instant, Stepping, Timing};
 use winit::event::WindowEvent;
 use winit::event::{KeyEvent, TouchPhase};
 use winit::keyboard::PhysicalKey;
 
+/// Storage key for the game state saved when the game is suspended.
+const SUSPENDED_STATE_KEY: &str = "suspended-state";
+
+/// Replay passed to [`run_replay`], to be picked up when the application is created.
+static PENDING_REPLAY: std::sync::Mutex<Option<Replay>> = std::sync::Mutex::new(None);
+
 struct GraphicsState {
     canvasette_renderer: canvasette::Renderer,
+    splash: Option<graphics::Texture>,
+    white_pixel: graphics::Texture,
+
+    /// Configuration the surface was created with, which is reused with only the size changed when it must be reconfigured.
+    surface_config: wgpu::SurfaceConfiguration,
+}
+
+/// Reconfigures the surface with the given configuration, changing only its size to match the window.
+fn configure_surface(
+    config: &mut wgpu::SurfaceConfiguration,
+    surface: &wgpu::Surface,
+    device: &wgpu::Device,
+    size: winit::dpi::PhysicalSize<u32>,
+) {
+    config.width = size.width.max(1);
+    config.height = size.height.max(1);
+    surface.configure(device, config);
+}
+
+/// Acquires the next frame to draw to, first reconfiguring the surface if it no longer matches the window.
+///
+/// If no frame could be acquired, another redraw is requested to try again.
+fn acquire_frame(
+    config: &mut wgpu::SurfaceConfiguration,
+    surface: &wgpu::Surface,
+    device: &wgpu::Device,
+    window: &winit::window::Window,
+) -> Option<wgpu::SurfaceTexture> {
+    // wginit reconfigures the surface when the window is resized, so reconfigure it again with our configuration and device.
+    let inner_size = window.inner_size();
+    if (config.width, config.height) != (inner_size.width.max(1), inner_size.height.max(1)) {
+        configure_surface(config, surface, device, inner_size);
+    }
+
+    match surface.get_current_texture() {
+        Ok(frame) => Some(frame),
+        Err(e @ (wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated)) => {
+            // The surface no longer matches the window (e.g. it moved to another monitor), so reconfigure it and skip this frame.
+            log::warn!("{e}, reconfiguring surface");
+            configure_surface(config, surface, device, inner_size);
+            window.request_redraw();
+            None
+        }
+        Err(e) => {
+            // Timeouts and running out of memory may be transient, so try again next frame.
+            log::warn!("failed to acquire next frame: {e}");
+            window.request_redraw();
+            None
+        }
+    }
+}
+
+/// A device requested from the adapter to replace the window's after it was lost.
+struct RecoveredDevice {
+    /// The lost device, such that the replacement is only used while the window still has it.
+    lost: graphics::DeviceId,
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+}
+
+impl RecoveredDevice {
+    /// Gets whether this replaces the given device.
+    fn replaces(&self, device: &wgpu::Device) -> bool {
+        self.lost == graphics::DeviceId::of(device)
+    }
+}
+
+/// Gets the device and queue to render with, which are the window's unless it lost its device.
+fn active_device<'a>(
+    recovered: &'a Option<RecoveredDevice>,
+    wgpu: &'a wginit::Wgpu,
+) -> (&'a wgpu::Device, &'a wgpu::Queue) {
+    match recovered {
+        Some(recovered) if recovered.replaces(&wgpu.device) => {
+            (&recovered.device, &recovered.queue)
+        }
+        _ => (&wgpu.device, &wgpu.queue),
+    }
 }
 
 struct Application<G> {
@@ -48,7 +192,12 @@ struct Application<G> {
     audio: Audio,
 
     input_state: InputState,
-    game: G,
+
+    /// Constructed on the first redraw, once the splash image is on screen, as constructing it may take a while.
+    game: Option<G>,
+
+    #[cfg(feature = "physics")]
+    physics: PhysicsWorld,
 
     gfx_state: Option<GraphicsState>,
 
@@ -56,6 +205,50 @@ struct Application<G> {
     tokio_rt: tokio::runtime::Runtime,
 
     update_ticker: UpdateTicker,
+    stepping: Stepping,
+    haptics: haptics::Haptics,
+    timing: Timing,
+    replay: Replay,
+    playback: Option<Playback>,
+    fullscreen: bool,
+
+    /// Size and scale factor of the window the game was last told about.
+    size: Option<(math::UVec2, f64)>,
+
+    /// System theme the game was last told about.
+    theme: Option<system::Theme>,
+
+    /// Window placement to save, if [`Game::REMEMBER_WINDOW_PLACEMENT`] is enabled.
+    #[cfg(not(any(target_arch = "wasm32", target_os = "android", target_os = "ios")))]
+    placement: Option<placement::Placement>,
+
+    /// Passes announcements on to the screen reader, created once the window exists.
+    #[cfg(all(
+        feature = "accesskit",
+        not(any(target_arch = "wasm32", target_os = "android", target_os = "ios"))
+    ))]
+    accesskit: Option<accessibility::native::Adapter>,
+
+    /// Set from the device lost callback, which may be called on any thread.
+    device_lost: Arc<AtomicBool>,
+
+    recovered_device: Option<RecoveredDevice>,
+
+    splash_done: bool,
+
+    #[cfg(feature = "debug")]
+    quick_save: Option<Vec<u8>>,
+
+    #[cfg(feature = "debug")]
+    gizmos: graphics::debug::Gizmos,
+
+    frame_uniforms: graphics::post::FrameUniforms,
+
+    /// Screenshots requested during the current frame.
+    #[cfg(feature = "image")]
+    screenshots: Vec<screenshot::Action>,
+
+    event_sender: EventSender<G::Event>,
 }
 
 struct UpdateTicker {
@@ -65,108 +258,485 @@ struct UpdateTicker {
 }
 
 impl UpdateTicker {
-    fn new(ticks_per_second: u32) -> Self {
+    fn new(stepping: &Stepping) -> Self {
         Self {
-            tick_interval: Duration::from_secs(1) / ticks_per_second as u32,
+            tick_interval: stepping.tick_interval(),
             current_draw_time: Instant::now(),
             draw_time_accumulator: Duration::ZERO,
         }
     }
 
-    fn start_draw(&mut self) {
+    /// Follows changes to the tick rate, keeping the accumulated fraction of a tick.
+    fn sync_tick_interval(&mut self, stepping: &Stepping) {
+        let tick_interval = stepping.tick_interval();
+        if tick_interval == self.tick_interval {
+            return;
+        }
+        self.draw_time_accumulator = self
+            .draw_time_accumulator
+            .mul_f64(tick_interval.as_secs_f64() / self.tick_interval.as_secs_f64());
+        self.tick_interval = tick_interval;
+    }
+
+    fn start_draw(&mut self, stepping: &Stepping) -> Duration {
+        self.sync_tick_interval(stepping);
         let new_redraw_time = Instant::now();
         let frame_time = new_redraw_time - self.current_draw_time;
         self.current_draw_time = new_redraw_time;
-        self.draw_time_accumulator += frame_time;
+        self.draw_time_accumulator += stepping.scale(frame_time);
+        frame_time
     }
 
-    fn tick(&mut self) -> bool {
+    fn tick(&mut self, stepping: &mut Stepping) -> bool {
+        // The game may have changed the tick rate during the previous tick.
+        self.sync_tick_interval(stepping);
+        if stepping.take_step() {
+            return true;
+        }
         if self.draw_time_accumulator < self.tick_interval {
             return false;
         }
         self.draw_time_accumulator -= self.tick_interval;
         true
     }
+
+    /// Gets the fraction of a tick accumulated towards the next tick.
+    fn alpha(&self) -> f32 {
+        (self.draw_time_accumulator.as_secs_f32() / self.tick_interval.as_secs_f32()).min(1.0)
+    }
 }
 
-impl<G> wginit::ApplicationHandler for Application<G>
+impl<G> Application<G>
 where
     G: Game,
 {
-    type UserEvent = std::convert::Infallible;
+    /// Releases graphics resources and saves the game state, in case we are terminated before being resumed.
+    fn suspend(&mut self) {
+        #[cfg(not(any(target_arch = "wasm32", target_os = "android", target_os = "ios")))]
+        if let Some(placement) = &self.placement {
+            placement.save();
+        }
 
-    fn new(_user_event_sender: wginit::UserEventSender<Self::UserEvent>) -> Self {
-        #[cfg(all(not(target_arch = "wasm32"), feature = "tokio"))]
-        let tokio_rt = tokio::runtime::Runtime::new().unwrap();
+        self.gfx_state = None;
 
         #[cfg(feature = "audio")]
-        let audio = Audio::new().unwrap();
+        self.audio.set_suspended(true);
 
-        let input_state = InputState::new();
+        let Some(game) = &mut self.game else {
+            return;
+        };
+        game.suspended();
+        let state = game.save_state();
+        if !state.is_empty() {
+            if let Err(e) = storage::set(SUSPENDED_STATE_KEY, &state) {
+                log::warn!("failed to save suspended state: {e}");
+            }
+        }
+    }
 
-        Self {
-            game: G::new(),
-            gfx_state: None,
+    /// Creates graphics resources for the window and tells the game it was resumed.
+    fn resume(&mut self, window: &winit::window::Window, wgpu: &wginit::Wgpu) {
+        report::set_gpu_info(&wgpu.adapter.get_info());
+        #[cfg(not(target_arch = "wasm32"))]
+        graphics::check_adapter(&wgpu.adapter);
 
-            #[cfg(feature = "audio")]
-            audio,
-            input_state,
+        // The window may have been given a new device since we replaced its lost one.
+        if !matches!(&self.recovered_device, Some(recovered) if recovered.replaces(&wgpu.device)) {
+            self.recovered_device = None;
+        }
+        let (device, queue) = active_device(&self.recovered_device, wgpu);
 
-            #[cfg(all(not(target_arch = "wasm32"), feature = "tokio"))]
-            tokio_rt,
+        self.device_lost.store(false, Ordering::Relaxed);
+        let device_lost = self.device_lost.clone();
+        device.set_device_lost_callback(move |reason, message| {
+            // The device is also reported as lost when we drop it ourselves.
+            if reason == wgpu::DeviceLostReason::Unknown {
+                log::error!("graphics device lost: {message}");
+                device_lost.store(true, Ordering::Relaxed);
+            }
+        });
+
+        #[cfg(target_arch = "wasm32")]
+        web::prevent_default(window, G::WEB_PREVENTED_KEYS, G::WEB_PREVENT_CONTEXT_MENU);
 
-            update_ticker: UpdateTicker::new(G::TICKS_PER_SECOND),
+        // The menu bar only exists once the event loop runs, so it can't be extended before.
+        #[cfg(target_os = "macos")]
+        macos::add_window_menu();
+
+        #[cfg(all(
+            feature = "accesskit",
+            not(any(target_arch = "wasm32", target_os = "android", target_os = "ios"))
+        ))]
+        if self.accesskit.is_none() {
+            self.accesskit = accessibility::native::Adapter::new(window);
         }
-    }
 
-    fn resumed(&mut self, ctxt: &wginit::Context) {
-        let window = ctxt.window.unwrap();
-        let wgpu = ctxt.wgpu.unwrap();
+        // Only restore the placement when the game starts, not when it is resumed later.
+        #[cfg(not(any(target_arch = "wasm32", target_os = "android", target_os = "ios")))]
+        if G::REMEMBER_WINDOW_PLACEMENT && self.placement.is_none() {
+            let saved = placement::Placement::load();
+            if let Some(saved) = &saved {
+                saved.apply(window);
+            }
+            self.placement = Some(saved.unwrap_or_else(|| placement::Placement::of(window)));
+        }
+
+        // Configure the surface ourselves, such that it can be reconfigured the same way later, including for a replacement device.
+        let size = window.inner_size();
+        let surface_config = wgpu
+            .surface
+            .get_default_config(&wgpu.adapter, size.width.max(1), size.height.max(1))
+            .unwrap();
+        wgpu.surface.configure(device, &surface_config);
 
-        let canvasette_renderer = canvasette::Renderer::new(
-            &wgpu.device,
-            wgpu.surface.get_capabilities(&wgpu.adapter).formats[0],
+        let mut canvasette_renderer = canvasette::Renderer::new(device, surface_config.format);
+
+        let white_pixel = graphics::Texture::create(
+            device,
+            queue,
+            graphics::white_pixel_img(),
+            graphics::TextureOptions::default(),
         );
 
+        let splash = if self.splash_done {
+            None
+        } else {
+            G::splash().map(|img| {
+                Graphics {
+                    canvasette_renderer: &mut canvasette_renderer,
+                    adapter: &wgpu.adapter,
+                    device,
+                    queue,
+                    format: surface_config.format,
+                    window: graphics::Window::new(window),
+                    frame_uniforms: &mut self.frame_uniforms,
+                    white_pixel: &white_pixel,
+                    #[cfg(feature = "image")]
+                    screenshots: &mut self.screenshots,
+                }
+                .load_texture(img)
+            })
+        };
+
         self.gfx_state = Some(GraphicsState {
             canvasette_renderer,
+            splash,
+            white_pixel,
+            surface_config,
         });
 
-        let gfx_state = self.gfx_state.as_mut().unwrap();
+        #[cfg(feature = "audio")]
+        self.audio.set_suspended(false);
+
+        // If the game was constructed, we were not terminated while suspended, so the saved state is no longer needed. Otherwise it is restored once the game is.
+        if self.game.is_some() {
+            _ = storage::remove(SUSPENDED_STATE_KEY);
+        }
 
-        self.game.resumed(&mut Context {
+        self.resume_game(window, wgpu);
+    }
+
+    /// Tells the game it was resumed, if it was constructed.
+    fn resume_game(&mut self, window: &winit::window::Window, wgpu: &wginit::Wgpu) {
+        let (Some(game), Some(gfx_state)) = (&mut self.game, &mut self.gfx_state) else {
+            return;
+        };
+        let (device, queue) = active_device(&self.recovered_device, wgpu);
+
+        game.resumed(&mut Context {
             input: &self.input_state,
+            time: &self.timing,
+            replay: &self.replay,
+            event_sender: &self.event_sender,
+            stepping: &mut self.stepping,
+            haptics: &mut self.haptics,
+            #[cfg(feature = "debug")]
+            debug: &mut self.gizmos,
             #[cfg(feature = "audio")]
             audio: &mut self.audio,
+            #[cfg(feature = "physics")]
+            physics: &mut self.physics,
             gfx: &mut Graphics {
                 canvasette_renderer: &mut gfx_state.canvasette_renderer,
-                wgpu,
-                window,
+                adapter: &wgpu.adapter,
+                device,
+                queue,
+                format: gfx_state.surface_config.format,
+                window: graphics::Window::new(window),
+                frame_uniforms: &mut self.frame_uniforms,
+                white_pixel: &gfx_state.white_pixel,
+                #[cfg(feature = "image")]
+                screenshots: &mut self.screenshots,
             },
         });
     }
 
-    fn suspended(&mut self, _ctxt: &wginit::Context) {
+    /// Shows the splash image, if any, then constructs the game and tells it that it was resumed.
+    fn start_game(&mut self, window: &winit::window::Window, wgpu: &wginit::Wgpu) {
+        let gfx_state = self.gfx_state.as_mut().unwrap();
+        if let Some(splash) = &gfx_state.splash {
+            let (device, queue) = active_device(&self.recovered_device, wgpu);
+            let mut canvas = Canvas::new();
+            draw_splash(&mut canvas, splash, window);
+            if let Some(frame) =
+                acquire_frame(&mut gfx_state.surface_config, &wgpu.surface, device, window)
+            {
+                graphics::render_to_texture(
+                    device,
+                    queue,
+                    &mut gfx_state.canvasette_renderer,
+                    &canvas,
+                    frame.texture.size(),
+                    &frame
+                        .texture
+                        .create_view(&wgpu::TextureViewDescriptor::default()),
+                );
+                window.pre_present_notify();
+                frame.present();
+            }
+        }
+
+        let mut game = G::new();
+
+        // Restore state if the game was suspended and then terminated by the OS, unless playing back a replay, which must start from a fresh game to match the recorded run.
+        if self.playback.is_none() {
+            match storage::get(SUSPENDED_STATE_KEY) {
+                Ok(Some(state)) => {
+                    game.load_state(&state);
+                    _ = storage::remove(SUSPENDED_STATE_KEY);
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    log::warn!("failed to read suspended state: {e}");
+                }
+            }
+        }
+
+        // Later changes arrive as window events.
+        if let Some(theme) = window.theme() {
+            self.theme = Some(theme);
+            game.theme_changed(theme);
+        }
+
+        self.game = Some(game);
+        self.resume_game(window, wgpu);
+
+        // Don't count the time spent constructing the game towards the first update.
+        self.update_ticker = UpdateTicker::new(&self.stepping);
+        window.request_redraw();
+    }
+
+    /// Replaces the lost device with a new one from the same adapter, then resumes with it, returning whether that succeeded.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn recover_device(&mut self, window: &winit::window::Window, wgpu: &wginit::Wgpu) -> bool {
+        let (lost, _) = active_device(&self.recovered_device, wgpu);
+        let descriptor = wgpu::DeviceDescriptor {
+            label: Some("teenygame: device"),
+            required_features: lost.features(),
+            required_limits: lost.limits(),
+            memory_hints: wgpu::MemoryHints::default(),
+        };
+        let (device, queue) =
+            match ::futures::executor::block_on(wgpu.adapter.request_device(&descriptor, None)) {
+                Ok(device) => device,
+                Err(e) => {
+                    log::error!("failed to recreate graphics device: {e}");
+                    return false;
+                }
+            };
+        log::info!("recreated graphics device");
+
+        // wginit still reconfigures the surface with the lost device when the window is resized, which fails, so log its errors instead of panicking.
+        wgpu.device.on_uncaptured_error(Box::new(|e| {
+            log::warn!("error on lost graphics device: {e}");
+        }));
+
+        self.recovered_device = Some(RecoveredDevice {
+            lost: graphics::DeviceId::of(&wgpu.device),
+            device,
+            queue,
+        });
         self.gfx_state = None;
-        self.game.suspended();
+        self.resume(window, wgpu);
+        true
+    }
+}
+
+impl<G> wginit::ApplicationHandler for Application<G>
+where
+    G: Game,
+{
+    type UserEvent = G::Event;
+
+    fn new(user_event_sender: wginit::UserEventSender<Self::UserEvent>) -> Self {
+        #[cfg(all(not(target_arch = "wasm32"), feature = "tokio"))]
+        let tokio_rt = tokio::runtime::Runtime::new().unwrap();
+
+        #[cfg(feature = "audio")]
+        let audio = Audio::new().unwrap();
+
+        let input_state = InputState::new();
+        let stepping = Stepping::new(G::TICKS_PER_SECOND);
+
+        let playback = PENDING_REPLAY.lock().unwrap().take().map(Playback::new);
+        let seed = playback
+            .as_ref()
+            .map_or_else(math::rng::Rng::seed_from_time, |playback| {
+                playback.replay().seed()
+            });
+        replay::set_seed(seed);
+
+        Self {
+            game: None,
+            gfx_state: None,
+
+            #[cfg(feature = "physics")]
+            physics: PhysicsWorld::new(),
+
+            #[cfg(feature = "audio")]
+            audio,
+            input_state,
+
+            #[cfg(all(not(target_arch = "wasm32"), feature = "tokio"))]
+            tokio_rt,
+
+            update_ticker: UpdateTicker::new(&stepping),
+            stepping,
+            haptics: haptics::Haptics::new(),
+            timing: Timing::new(),
+            replay: Replay::new(seed),
+            playback,
+            fullscreen: false,
+            size: None,
+            theme: None,
+            #[cfg(not(any(target_arch = "wasm32", target_os = "android", target_os = "ios")))]
+            placement: None,
+            #[cfg(all(
+                feature = "accesskit",
+                not(any(target_arch = "wasm32", target_os = "android", target_os = "ios"))
+            ))]
+            accesskit: None,
+            device_lost: Arc::new(AtomicBool::new(false)),
+            recovered_device: None,
+            splash_done: false,
+
+            #[cfg(feature = "debug")]
+            quick_save: None,
+
+            #[cfg(feature = "debug")]
+            gizmos: graphics::debug::Gizmos::new(),
+
+            frame_uniforms: Default::default(),
+
+            #[cfg(feature = "image")]
+            screenshots: vec![],
+
+            event_sender: EventSender(EventSenderInner::EventLoop(user_event_sender)),
+        }
+    }
+
+    fn resumed(&mut self, ctxt: &wginit::Context) {
+        self.resume(ctxt.window.unwrap(), ctxt.wgpu.unwrap());
+    }
+
+    fn suspended(&mut self, _ctxt: &wginit::Context) {
+        self.suspend();
     }
 
     fn window_event(&mut self, _ctxt: &wginit::Context, event: winit::event::WindowEvent) {
+        if let Some(game) = &mut self.game {
+            game.raw_window_event(&event);
+        }
+
+        #[cfg(not(any(target_arch = "wasm32", target_os = "android", target_os = "ios")))]
+        if let (WindowEvent::CloseRequested, Some(placement)) = (&event, &self.placement) {
+            placement.save();
+        }
+
+        #[cfg(all(
+            feature = "accesskit",
+            not(any(target_arch = "wasm32", target_os = "android", target_os = "ios"))
+        ))]
+        if let (WindowEvent::Focused(focused), Some(accesskit)) = (&event, &mut self.accesskit) {
+            accesskit.set_focused(*focused);
+        }
+
+        // The theme may change at any time, e.g. when the OS switches to dark mode at night.
+        if let (WindowEvent::ThemeChanged(theme), Some(game)) = (&event, &mut self.game) {
+            if self.theme != Some(*theme) {
+                self.theme = Some(*theme);
+                game.theme_changed(*theme);
+            }
+        }
+
+        if self.playback.is_some()
+            && matches!(
+                event,
+                WindowEvent::KeyboardInput { .. }
+                    | WindowEvent::MouseInput { .. }
+                    | WindowEvent::CursorMoved { .. }
+                    | WindowEvent::CursorLeft { .. }
+                    | WindowEvent::MouseWheel { .. }
+                    | WindowEvent::Touch(..)
+            )
+        {
+            // Live input is ignored while playing back a replay.
+            return;
+        }
+
         match event {
             WindowEvent::KeyboardInput {
                 event:
                     KeyEvent {
                         physical_key: PhysicalKey::Code(key_code),
+                        logical_key,
                         state,
+                        repeat,
                         ..
                     },
                 ..
             } => match state {
                 winit::event::ElementState::Pressed => {
-                    self.input_state.keyboard.handle_key_down(key_code);
+                    self.input_state
+                        .keyboard
+                        .handle_key_down(key_code, logical_key, repeat);
+
+                    // Debug keys are not consumed, as the game sees the key press via its input state regardless.
+                    #[cfg(feature = "debug")]
+                    if !repeat {
+                        if Some(key_code) == G::QUICK_SAVE_KEY {
+                            if let Some(game) = &self.game {
+                                self.quick_save = Some(game.save_state());
+                            }
+                        }
+                        if Some(key_code) == G::QUICK_LOAD_KEY {
+                            if let (Some(game), Some(state)) = (&mut self.game, &self.quick_save) {
+                                game.load_state(state);
+                            }
+                        }
+                        if Some(key_code) == G::PAUSE_KEY {
+                            self.stepping.set_paused(!self.stepping.is_paused());
+                        }
+                        if Some(key_code) == G::SLOW_MOTION_KEY {
+                            self.stepping
+                                .set_time_scale(if self.stepping.time_scale() == 1.0 {
+                                    0.1
+                                } else {
+                                    1.0
+                                });
+                        }
+                    }
+
+                    // Holding the step key keeps stepping, following the OS's key repeat.
+                    #[cfg(feature = "debug")]
+                    if Some(key_code) == G::STEP_KEY {
+                        self.stepping.step();
+                    }
                 }
                 winit::event::ElementState::Released => {
-                    self.input_state.keyboard.handle_key_up(key_code);
+                    self.input_state
+                        .keyboard
+                        .handle_key_up(key_code, logical_key);
                 }
             },
             WindowEvent::MouseInput { state, button, .. } => match state {
@@ -183,6 +753,9 @@ where
             WindowEvent::CursorLeft { .. } => {
                 self.input_state.mouse.set_position(None);
             }
+            WindowEvent::MouseWheel { delta, .. } => {
+                self.input_state.mouse.handle_scroll(delta);
+            }
             WindowEvent::Touch(touch) => {
                 match touch.phase {
                     TouchPhase::Started => {
@@ -202,6 +775,20 @@ where
             }
             _ => {}
         };
+
+        if G::EMULATE_TOUCH_FROM_MOUSE {
+            self.input_state.emulate_touch_from_mouse();
+        }
+        if G::EMULATE_MOUSE_FROM_TOUCH {
+            self.input_state.emulate_mouse_from_touch();
+        }
+    }
+
+    fn user_event(&mut self, _ctxt: &wginit::Context, event: Self::UserEvent) {
+        // Events can only be sent via senders handed to the game, so it exists by now.
+        if let Some(game) = &mut self.game {
+            game.user_event(event);
+        }
     }
 
     fn redraw(&mut self, window: &winit::window::Window, wgpu: &wginit::Wgpu) {
@@ -209,104 +796,577 @@ where
         #[cfg(all(not(target_arch = "wasm32"), feature = "tokio"))]
         let _guard = self.tokio_rt.enter();
 
+        if self.device_lost.load(Ordering::Relaxed) {
+            // Browsers only hand out devices asynchronously, so the device cannot be replaced there.
+            #[cfg(target_arch = "wasm32")]
+            let recovered = false;
+            #[cfg(not(target_arch = "wasm32"))]
+            let recovered = self.gfx_state.is_some() && self.recover_device(window, wgpu);
+
+            // Nothing can be drawn without a device, so if it was not replaced, treat losing it like being suspended, such that progress is saved.
+            if self.gfx_state.is_some() && !recovered {
+                self.suspend();
+            }
+            if self.gfx_state.is_none() {
+                return;
+            }
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        if G::FIT_CANVAS_TO_PARENT {
+            web::fit_canvas_to_parent(window);
+        }
+
+        if self.game.is_none() {
+            self.start_game(window, wgpu);
+            return;
+        }
+        let game = self.game.as_mut().unwrap();
+
+        self.input_state.set_scale_factor(window.scale_factor());
+
+        // Fullscreen may also be entered or left by the user (e.g. pressing Escape in the browser), so poll for changes.
+        let fullscreen = window.fullscreen().is_some();
+        if fullscreen != self.fullscreen {
+            self.fullscreen = fullscreen;
+            game.fullscreen_changed(fullscreen);
+        }
+
+        #[cfg(not(any(target_arch = "wasm32", target_os = "android", target_os = "ios")))]
+        if let Some(placement) = &mut self.placement {
+            placement.update(window);
+        }
+
+        #[cfg(all(
+            feature = "accesskit",
+            not(any(target_arch = "wasm32", target_os = "android", target_os = "ios"))
+        ))]
+        if let Some(accesskit) = &mut self.accesskit {
+            accesskit.update();
+        }
+
+        // The surface is reconfigured as soon as the window is resized, so by the time we draw it matches the new size.
+        let inner_size = window.inner_size();
+        let size = (
+            math::UVec2::new(inner_size.width, inner_size.height),
+            window.scale_factor(),
+        );
+        if self.size != Some(size) {
+            self.size = Some(size);
+            game.resized(size.0, size.1);
+        }
+
         let gfx_state = self.gfx_state.as_mut().unwrap();
+        let (device, queue) = active_device(&self.recovered_device, wgpu);
 
-        self.update_ticker.start_draw();
-        while self.update_ticker.tick() {
-            self.game.update(&mut Context {
+        let frame_time = self.update_ticker.start_draw(&self.stepping);
+        self.timing.record_frame_time(frame_time);
+        self.frame_uniforms.advance(self.stepping.scale(frame_time));
+
+        #[cfg(feature = "audio")]
+        self.audio.update();
+
+        let update_start_time = Instant::now();
+        while self.update_ticker.tick(&mut self.stepping) {
+            if let Some(playback) = &mut self.playback {
+                if playback.is_finished() {
+                    // Release all recorded input and hand control back to live input.
+                    self.input_state.apply(&Default::default());
+                    self.playback = None;
+                } else if let Some(frame) = playback.next() {
+                    self.input_state.apply(frame);
+                }
+            }
+            if G::RECORD_REPLAY {
+                self.replay.record(self.input_state.snapshot());
+            }
+
+            // Only gizmos from the latest update are drawn.
+            #[cfg(feature = "debug")]
+            self.gizmos.clear();
+
+            game.update(&mut Context {
                 input: &self.input_state,
+                time: &self.timing,
+                replay: &self.replay,
+                event_sender: &self.event_sender,
+                stepping: &mut self.stepping,
+                haptics: &mut self.haptics,
+                #[cfg(feature = "debug")]
+                debug: &mut self.gizmos,
                 #[cfg(feature = "audio")]
                 audio: &mut self.audio,
+                #[cfg(feature = "physics")]
+                physics: &mut self.physics,
                 gfx: &mut Graphics {
                     canvasette_renderer: &mut gfx_state.canvasette_renderer,
-                    wgpu,
-                    window,
+                    adapter: &wgpu.adapter,
+                    device,
+                    queue,
+                    format: gfx_state.surface_config.format,
+                    window: graphics::Window::new(window),
+                    frame_uniforms: &mut self.frame_uniforms,
+                    white_pixel: &gfx_state.white_pixel,
+                    #[cfg(feature = "image")]
+                    screenshots: &mut self.screenshots,
                 },
             });
+            #[cfg(feature = "physics")]
+            self.physics
+                .step(self.update_ticker.tick_interval.as_secs_f32());
             self.input_state.update();
+            self.timing.record_tick();
+        }
+        self.timing
+            .record_update_duration(update_start_time.elapsed());
+        self.timing.record_alpha(self.update_ticker.alpha());
+
+        if gfx_state.splash.is_some() && loading::progress().is_done() && game.is_loaded() {
+            gfx_state.splash = None;
+            self.splash_done = true;
         }
 
+        let draw_start_time = Instant::now();
+        #[cfg(feature = "debug")]
+        let update_gizmos_len = self.gizmos.len();
         let mut canvas = Canvas::new();
-        self.game.draw(
-            &mut Context {
-                input: &self.input_state,
-                #[cfg(feature = "audio")]
-                audio: &mut self.audio,
-                gfx: &mut Graphics {
-                    canvasette_renderer: &mut gfx_state.canvasette_renderer,
-                    wgpu,
-                    window,
+        if let Some(splash) = &gfx_state.splash {
+            draw_splash(&mut canvas, splash, window);
+        } else {
+            game.draw(
+                &mut Context {
+                    input: &self.input_state,
+                    time: &self.timing,
+                    replay: &self.replay,
+                    event_sender: &self.event_sender,
+                    stepping: &mut self.stepping,
+                    haptics: &mut self.haptics,
+                    #[cfg(feature = "debug")]
+                    debug: &mut self.gizmos,
+                    #[cfg(feature = "audio")]
+                    audio: &mut self.audio,
+                    #[cfg(feature = "physics")]
+                    physics: &mut self.physics,
+                    gfx: &mut Graphics {
+                        canvasette_renderer: &mut gfx_state.canvasette_renderer,
+                        adapter: &wgpu.adapter,
+                        device,
+                        queue,
+                        format: gfx_state.surface_config.format,
+                        window: graphics::Window::new(window),
+                        frame_uniforms: &mut self.frame_uniforms,
+                        white_pixel: &gfx_state.white_pixel,
+                        #[cfg(feature = "image")]
+                        screenshots: &mut self.screenshots,
+                    },
                 },
-            },
-            &mut canvas,
-        );
+                &mut canvas,
+            );
 
-        let frame = wgpu
-            .surface
-            .get_current_texture()
-            .expect("failed to acquire next swap chain texture");
+            #[cfg(feature = "debug")]
+            {
+                self.gizmos.flush(
+                    &mut Graphics {
+                        canvasette_renderer: &mut gfx_state.canvasette_renderer,
+                        adapter: &wgpu.adapter,
+                        device,
+                        queue,
+                        format: gfx_state.surface_config.format,
+                        window: graphics::Window::new(window),
+                        frame_uniforms: &mut self.frame_uniforms,
+                        white_pixel: &gfx_state.white_pixel,
+                        #[cfg(feature = "image")]
+                        screenshots: &mut self.screenshots,
+                    },
+                    &mut canvas,
+                    gfx_state.white_pixel.layer(0).unwrap(),
+                );
+                self.gizmos.truncate(update_gizmos_len);
+            }
+        }
+        self.timing.record_draw_duration(draw_start_time.elapsed());
+
+        let Some(frame) =
+            acquire_frame(&mut gfx_state.surface_config, &wgpu.surface, device, window)
+        else {
+            return;
+        };
 
         graphics::render_to_texture(
-            wgpu,
+            device,
+            queue,
             &mut gfx_state.canvasette_renderer,
             &canvas,
-            &frame.texture,
+            frame.texture.size(),
+            &frame
+                .texture
+                .create_view(&wgpu::TextureViewDescriptor::default()),
         );
 
+        #[cfg(feature = "image")]
+        if !self.screenshots.is_empty() {
+            screenshot::run(
+                screenshot::capture(
+                    device,
+                    queue,
+                    &mut gfx_state.canvasette_renderer,
+                    &canvas,
+                    frame.texture.size(),
+                    frame.texture.format(),
+                ),
+                std::mem::take(&mut self.screenshots),
+            );
+        }
+
+        #[cfg(feature = "image")]
+        screenshot::poll(device);
+
         window.pre_present_notify();
         frame.present();
+
+        #[cfg(target_arch = "wasm32")]
+        web::notify_ready();
         window.request_redraw();
     }
 }
 
+/// Draws the splash image centered in the window, scaled down to fit if needed.
+fn draw_splash<'a>(
+    canvas: &mut Canvas<'a>,
+    splash: &'a graphics::Texture,
+    window: &winit::window::Window,
+) {
+    let Some(slice) = splash.layer(0) else {
+        return;
+    };
+    let window_size = window.inner_size();
+    let window_size = math::vec2(window_size.width as f32, window_size.height as f32);
+    let size = slice.size().as_vec2();
+    let scale = (window_size / size).min_element().min(1.0);
+    canvas.draw(
+        slice,
+        math::Affine2::from_translation(window_size / 2.0)
+            * math::Affine2::from_scale(math::Vec2::splat(scale))
+            * math::Affine2::from_translation(-size / 2.0),
+    );
+}
+
+/// Sends events to the game from anywhere, including background tasks and other threads, waking up the event loop.
+///
+/// Events are delivered to [`Game::user_event`]. An event sender may be retrieved via [`Context::event_sender`].
+pub struct EventSender<E: 'static>(EventSenderInner<E>);
+
+enum EventSenderInner<E: 'static> {
+    EventLoop(wginit::UserEventSender<E>),
+
+    /// Queues events for a game running without an event loop, i.e. in a [`golden::Harness`].
+    #[cfg(all(feature = "image", not(target_arch = "wasm32")))]
+    Queue(std::sync::mpsc::Sender<E>),
+}
+
+impl<E: 'static> Clone for EventSender<E> {
+    fn clone(&self) -> Self {
+        Self(match &self.0 {
+            EventSenderInner::EventLoop(sender) => EventSenderInner::EventLoop(sender.clone()),
+            #[cfg(all(feature = "image", not(target_arch = "wasm32")))]
+            EventSenderInner::Queue(sender) => EventSenderInner::Queue(sender.clone()),
+        })
+    }
+}
+
+impl<E: 'static> EventSender<E> {
+    /// Sends an event to the game.
+    ///
+    /// The event is dropped if the game has already exited.
+    pub fn send(&self, event: E) {
+        match &self.0 {
+            EventSenderInner::EventLoop(sender) => {
+                let _ = sender.send_event(event);
+            }
+            #[cfg(all(feature = "image", not(target_arch = "wasm32")))]
+            EventSenderInner::Queue(sender) => {
+                let _ = sender.send(event);
+            }
+        }
+    }
+}
+
 /// Bag of stuff available to be accessed during [`Game::update`].
-pub struct Context<'a> {
+///
+/// `E` is the game's [`Game::Event`] type, which games without custom events don't need to name.
+///
+/// [`Context::input`], [`Context::time`] and [`Context::replay`] are read-only and may be shared with other threads, e.g. from [`Context::par_scope`], as may senders from [`Context::event_sender`] if the event type is [`Send`]. Audio, physics and graphics must only be used from the thread the game runs on.
+pub struct Context<'a, E: 'static = std::convert::Infallible> {
     /// Input state.
     pub input: &'a InputState,
 
+    /// Timing statistics.
+    pub time: &'a Timing,
+
+    /// Inputs recorded since the game started, which is empty unless [`Game::RECORD_REPLAY`] is enabled.
+    pub replay: &'a Replay,
+
     #[cfg(feature = "audio")]
     /// Audio context.
     pub audio: &'a mut Audio,
 
+    #[cfg(feature = "physics")]
+    /// Physics world.
+    pub physics: &'a mut PhysicsWorld,
+
     /// Graphics context.
     pub gfx: &'a mut Graphics<'a>,
+
+    /// Controls for pausing and slowing down the tick loop.
+    pub stepping: &'a mut Stepping,
+
+    /// Device vibration.
+    pub haptics: &'a mut haptics::Haptics,
+
+    #[cfg(feature = "debug")]
+    /// Debug text and shapes drawn on top of the frame.
+    pub debug: &'a mut graphics::debug::Gizmos,
+
+    event_sender: &'a EventSender<E>,
+}
+
+impl<E: 'static> Context<'_, E> {
+    /// Gets the options the game was launched with, i.e. command-line arguments or URL query parameters.
+    pub fn launch_options(&self) -> &'static launch::LaunchOptions {
+        launch::launch_options()
+    }
+
+    /// Gets the player's accessibility preferences, e.g. to tone down screen shake when reduced motion is preferred.
+    pub fn accessibility(&self) -> accessibility::Preferences {
+        accessibility::preferences()
+    }
+
+    /// Changes how many times [`Game::update`] is called per second from now on. See [`Stepping::set_ticks_per_second`].
+    pub fn set_ticks_per_second(&mut self, ticks_per_second: u32) {
+        self.stepping.set_ticks_per_second(ticks_per_second);
+    }
+
+    /// Gets the system's color theme, if the platform reports one.
+    pub fn theme(&self) -> Option<system::Theme> {
+        self.gfx.window().theme()
+    }
+
+    /// Gets the user's preferred locales, most preferred first. See [`system::locales`].
+    pub fn locales(&self) -> Vec<String> {
+        system::locales()
+    }
+
+    /// Gets the memory currently used by loaded resources.
+    pub fn stats(&self) -> stats::Stats {
+        stats::stats()
+    }
+
+    /// Gets a sender for delivering events to [`Game::user_event`].
+    pub fn event_sender(&self) -> EventSender<E> {
+        self.event_sender.clone()
+    }
+
+    /// Runs a closure in which work may be spawned onto [`rayon`]'s thread pool, returning once all spawned work has finished.
+    ///
+    /// This is intended for splitting heavy simulations across cores within a single tick. Parallel iterators from [`rayon::prelude`] may also be used directly.
+    #[cfg(feature = "rayon")]
+    pub fn par_scope<'s, F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&rayon::Scope<'s>) -> R + Send,
+        R: Send,
+    {
+        rayon::scope(f)
+    }
 }
 
 /// Trait to implement for your game.
 pub trait Game {
+    /// The type of events that may be sent to the game via an [`EventSender`].
+    ///
+    /// Use [`std::convert::Infallible`] if the game doesn't need custom events, which is also the event type of a [`Context`] that doesn't name one.
+    ///
+    /// This was added in teenygame 0.6, so games written against 0.5 need to add it, as associated types can't have defaults. See the changelog for migrating.
+    type Event: 'static;
+
     /// How may times [`Game::update`] should be called per second.
     ///
-    /// Defaults to 60.
+    /// Defaults to 60. May be changed at runtime via [`Context::set_ticks_per_second`].
     const TICKS_PER_SECOND: u32 = 60;
 
+    /// Whether inputs are recorded into [`Context::replay`].
+    ///
+    /// Only changes in input are recorded, but the replay is kept in memory and grows for as long as the game runs, so only games that use replays should enable this. Defaults to false.
+    const RECORD_REPLAY: bool = false;
+
+    /// Whether the canvas should be resized to fill its parent element (or the browser window, if its parent is the body), following changes to the page layout and device pixel ratio.
+    ///
+    /// Only applies on WASM. Defaults to false, where the canvas keeps the size set by the page or [`graphics::Window::set_size`].
+    const FIT_CANVAS_TO_PARENT: bool = false;
+
+    /// Whether the window's position, size, monitor and fullscreen state are saved via [`storage`] when the game exits or is suspended, and restored when it starts.
+    ///
+    /// Only applies on desktop platforms. Defaults to false.
+    const REMEMBER_WINDOW_PLACEMENT: bool = false;
+
+    /// Keys whose default browser behavior (e.g. scrolling the page) is prevented while the game has focus.
+    ///
+    /// Only applies on WASM. Defaults to the arrow keys, space and page navigation keys.
+    const WEB_PREVENTED_KEYS: &'static [input::KeyCode] = &[
+        input::KeyCode::ArrowUp,
+        input::KeyCode::ArrowDown,
+        input::KeyCode::ArrowLeft,
+        input::KeyCode::ArrowRight,
+        input::KeyCode::Space,
+        input::KeyCode::PageUp,
+        input::KeyCode::PageDown,
+        input::KeyCode::Home,
+        input::KeyCode::End,
+    ];
+
+    /// Whether the browser's context menu is prevented from opening when right-clicking the canvas.
+    ///
+    /// Only applies on WASM. Defaults to true.
+    const WEB_PREVENT_CONTEXT_MENU: bool = true;
+
+    /// Namespace for the game's [`storage`] keys, as games hosted on the same site share the browser's local storage.
+    ///
+    /// Only applies on WASM. Defaults to the path of the page's directory (e.g. `/games/pong/`), so the namespace changes if the game is moved.
+    const WEB_STORAGE_NAMESPACE: Option<&'static str> = None;
+
+    /// Key that quick-saves the game via [`Game::save_state`], or [`None`] to disable quick-saving.
+    ///
+    /// Only applies when the `debug` feature is enabled. The key press is still passed on to the game. Defaults to F5.
+    const QUICK_SAVE_KEY: Option<input::KeyCode> = Some(input::KeyCode::F5);
+
+    /// Key that loads the last quick-save via [`Game::load_state`], or [`None`] to disable quick-loading.
+    ///
+    /// Only applies when the `debug` feature is enabled. The key press is still passed on to the game. Defaults to F9.
+    const QUICK_LOAD_KEY: Option<input::KeyCode> = Some(input::KeyCode::F9);
+
+    /// Key that toggles pausing ticks via [`Context::stepping`], or [`None`] to disable it.
+    ///
+    /// Only applies when the `debug` feature is enabled. The key press is still passed on to the game. Defaults to F6.
+    const PAUSE_KEY: Option<input::KeyCode> = Some(input::KeyCode::F6);
+
+    /// Key that advances a single tick while paused, or [`None`] to disable it.
+    ///
+    /// Only applies when the `debug` feature is enabled. The key press is still passed on to the game. Defaults to F7.
+    const STEP_KEY: Option<input::KeyCode> = Some(input::KeyCode::F7);
+
+    /// Key that toggles running ticks at 10% speed, or [`None`] to disable it.
+    ///
+    /// Only applies when the `debug` feature is enabled. The key press is still passed on to the game. Defaults to F8.
+    const SLOW_MOTION_KEY: Option<input::KeyCode> = Some(input::KeyCode::F8);
+
+    /// Whether a touch contact is emulated while the primary mouse button is held, such that touch controls can be tested with a mouse.
+    ///
+    /// Defaults to false.
+    const EMULATE_TOUCH_FROM_MOUSE: bool = false;
+
+    /// Whether the mouse is moved and the primary mouse button is held following the first held touch, such that mouse controls work on touchscreens.
+    ///
+    /// Defaults to false.
+    const EMULATE_MOUSE_FROM_TOUCH: bool = false;
+
+    /// Backends to pick a graphics adapter from, e.g. to force Vulkan when another backend misbehaves.
+    ///
+    /// This is a hint, passed on via the `WGPU_BACKEND` environment variable, which wgpu only honors where the adapter is picked with its environment helpers. A warning is logged if the picked adapter uses another backend. May be overridden with the `backend` launch option (e.g. `--backend=vulkan`) or the `WGPU_BACKEND` environment variable. Only applies on native platforms. Defaults to all backends.
+    const BACKENDS: graphics::Backends = graphics::Backends::all();
+
+    /// Whether to prefer a low-power (e.g. integrated) or high-performance (e.g. discrete) graphics adapter.
+    ///
+    /// Like [`Game::BACKENDS`], this is a hint, passed on via the `WGPU_POWER_PREF` environment variable. May be overridden with the `power` launch option (`--power=low` or `--power=high`) or the `WGPU_POWER_PREF` environment variable. Only applies on native platforms. Defaults to no preference.
+    const POWER_PREFERENCE: graphics::PowerPreference = graphics::PowerPreference::None;
+
     /// Constructs the game.
     ///
-    /// If Tokio support is enabled, the Tokio runtime will be available here.
+    /// Called on the first redraw, once the window is created and the [`Game::splash`] image (if any) is on screen, so expensive setup doesn't leave the window blank. [`Game::resumed`] is called right after. If Tokio support is enabled, the Tokio runtime will be available here.
     fn new() -> Self;
 
+    /// Gets an image to show as soon as the window is created, until the game has finished loading.
+    ///
+    /// This is called before the game is constructed. Loading is finished once all [`loading`] progress is done and [`Game::is_loaded`] returns true. The game is updated as usual in the meantime, but not drawn. Defaults to no splash image.
+    fn splash() -> Option<image::Img<Vec<graphics::Color>>> {
+        None
+    }
+
+    /// Whether the game has finished loading its initial assets, such that the splash image should be hidden.
+    ///
+    /// Defaults to true.
+    fn is_loaded(&self) -> bool {
+        true
+    }
+
     /// The game was resumed (e.g. this is now the foreground app).
-    fn resumed(&mut self, ctxt: &mut Context) {
+    fn resumed(&mut self, ctxt: &mut Context<Self::Event>) {
         _ = ctxt;
     }
 
     /// The game was suspended (e.g. this is no longer the foreground app).
     fn suspended(&mut self) {}
 
+    /// The window entered or left fullscreen.
+    fn fullscreen_changed(&mut self, fullscreen: bool) {
+        _ = fullscreen;
+    }
+
+    /// The window was resized or moved to a monitor with a different scale factor.
+    ///
+    /// This is called once per change, before the next update and draw, and once before the first draw. The size is in physical pixels, such that layouts depending on the window size can be recomputed here rather than every frame.
+    fn resized(&mut self, size: math::UVec2, scale_factor: f64) {
+        _ = (size, scale_factor);
+    }
+
+    /// The system switched between a light and a dark theme.
+    ///
+    /// This is also called once before the first draw, if the platform reports a theme.
+    fn theme_changed(&mut self, theme: system::Theme) {
+        _ = theme;
+    }
+
+    /// An event was sent via an [`EventSender`].
+    fn user_event(&mut self, event: Self::Event) {
+        _ = event;
+    }
+
+    /// A raw window event was received.
+    ///
+    /// This is called for every event before teenygame handles it, including events teenygame doesn't otherwise expose (e.g. touchpad gestures or theme changes). Input is still tracked as usual, so most games don't need this.
+    fn raw_window_event(&mut self, event: &winit::event::WindowEvent) {
+        _ = event;
+    }
+
+    /// Saves a snapshot of the game state.
+    ///
+    /// This is used to persist the game when suspended, in case the OS terminates it, and for quick-saving with [`Game::QUICK_SAVE_KEY`] when the `debug` feature is enabled. Returns an empty snapshot by default, meaning there is no state to save.
+    ///
+    /// See [`serde_state`] for implementing this with [`serde`].
+    fn save_state(&self) -> Vec<u8> {
+        vec![]
+    }
+
+    /// Restores a snapshot of the game state created by [`Game::save_state`].
+    ///
+    /// This is called after [`Game::new`] if the game was terminated while suspended, and for quick-loading with [`Game::QUICK_LOAD_KEY`] when the `debug` feature is enabled.
+    fn load_state(&mut self, state: &[u8]) {
+        _ = state;
+    }
+
     /// Updates the game state [`Game::TICKS_PER_SECOND`] per second.
     ///
     /// This may be called multiple times between calls to [`Game::draw`], depending on the time elapsed. This implements the [fix your timestep](https://gafferongames.com/post/fix_your_timestep/) pattern internally.
     ///
     /// You may not perform any drawing in this function.
-    fn update(&mut self, ctxt: &mut Context);
+    fn update(&mut self, ctxt: &mut Context<Self::Event>);
 
     /// Draws the game state.
-    fn draw<'a>(&'a mut self, ctxt: &mut Context, canvas: &mut Canvas<'a>);
+    fn draw<'a>(&'a mut self, ctxt: &mut Context<Self::Event>, canvas: &mut Canvas<'a>);
 }
 
 /// Runs the game.
 ///
 /// This should be the only function called in your `main`. It will:
-/// - Set up logging (and panic handling for WASM).
+/// - Set up logging and panic handling, including [`report`]ing.
 /// - Create the event loop.
 /// - If enabled and running on a native platform, start the Tokio runtime.
 /// - Starts the event loop and hands over control.
@@ -318,14 +1378,33 @@ where
 {
     #[cfg(not(target_arch = "wasm32"))]
     {
-        env_logger::init();
+        let logger = env_logger::Builder::from_default_env().build();
+        let max_level = logger.filter().max(log::LevelFilter::Error);
+        log::set_boxed_logger(Box::new(report::ReportingLogger(logger))).unwrap();
+        log::set_max_level(max_level);
+
+        graphics::request_adapter(G::BACKENDS, G::POWER_PREFERENCE);
     }
 
     #[cfg(target_arch = "wasm32")]
     {
         console_error_panic_hook::set_once();
         wasm_logger::init(wasm_logger::Config::default());
+        storage::set_namespace(G::WEB_STORAGE_NAMESPACE);
     }
 
+    report::install_panic_hook();
+
     wginit::run::<Application<G>>().unwrap();
 }
+
+/// Runs the game, playing back the recorded inputs of a replay instead of live inputs.
+///
+/// Once all recorded ticks have been played back, live input takes over. See [`replay`] for the requirements on the game for playback to be faithful.
+pub fn run_replay<G>(replay: Replay)
+where
+    G: Game,
+{
+    *PENDING_REPLAY.lock().unwrap() = Some(replay);
+    run::<G>();
+}
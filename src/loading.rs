@@ -0,0 +1,96 @@
+//! Loading progress.
+//!
+//! Every file read with [`crate::file::read`] counts towards the loading [`Progress`], as does any other future wrapped with [`track`]. Games may use this to draw a loading bar.
+//!
+//! On WASM, progress is also reported to the page, such that the HTML shell can replace its blank loading screen with something more informative:
+//!
+//! - A `teenygame-progress` event is dispatched on `window` whenever progress changes, with `detail` set to `{ loaded, total }`.
+//! - A `teenygame-ready` event is dispatched on `window` once the first frame has been drawn.
+//!
+//! ```js
+//! window.addEventListener("teenygame-progress", (e) => {
+//!     progressBar.value = e.detail.loaded / e.detail.total;
+//! });
+//! window.addEventListener("teenygame-ready", () => {
+//!     loadingScreen.remove();
+//! });
+//! ```
+
+use std::future::Future;
+use std::sync::Mutex;
+
+/// Progress of loading.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct Progress {
+    /// Number of loads that have finished.
+    pub loaded: u32,
+
+    /// Number of loads that have started.
+    pub total: u32,
+}
+
+impl Progress {
+    /// Gets the fraction of loads that have finished, from 0 to 1.
+    ///
+    /// If nothing has been loaded yet, this is 1.
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            return 1.0;
+        }
+        self.loaded as f32 / self.total as f32
+    }
+
+    /// Checks if all started loads have finished.
+    pub fn is_done(&self) -> bool {
+        self.loaded >= self.total
+    }
+}
+
+static PROGRESS: Mutex<Progress> = Mutex::new(Progress {
+    loaded: 0,
+    total: 0,
+});
+
+fn update(f: impl FnOnce(&mut Progress)) {
+    let progress = {
+        let mut progress = PROGRESS.lock().unwrap_or_else(|e| e.into_inner());
+        f(&mut progress);
+        *progress
+    };
+
+    #[cfg(target_arch = "wasm32")]
+    crate::web::dispatch_progress(progress);
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let _ = progress;
+}
+
+/// Gets the current loading progress.
+pub fn progress() -> Progress {
+    *PROGRESS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Marks a load as finished when dropped, such that cancelled loads don't hold up progress.
+struct Guard;
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        update(|p| p.loaded += 1);
+    }
+}
+
+/// Counts a future towards the loading progress.
+///
+/// The load starts when this is called, and finishes when the future completes or is dropped.
+pub fn track<F>(fut: F) -> impl Future<Output = F::Output>
+where
+    F: Future,
+{
+    update(|p| p.total += 1);
+    let guard = Guard;
+    async move {
+        let output = fut.await;
+        drop(guard);
+        output
+    }
+}
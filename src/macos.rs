@@ -0,0 +1,70 @@
+//! Glue for running on macOS.
+
+use std::sync::Once;
+
+use objc2::rc::Retained;
+use objc2::runtime::Sel;
+use objc2::sel;
+use objc2_app_kit::{NSApplication, NSEventModifierFlags, NSMenu, NSMenuItem};
+use objc2_foundation::{ns_string, MainThreadMarker, NSString};
+
+/// Adds a Window menu to the menu bar winit creates, with the standard items for toggling fullscreen (Cmd+Ctrl+F) and closing the window (Cmd+W).
+///
+/// Both are sent to the key window: toggling fullscreen is handled by AppKit, which winit follows, and closing goes through the window delegate, so the game sees it as [`winit::event::WindowEvent::CloseRequested`] like clicking the close button. Quitting is already in winit's application menu.
+///
+/// Does nothing after the first call, or if called off the main thread or before the menu bar exists.
+pub fn add_window_menu() {
+    static ADDED: Once = Once::new();
+
+    let Some(mtm) = MainThreadMarker::new() else {
+        return;
+    };
+    let app = NSApplication::sharedApplication(mtm);
+    // SAFETY: Called on the main thread, as ensured by the marker.
+    let Some(menubar) = (unsafe { app.mainMenu() }) else {
+        return;
+    };
+
+    ADDED.call_once(|| {
+        // SAFETY: The menu is allocated on the main thread and initialized with a valid title.
+        let menu = unsafe { NSMenu::initWithTitle(mtm.alloc(), ns_string!("Window")) };
+        menu.addItem(&menu_item(
+            mtm,
+            ns_string!("Toggle Full Screen"),
+            sel!(toggleFullScreen:),
+            ns_string!("f"),
+            NSEventModifierFlags::NSEventModifierFlagCommand
+                | NSEventModifierFlags::NSEventModifierFlagControl,
+        ));
+        menu.addItem(&menu_item(
+            mtm,
+            ns_string!("Close Window"),
+            sel!(performClose:),
+            ns_string!("w"),
+            NSEventModifierFlags::NSEventModifierFlagCommand,
+        ));
+
+        // The menu bar shows the submenu's title.
+        let item = NSMenuItem::new(mtm);
+        item.setSubmenu(Some(&menu));
+        menubar.addItem(&item);
+        // SAFETY: The menu is retained by the menu bar for the lifetime of the app.
+        unsafe { app.setWindowsMenu(Some(&menu)) };
+    });
+}
+
+/// Creates a menu item sending `action` to the first responder, e.g. the key window.
+fn menu_item(
+    mtm: MainThreadMarker,
+    title: &NSString,
+    action: Sel,
+    key: &NSString,
+    modifiers: NSEventModifierFlags,
+) -> Retained<NSMenuItem> {
+    // SAFETY: The item is allocated on the main thread, and has no target, so the action is sent along the responder chain.
+    let item = unsafe {
+        NSMenuItem::initWithTitle_action_keyEquivalent(mtm.alloc(), title, Some(action), key)
+    };
+    item.setKeyEquivalentModifierMask(modifiers);
+    item
+}
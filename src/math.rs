@@ -1,7 +1,19 @@
 //! Various math types and shorthand helpers.
 
+pub mod collision;
+pub mod curve;
+#[cfg(feature = "fixed")]
+pub mod fixed;
+pub mod interpolate;
+pub mod pathfinding;
+pub mod raycast;
+mod rect;
+pub mod rng;
+pub mod spatial;
+
 #[doc(no_inline)]
 pub use glam::*;
+pub use rect::*;
 
 /// Creates a translation matrix to (x, y).
 pub fn translate(x: f32, y: f32) -> Affine2 {
@@ -22,3 +34,138 @@ pub fn rotate(theta: f32) -> Affine2 {
 pub fn uniform_scale(s: f32) -> Affine2 {
     glam::Affine2::from_scale(Vec2::new(s, s))
 }
+
+/// Computes sprite transforms from columns of positions, rotations and uniform scales, as stored in struct-of-arrays layouts.
+///
+/// Each transform is equivalent to `translate(pos) * rotate(rotation) * uniform_scale(scale) * translate(-origin)`, but is computed directly instead of by multiplying matrices. `out` is cleared first, such that it may be reused across frames without allocating.
+///
+/// # Panics
+///
+/// Panics if the columns have different lengths.
+pub fn transforms_from_columns(
+    positions: &[Vec2],
+    rotations: &[f32],
+    scales: &[f32],
+    origin: Vec2,
+    out: &mut Vec<Affine2>,
+) {
+    assert_eq!(positions.len(), rotations.len());
+    assert_eq!(positions.len(), scales.len());
+
+    out.clear();
+    out.extend(
+        positions
+            .iter()
+            .zip(rotations)
+            .zip(scales)
+            .map(|((pos, rotation), scale)| {
+                let (sin, cos) = rotation.sin_cos();
+                let x_axis = Vec2::new(cos, sin) * *scale;
+                let y_axis = Vec2::new(-sin, cos) * *scale;
+                Affine2::from_cols(x_axis, y_axis, *pos - x_axis * origin.x - y_axis * origin.y)
+            }),
+    );
+}
+
+/// Wraps an angle in radians to the range (-π, π].
+pub fn wrap_angle(theta: f32) -> f32 {
+    let wrapped =
+        (theta + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU) - std::f32::consts::PI;
+    if wrapped == -std::f32::consts::PI {
+        std::f32::consts::PI
+    } else {
+        wrapped
+    }
+}
+
+/// Linearly interpolates between two angles in radians by t, taking the shortest way around.
+pub fn lerp_angle(from: f32, to: f32, t: f32) -> f32 {
+    from + wrap_angle(to - from) * t
+}
+
+/// Moves a value towards a target by at most max_delta, without overshooting.
+pub fn move_toward(current: f32, target: f32, max_delta: f32) -> f32 {
+    let delta = target - current;
+    if delta.abs() <= max_delta {
+        target
+    } else {
+        current + delta.signum() * max_delta
+    }
+}
+
+/// Moves a point towards a target by at most max_distance, without overshooting.
+pub fn move_toward_vec2(current: Vec2, target: Vec2, max_distance: f32) -> Vec2 {
+    let delta = target - current;
+    let distance = delta.length();
+    if distance <= max_distance || distance == 0.0 {
+        target
+    } else {
+        current + delta / distance * max_distance
+    }
+}
+
+/// Exponentially decays a value towards a target, at a rate independent of the time step.
+///
+/// This is the frame rate independent version of `current = lerp(current, target, k)`: the larger the rate, the faster the value approaches the target. After $\frac{\ln 2}{\text{rate}}$ seconds, half the distance to the target will have been covered.
+pub fn decay(current: f32, target: f32, rate: f32, dt: f32) -> f32 {
+    target + (current - target) * (-rate * dt).exp()
+}
+
+/// Exponentially decays a point towards a target, at a rate independent of the time step.
+///
+/// See [`decay`] for details.
+pub fn decay_vec2(current: Vec2, target: Vec2, rate: f32, dt: f32) -> Vec2 {
+    target + (current - target) * (-rate * dt).exp()
+}
+
+/// Smoothly moves a value towards a target like a critically damped spring, such that it never overshoots.
+///
+/// `velocity` is the current velocity of the value and must be kept between calls. `smooth_time` is approximately the time it takes to reach the target.
+pub fn smooth_damp(
+    current: f32,
+    target: f32,
+    velocity: &mut f32,
+    smooth_time: f32,
+    dt: f32,
+) -> f32 {
+    let omega = 2.0 / smooth_time.max(0.0001);
+    let x = omega * dt;
+    let exp = 1.0 / (1.0 + x + 0.48 * x * x + 0.235 * x * x * x);
+    let change = current - target;
+    let temp = (*velocity + omega * change) * dt;
+    *velocity = (*velocity - omega * temp) * exp;
+    let mut output = target + (change + temp) * exp;
+
+    // Prevent overshooting.
+    if (target - current > 0.0) == (output > target) {
+        output = target;
+        *velocity = 0.0;
+    }
+    output
+}
+
+/// Smoothly moves a point towards a target like a critically damped spring, such that it never overshoots.
+///
+/// See [`smooth_damp`] for details.
+pub fn smooth_damp_vec2(
+    current: Vec2,
+    target: Vec2,
+    velocity: &mut Vec2,
+    smooth_time: f32,
+    dt: f32,
+) -> Vec2 {
+    let omega = 2.0 / smooth_time.max(0.0001);
+    let x = omega * dt;
+    let exp = 1.0 / (1.0 + x + 0.48 * x * x + 0.235 * x * x * x);
+    let change = current - target;
+    let temp = (*velocity + omega * change) * dt;
+    *velocity = (*velocity - omega * temp) * exp;
+    let mut output = target + (change + temp) * exp;
+
+    // Prevent overshooting.
+    if (target - current).dot(output - target) > 0.0 {
+        output = target;
+        *velocity = Vec2::ZERO;
+    }
+    output
+}
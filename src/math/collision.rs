@@ -0,0 +1,599 @@
+//! 2D collision detection.
+//!
+//! Overlap tests return a [`Contact`] describing how to separate the two shapes, and segment casts return a [`Hit`] describing where the segment first touches a shape.
+//!
+//! Axis-aligned bounding boxes are represented by [`Rect`].
+
+use super::{Affine2, Rect, Vec2};
+
+/// Information about two overlapping shapes.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Contact {
+    /// The unit vector pointing from the first shape towards the second shape, along which the shapes should be separated.
+    pub normal: Vec2,
+
+    /// How far the shapes overlap along the normal.
+    ///
+    /// Moving the second shape by `normal * depth` (or the first shape by `-normal * depth`) will separate the shapes.
+    pub depth: f32,
+}
+
+impl Contact {
+    /// Flips the contact such that it points from the second shape towards the first shape.
+    pub fn flipped(self) -> Self {
+        Self {
+            normal: -self.normal,
+            depth: self.depth,
+        }
+    }
+}
+
+/// Information about where a segment first touches a shape.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Hit {
+    /// How far along the segment the hit occurred, from 0.0 (the start) to 1.0 (the end).
+    pub fraction: f32,
+
+    /// The point where the hit occurred.
+    pub point: Vec2,
+
+    /// The unit surface normal of the shape at the hit point.
+    ///
+    /// If the segment starts inside the shape, this is the opposite of the segment's direction.
+    pub normal: Vec2,
+}
+
+/// A circle.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Circle {
+    /// The center of the circle.
+    pub center: Vec2,
+
+    /// The radius of the circle.
+    pub radius: f32,
+}
+
+impl Circle {
+    /// Creates a new circle.
+    pub fn new(center: Vec2, radius: f32) -> Self {
+        Self { center, radius }
+    }
+
+    /// Checks if the point lies within the circle.
+    pub fn contains(&self, point: Vec2) -> bool {
+        self.center.distance_squared(point) <= self.radius * self.radius
+    }
+
+    /// Gets the bounding box of the circle.
+    pub fn bounds(&self) -> Rect {
+        Rect::from_center_size(self.center, Vec2::splat(self.radius * 2.0))
+    }
+}
+
+/// A convex polygon.
+///
+/// The points may be wound either clockwise or counterclockwise, but the polygon must be convex for collision tests to be correct.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Polygon {
+    /// The vertices of the polygon.
+    pub points: Vec<Vec2>,
+}
+
+impl Polygon {
+    /// Creates a new convex polygon from its vertices.
+    pub fn new(points: Vec<Vec2>) -> Self {
+        Self { points }
+    }
+
+    /// Creates a polygon from a rectangle.
+    pub fn from_rect(rect: &Rect) -> Self {
+        Self::new(vec![
+            rect.min,
+            Vec2::new(rect.max.x, rect.min.y),
+            rect.max,
+            Vec2::new(rect.min.x, rect.max.y),
+        ])
+    }
+
+    /// Transforms every vertex of the polygon, e.g. to rotate it.
+    pub fn transformed(&self, transform: &Affine2) -> Self {
+        Self::new(
+            self.points
+                .iter()
+                .map(|p| transform.transform_point2(*p))
+                .collect(),
+        )
+    }
+
+    /// Gets the average of the vertices of the polygon.
+    pub fn centroid(&self) -> Vec2 {
+        if self.points.is_empty() {
+            return Vec2::ZERO;
+        }
+        self.points.iter().sum::<Vec2>() / self.points.len() as f32
+    }
+
+    /// Gets the bounding box of the polygon.
+    pub fn bounds(&self) -> Rect {
+        let mut min = Vec2::splat(f32::INFINITY);
+        let mut max = Vec2::splat(f32::NEG_INFINITY);
+        for p in self.points.iter() {
+            min = min.min(*p);
+            max = max.max(*p);
+        }
+        Rect::from_min_max(min, max)
+    }
+
+    /// Checks if the point lies within the polygon.
+    pub fn contains(&self, point: Vec2) -> bool {
+        self.edges()
+            .all(|(a, _, normal)| (point - a).dot(normal) <= 0.0)
+    }
+
+    /// Iterates over each edge as (start, end, outward unit normal).
+    fn edges(&self) -> impl Iterator<Item = (Vec2, Vec2, Vec2)> + '_ {
+        let centroid = self.centroid();
+        let n = self.points.len();
+        (0..n).map(move |i| {
+            let a = self.points[i];
+            let b = self.points[(i + 1) % n];
+            let mut normal = (b - a).perp().normalize_or_zero();
+            if (a - centroid).dot(normal) < 0.0 {
+                normal = -normal;
+            }
+            (a, b, normal)
+        })
+    }
+
+    fn project(&self, axis: Vec2) -> (f32, f32) {
+        self.points
+            .iter()
+            .map(|p| p.dot(axis))
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), d| {
+                (min.min(d), max.max(d))
+            })
+    }
+}
+
+/// A line segment.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Segment {
+    /// The start of the segment.
+    pub start: Vec2,
+
+    /// The end of the segment.
+    pub end: Vec2,
+}
+
+impl Segment {
+    /// Creates a new segment.
+    pub fn new(start: Vec2, end: Vec2) -> Self {
+        Self { start, end }
+    }
+
+    /// Gets the vector from the start to the end of the segment.
+    pub fn delta(&self) -> Vec2 {
+        self.end - self.start
+    }
+
+    /// Gets the length of the segment.
+    pub fn length(&self) -> f32 {
+        self.delta().length()
+    }
+
+    /// Gets the point at the given fraction along the segment.
+    pub fn at(&self, fraction: f32) -> Vec2 {
+        self.start + self.delta() * fraction
+    }
+
+    /// Gets the closest point on the segment to the given point.
+    pub fn closest_point(&self, point: Vec2) -> Vec2 {
+        let delta = self.delta();
+        let len_sq = delta.length_squared();
+        if len_sq == 0.0 {
+            return self.start;
+        }
+        self.at(((point - self.start).dot(delta) / len_sq).clamp(0.0, 1.0))
+    }
+
+    fn inside_hit(&self) -> Hit {
+        Hit {
+            fraction: 0.0,
+            point: self.start,
+            normal: -self.delta().normalize_or_zero(),
+        }
+    }
+
+    /// Casts the segment against a rectangle.
+    pub fn cast_rect(&self, rect: &Rect) -> Option<Hit> {
+        if rect.contains(self.start) {
+            return Some(self.inside_hit());
+        }
+
+        // Slab method.
+        let delta = self.delta();
+        let mut t_enter = 0.0f32;
+        let mut t_exit = 1.0f32;
+        let mut normal = Vec2::ZERO;
+
+        for axis in 0..2 {
+            let (start, d, min, max) = (
+                self.start[axis],
+                delta[axis],
+                rect.min[axis],
+                rect.max[axis],
+            );
+            if d == 0.0 {
+                if start < min || start > max {
+                    return None;
+                }
+                continue;
+            }
+
+            let (mut t0, mut t1) = ((min - start) / d, (max - start) / d);
+            let mut n = Vec2::ZERO;
+            n[axis] = -d.signum();
+            if t0 > t1 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+            if t0 > t_enter {
+                t_enter = t0;
+                normal = n;
+            }
+            t_exit = t_exit.min(t1);
+            if t_enter > t_exit {
+                return None;
+            }
+        }
+
+        Some(Hit {
+            fraction: t_enter,
+            point: self.at(t_enter),
+            normal,
+        })
+    }
+
+    /// Casts the segment against a circle.
+    pub fn cast_circle(&self, circle: &Circle) -> Option<Hit> {
+        if circle.contains(self.start) {
+            return Some(self.inside_hit());
+        }
+
+        let delta = self.delta();
+        let offset = self.start - circle.center;
+        let a = delta.length_squared();
+        if a == 0.0 {
+            return None;
+        }
+        let b = offset.dot(delta);
+        let c = offset.length_squared() - circle.radius * circle.radius;
+        let discriminant = b * b - a * c;
+        if discriminant < 0.0 {
+            return None;
+        }
+
+        let t = (-b - discriminant.sqrt()) / a;
+        if !(0.0..=1.0).contains(&t) {
+            return None;
+        }
+
+        let point = self.at(t);
+        Some(Hit {
+            fraction: t,
+            point,
+            normal: (point - circle.center).normalize_or_zero(),
+        })
+    }
+
+    /// Casts the segment against another segment.
+    pub fn cast_segment(&self, other: &Segment) -> Option<Hit> {
+        let d1 = self.delta();
+        let d2 = other.delta();
+        let denom = d1.perp_dot(d2);
+        if denom == 0.0 {
+            // Parallel or collinear.
+            return None;
+        }
+
+        let offset = other.start - self.start;
+        let t = offset.perp_dot(d2) / denom;
+        let u = offset.perp_dot(d1) / denom;
+        if !(0.0..=1.0).contains(&t) || !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+
+        let mut normal = d2.perp().normalize_or_zero();
+        if normal.dot(d1) > 0.0 {
+            normal = -normal;
+        }
+        Some(Hit {
+            fraction: t,
+            point: self.at(t),
+            normal,
+        })
+    }
+
+    /// Casts the segment against a convex polygon.
+    pub fn cast_polygon(&self, polygon: &Polygon) -> Option<Hit> {
+        if polygon.points.len() < 3 {
+            return None;
+        }
+        if polygon.contains(self.start) {
+            return Some(self.inside_hit());
+        }
+
+        // Cyrus-Beck clipping.
+        let delta = self.delta();
+        let mut t_enter = 0.0f32;
+        let mut t_exit = 1.0f32;
+        let mut normal = Vec2::ZERO;
+
+        for (a, _, n) in polygon.edges() {
+            let num = (a - self.start).dot(n);
+            let denom = delta.dot(n);
+            if denom == 0.0 {
+                if num < 0.0 {
+                    return None;
+                }
+                continue;
+            }
+
+            let t = num / denom;
+            if denom < 0.0 {
+                if t > t_enter {
+                    t_enter = t;
+                    normal = n;
+                }
+            } else {
+                t_exit = t_exit.min(t);
+            }
+            if t_enter > t_exit {
+                return None;
+            }
+        }
+
+        Some(Hit {
+            fraction: t_enter,
+            point: self.at(t_enter),
+            normal,
+        })
+    }
+
+    /// Casts the segment against any shape.
+    pub fn cast(&self, shape: &Shape) -> Option<Hit> {
+        match shape {
+            Shape::Rect(rect) => self.cast_rect(rect),
+            Shape::Circle(circle) => self.cast_circle(circle),
+            Shape::Polygon(polygon) => self.cast_polygon(polygon),
+        }
+    }
+}
+
+/// Tests if two rectangles overlap.
+pub fn rect_rect(a: &Rect, b: &Rect) -> Option<Contact> {
+    let overlap = a.max.min(b.max) - a.min.max(b.min);
+    if overlap.x <= 0.0 || overlap.y <= 0.0 {
+        return None;
+    }
+
+    let direction = b.center() - a.center();
+    Some(if overlap.x < overlap.y {
+        Contact {
+            normal: Vec2::new(if direction.x < 0.0 { -1.0 } else { 1.0 }, 0.0),
+            depth: overlap.x,
+        }
+    } else {
+        Contact {
+            normal: Vec2::new(0.0, if direction.y < 0.0 { -1.0 } else { 1.0 }),
+            depth: overlap.y,
+        }
+    })
+}
+
+/// Tests if two circles overlap.
+pub fn circle_circle(a: &Circle, b: &Circle) -> Option<Contact> {
+    let delta = b.center - a.center;
+    let radii = a.radius + b.radius;
+    let dist_sq = delta.length_squared();
+    if dist_sq >= radii * radii {
+        return None;
+    }
+
+    let dist = dist_sq.sqrt();
+    Some(Contact {
+        normal: if dist > 0.0 { delta / dist } else { Vec2::X },
+        depth: radii - dist,
+    })
+}
+
+/// Tests if a circle and a rectangle overlap.
+pub fn circle_rect(a: &Circle, b: &Rect) -> Option<Contact> {
+    let closest = b.clamp(a.center);
+    let delta = closest - a.center;
+    let dist_sq = delta.length_squared();
+
+    if dist_sq > 0.0 {
+        if dist_sq >= a.radius * a.radius {
+            return None;
+        }
+        let dist = dist_sq.sqrt();
+        return Some(Contact {
+            normal: delta / dist,
+            depth: a.radius - dist,
+        });
+    }
+
+    // The center of the circle is inside the rectangle: push out through the nearest edge.
+    let to_min = a.center - b.min;
+    let to_max = b.max - a.center;
+    let (dist, normal) = [
+        (to_min.x, Vec2::X),
+        (to_max.x, -Vec2::X),
+        (to_min.y, Vec2::Y),
+        (to_max.y, -Vec2::Y),
+    ]
+    .into_iter()
+    .min_by(|(d1, _), (d2, _)| d1.total_cmp(d2))
+    .unwrap();
+
+    Some(Contact {
+        normal,
+        depth: dist + a.radius,
+    })
+}
+
+/// Tests if a rectangle and a circle overlap.
+pub fn rect_circle(a: &Rect, b: &Circle) -> Option<Contact> {
+    circle_rect(b, a).map(Contact::flipped)
+}
+
+fn sat(a: &Polygon, b: &Polygon, axes: impl Iterator<Item = Vec2>) -> Option<Contact> {
+    let mut best: Option<Contact> = None;
+    for axis in axes {
+        if axis == Vec2::ZERO {
+            continue;
+        }
+        let (a_min, a_max) = a.project(axis);
+        let (b_min, b_max) = b.project(axis);
+        let depth = a_max.min(b_max) - a_min.max(b_min);
+        if depth <= 0.0 {
+            return None;
+        }
+        if best.is_none_or(|best| depth < best.depth) {
+            best = Some(Contact {
+                normal: axis,
+                depth,
+            });
+        }
+    }
+
+    let mut contact = best?;
+    if (b.centroid() - a.centroid()).dot(contact.normal) < 0.0 {
+        contact.normal = -contact.normal;
+    }
+    Some(contact)
+}
+
+/// Tests if two convex polygons overlap, using the separating axis theorem.
+pub fn polygon_polygon(a: &Polygon, b: &Polygon) -> Option<Contact> {
+    sat(
+        a,
+        b,
+        a.edges()
+            .map(|(_, _, n)| n)
+            .chain(b.edges().map(|(_, _, n)| n)),
+    )
+}
+
+/// Tests if a convex polygon and a circle overlap.
+pub fn polygon_circle(a: &Polygon, b: &Circle) -> Option<Contact> {
+    if a.points.is_empty() {
+        return None;
+    }
+
+    // The circle's only separating axis candidate is towards the closest vertex.
+    let closest = a
+        .points
+        .iter()
+        .copied()
+        .min_by(|p, q| {
+            p.distance_squared(b.center)
+                .total_cmp(&q.distance_squared(b.center))
+        })
+        .unwrap();
+
+    let mut best: Option<Contact> = None;
+    for axis in a
+        .edges()
+        .map(|(_, _, n)| n)
+        .chain(std::iter::once((b.center - closest).normalize_or_zero()))
+    {
+        if axis == Vec2::ZERO {
+            continue;
+        }
+        let (a_min, a_max) = a.project(axis);
+        let c = b.center.dot(axis);
+        let depth = a_max.min(c + b.radius) - a_min.max(c - b.radius);
+        if depth <= 0.0 {
+            return None;
+        }
+        if best.is_none_or(|best| depth < best.depth) {
+            best = Some(Contact {
+                normal: axis,
+                depth,
+            });
+        }
+    }
+
+    let mut contact = best?;
+    if (b.center - a.centroid()).dot(contact.normal) < 0.0 {
+        contact.normal = -contact.normal;
+    }
+    Some(contact)
+}
+
+/// A shape that can be tested for collisions.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Shape {
+    /// An axis-aligned rectangle.
+    Rect(Rect),
+
+    /// A circle.
+    Circle(Circle),
+
+    /// A convex polygon.
+    Polygon(Polygon),
+}
+
+impl Shape {
+    /// Gets the bounding box of the shape.
+    pub fn bounds(&self) -> Rect {
+        match self {
+            Shape::Rect(rect) => *rect,
+            Shape::Circle(circle) => circle.bounds(),
+            Shape::Polygon(polygon) => polygon.bounds(),
+        }
+    }
+
+    /// Checks if the point lies within the shape.
+    pub fn contains(&self, point: Vec2) -> bool {
+        match self {
+            Shape::Rect(rect) => rect.contains(point),
+            Shape::Circle(circle) => circle.contains(point),
+            Shape::Polygon(polygon) => polygon.contains(point),
+        }
+    }
+}
+
+impl From<Rect> for Shape {
+    fn from(rect: Rect) -> Self {
+        Shape::Rect(rect)
+    }
+}
+
+impl From<Circle> for Shape {
+    fn from(circle: Circle) -> Self {
+        Shape::Circle(circle)
+    }
+}
+
+impl From<Polygon> for Shape {
+    fn from(polygon: Polygon) -> Self {
+        Shape::Polygon(polygon)
+    }
+}
+
+/// Tests if two shapes overlap.
+pub fn overlap(a: &Shape, b: &Shape) -> Option<Contact> {
+    match (a, b) {
+        (Shape::Rect(a), Shape::Rect(b)) => rect_rect(a, b),
+        (Shape::Circle(a), Shape::Circle(b)) => circle_circle(a, b),
+        (Shape::Circle(a), Shape::Rect(b)) => circle_rect(a, b),
+        (Shape::Rect(a), Shape::Circle(b)) => rect_circle(a, b),
+        (Shape::Polygon(a), Shape::Polygon(b)) => polygon_polygon(a, b),
+        (Shape::Polygon(a), Shape::Circle(b)) => polygon_circle(a, b),
+        (Shape::Circle(a), Shape::Polygon(b)) => polygon_circle(b, a).map(Contact::flipped),
+        (Shape::Polygon(a), Shape::Rect(b)) => polygon_polygon(a, &Polygon::from_rect(b)),
+        (Shape::Rect(a), Shape::Polygon(b)) => polygon_polygon(&Polygon::from_rect(a), b),
+    }
+}
@@ -0,0 +1,277 @@
+//! Bézier curves and splines.
+//!
+//! All curves are parameterized by `t` from 0.0 (the start of the curve) to 1.0 (the end of the curve). As the speed along a curve is not constant, use [`ArcLength`] to move along a curve at a constant speed.
+
+use super::Vec2;
+
+/// A parametric curve.
+pub trait Curve {
+    /// Gets the position on the curve at `t`.
+    fn position(&self, t: f32) -> Vec2;
+
+    /// Gets the derivative (the tangent, scaled by speed) of the curve at `t`.
+    fn derivative(&self, t: f32) -> Vec2;
+
+    /// Gets the unit tangent of the curve at `t`.
+    fn tangent(&self, t: f32) -> Vec2 {
+        self.derivative(t).normalize_or_zero()
+    }
+
+    /// Samples `n` points along the curve, evenly spaced in `t`, including both endpoints.
+    fn sample(&self, n: usize) -> Vec<Vec2> {
+        match n {
+            0 => vec![],
+            1 => vec![self.position(0.0)],
+            _ => (0..n)
+                .map(|i| self.position(i as f32 / (n - 1) as f32))
+                .collect(),
+        }
+    }
+
+    /// Approximates the length of the curve by summing `segments` straight line segments.
+    fn length(&self, segments: usize) -> f32 {
+        self.sample(segments + 1)
+            .windows(2)
+            .map(|w| w[0].distance(w[1]))
+            .sum()
+    }
+}
+
+/// A quadratic Bézier curve.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct QuadraticBezier {
+    /// The start point.
+    pub p0: Vec2,
+
+    /// The control point.
+    pub p1: Vec2,
+
+    /// The end point.
+    pub p2: Vec2,
+}
+
+impl QuadraticBezier {
+    /// Creates a new quadratic Bézier curve.
+    pub fn new(p0: Vec2, p1: Vec2, p2: Vec2) -> Self {
+        Self { p0, p1, p2 }
+    }
+}
+
+impl Curve for QuadraticBezier {
+    fn position(&self, t: f32) -> Vec2 {
+        let u = 1.0 - t;
+        self.p0 * (u * u) + self.p1 * (2.0 * u * t) + self.p2 * (t * t)
+    }
+
+    fn derivative(&self, t: f32) -> Vec2 {
+        let u = 1.0 - t;
+        (self.p1 - self.p0) * (2.0 * u) + (self.p2 - self.p1) * (2.0 * t)
+    }
+}
+
+/// A cubic Bézier curve.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct CubicBezier {
+    /// The start point.
+    pub p0: Vec2,
+
+    /// The first control point.
+    pub p1: Vec2,
+
+    /// The second control point.
+    pub p2: Vec2,
+
+    /// The end point.
+    pub p3: Vec2,
+}
+
+impl CubicBezier {
+    /// Creates a new cubic Bézier curve.
+    pub fn new(p0: Vec2, p1: Vec2, p2: Vec2, p3: Vec2) -> Self {
+        Self { p0, p1, p2, p3 }
+    }
+}
+
+impl Curve for CubicBezier {
+    fn position(&self, t: f32) -> Vec2 {
+        let u = 1.0 - t;
+        self.p0 * (u * u * u)
+            + self.p1 * (3.0 * u * u * t)
+            + self.p2 * (3.0 * u * t * t)
+            + self.p3 * (t * t * t)
+    }
+
+    fn derivative(&self, t: f32) -> Vec2 {
+        let u = 1.0 - t;
+        (self.p1 - self.p0) * (3.0 * u * u)
+            + (self.p2 - self.p1) * (6.0 * u * t)
+            + (self.p3 - self.p2) * (3.0 * t * t)
+    }
+}
+
+/// A Catmull-Rom spline, which passes smoothly through all of its points.
+#[derive(Clone, PartialEq, Debug)]
+pub struct CatmullRom {
+    /// The points the spline passes through.
+    pub points: Vec<Vec2>,
+
+    /// Whether the spline loops back from the last point to the first point.
+    pub closed: bool,
+}
+
+impl CatmullRom {
+    /// Creates a new open Catmull-Rom spline through the given points.
+    pub fn new(points: Vec<Vec2>) -> Self {
+        Self {
+            points,
+            closed: false,
+        }
+    }
+
+    /// Creates a new closed Catmull-Rom spline through the given points.
+    pub fn new_closed(points: Vec<Vec2>) -> Self {
+        Self {
+            points,
+            closed: true,
+        }
+    }
+
+    /// Gets the number of segments in the spline.
+    pub fn num_segments(&self) -> usize {
+        if self.closed {
+            self.points.len()
+        } else {
+            self.points.len().saturating_sub(1)
+        }
+    }
+
+    fn point(&self, i: isize) -> Vec2 {
+        let n = self.points.len() as isize;
+        if self.closed {
+            self.points[i.rem_euclid(n) as usize]
+        } else {
+            self.points[i.clamp(0, n - 1) as usize]
+        }
+    }
+
+    /// Splits global `t` into a segment index and the local `t` within that segment.
+    fn segment(&self, t: f32) -> (isize, f32) {
+        let n = self.num_segments();
+        let scaled = t.clamp(0.0, 1.0) * n as f32;
+        let i = (scaled.floor() as usize).min(n - 1);
+        (i as isize, scaled - i as f32)
+    }
+}
+
+impl Curve for CatmullRom {
+    fn position(&self, t: f32) -> Vec2 {
+        match self.points.len() {
+            0 => return Vec2::ZERO,
+            1 => return self.points[0],
+            _ => {}
+        }
+
+        let (i, t) = self.segment(t);
+        let (p0, p1, p2, p3) = (
+            self.point(i - 1),
+            self.point(i),
+            self.point(i + 1),
+            self.point(i + 2),
+        );
+        let t2 = t * t;
+        let t3 = t2 * t;
+        0.5 * (p1 * 2.0
+            + (p2 - p0) * t
+            + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * t2
+            + (p1 * 3.0 - p0 - p2 * 3.0 + p3) * t3)
+    }
+
+    fn derivative(&self, t: f32) -> Vec2 {
+        if self.points.len() < 2 {
+            return Vec2::ZERO;
+        }
+
+        let (i, t) = self.segment(t);
+        let (p0, p1, p2, p3) = (
+            self.point(i - 1),
+            self.point(i),
+            self.point(i + 1),
+            self.point(i + 2),
+        );
+        let t2 = t * t;
+        let local = 0.5
+            * ((p2 - p0)
+                + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * (2.0 * t)
+                + (p1 * 3.0 - p0 - p2 * 3.0 + p3) * (3.0 * t2));
+
+        // Chain rule: each segment spans 1 / num_segments of global t.
+        local * self.num_segments() as f32
+    }
+}
+
+/// A lookup table for moving along a curve by distance instead of by `t`.
+pub struct ArcLength {
+    /// Cumulative distance at evenly spaced values of `t`.
+    distances: Vec<f32>,
+}
+
+impl ArcLength {
+    /// Builds the lookup table by approximating the curve with `segments` straight line segments.
+    ///
+    /// More segments are more accurate, but slower to build.
+    pub fn new(curve: &impl Curve, segments: usize) -> Self {
+        let segments = segments.max(1);
+        let mut distances = Vec::with_capacity(segments + 1);
+        let mut total = 0.0;
+        let mut prev = curve.position(0.0);
+        distances.push(0.0);
+        for i in 1..=segments {
+            let p = curve.position(i as f32 / segments as f32);
+            total += prev.distance(p);
+            distances.push(total);
+            prev = p;
+        }
+        Self { distances }
+    }
+
+    /// Gets the total length of the curve.
+    pub fn length(&self) -> f32 {
+        *self.distances.last().unwrap()
+    }
+
+    /// Gets the value of `t` at the given distance along the curve.
+    ///
+    /// The distance is clamped to the length of the curve.
+    pub fn t_at_distance(&self, distance: f32) -> f32 {
+        let segments = self.distances.len() - 1;
+        let distance = distance.clamp(0.0, self.length());
+
+        let i = self
+            .distances
+            .partition_point(|d| *d < distance)
+            .clamp(1, segments);
+        let (d0, d1) = (self.distances[i - 1], self.distances[i]);
+        let frac = if d1 > d0 {
+            (distance - d0) / (d1 - d0)
+        } else {
+            0.0
+        };
+        ((i - 1) as f32 + frac) / segments as f32
+    }
+
+    /// Gets the value of `t` at the given fraction of the total length of the curve, where `u` is between 0.0 and 1.0.
+    pub fn t_at_fraction(&self, u: f32) -> f32 {
+        self.t_at_distance(u * self.length())
+    }
+
+    /// Samples points along the curve, spaced `spacing` apart by distance along the curve, starting at the start of the curve.
+    pub fn sample_evenly(&self, curve: &impl Curve, spacing: f32) -> Vec<Vec2> {
+        if spacing <= 0.0 {
+            return vec![curve.position(0.0)];
+        }
+        let n = (self.length() / spacing).floor() as usize;
+        (0..=n)
+            .map(|i| curve.position(self.t_at_distance(i as f32 * spacing)))
+            .collect()
+    }
+}
@@ -0,0 +1,476 @@
+//! Fixed-point math for deterministic simulations.
+//!
+//! Floating-point results, especially of trigonometric functions, may differ between platforms, compilers and CPUs, which breaks lockstep and [rollback](crate::rollback) games whose peers must simulate exactly the same thing. [`Fixed`] and [`FVec2`] only use integer arithmetic, so they give bit-identical results everywhere. Convert to floats only for drawing:
+//!
+//! ```ignore
+//! // In the simulation:
+//! self.angle += Fixed::from_ratio(1, 16);
+//! self.pos += FVec2::from_angle(self.angle) * self.speed;
+//!
+//! // In draw:
+//! let pos = Vec2::from(self.pos);
+//! canvas.draw(sprite, math::translate(pos.x, pos.y));
+//! ```
+
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use super::Vec2;
+
+/// Number of fractional bits of [`Fixed`].
+const FRAC_BITS: u32 = 16;
+
+/// Table steps per quarter turn.
+const QUARTER_STEPS: usize = 256;
+
+/// Sine over a quarter turn, with one extra entry for the end of the quarter.
+const SIN_TABLE: [i32; QUARTER_STEPS + 1] = sin_table();
+
+/// Computes the sine table at compile time, with a Taylor series in 2.30 fixed point.
+const fn sin_table() -> [i32; QUARTER_STEPS + 1] {
+    const ONE: i64 = 1 << 30;
+    const FRAC_PI_2: i64 = 1_686_629_713; // π/2 in 2.30 fixed point.
+
+    let mut table = [0; QUARTER_STEPS + 1];
+    let mut i = 0;
+    while i <= QUARTER_STEPS {
+        let x = FRAC_PI_2 * i as i64 / QUARTER_STEPS as i64;
+        let x2 = x * x / ONE;
+
+        // x - x³/3! + x⁵/5! - ..., accumulating each term from the previous one.
+        let mut term = x;
+        let mut sum = x;
+        let mut n = 1;
+        while n <= 6 {
+            term = -term * x2 / ONE / ((2 * n) * (2 * n + 1));
+            sum += term;
+            n += 1;
+        }
+        table[i] = ((sum + (1 << 13)) >> 14) as i32;
+        i += 1;
+    }
+    table
+}
+
+/// A signed 16.16 fixed-point number, ranging from about -32768 to 32768 with a precision of 1/65536.
+///
+/// Arithmetic wraps on overflow, like it would in release builds for integers, such that all peers overflow the same way.
+#[derive(
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Default,
+    Debug,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub struct Fixed(
+    /// The raw value, in 1/65536ths.
+    pub i32,
+);
+
+impl Fixed {
+    /// Zero.
+    pub const ZERO: Self = Self(0);
+
+    /// One.
+    pub const ONE: Self = Self(1 << FRAC_BITS);
+
+    /// The smallest representable value.
+    pub const MIN: Self = Self(i32::MIN);
+
+    /// The largest representable value.
+    pub const MAX: Self = Self(i32::MAX);
+
+    /// π.
+    pub const PI: Self = Self(205_887);
+
+    /// π/2.
+    pub const FRAC_PI_2: Self = Self(102_944);
+
+    /// 2π.
+    pub const TAU: Self = Self(411_775);
+
+    /// Creates a fixed-point number from an integer.
+    pub const fn from_int(n: i32) -> Self {
+        Self(n.wrapping_shl(FRAC_BITS))
+    }
+
+    /// Creates a fixed-point number from the fraction `numerator / denominator`, rounding towards zero.
+    pub const fn from_ratio(numerator: i32, denominator: i32) -> Self {
+        Self((((numerator as i64) << FRAC_BITS) / denominator as i64) as i32)
+    }
+
+    /// Converts a float to the nearest fixed-point number.
+    ///
+    /// This is deterministic for the same float, but floats computed at runtime may not be, so only use this for constants and loaded data.
+    pub fn from_f32(f: f32) -> Self {
+        Self((f * Self::ONE.0 as f32).round() as i32)
+    }
+
+    /// Converts to a float, e.g. for drawing.
+    pub fn to_f32(self) -> f32 {
+        self.0 as f32 / Self::ONE.0 as f32
+    }
+
+    /// Gets the integer part, rounding towards negative infinity.
+    pub const fn floor(self) -> i32 {
+        self.0 >> FRAC_BITS
+    }
+
+    /// Gets the integer part, rounding to the nearest integer.
+    pub const fn round(self) -> i32 {
+        (self.0.wrapping_add(1 << (FRAC_BITS - 1))) >> FRAC_BITS
+    }
+
+    /// Gets the fractional part, from 0 to 1.
+    pub const fn fract(self) -> Self {
+        Self(self.0 & ((1 << FRAC_BITS) - 1))
+    }
+
+    /// Gets the absolute value.
+    pub const fn abs(self) -> Self {
+        Self(self.0.wrapping_abs())
+    }
+
+    /// Gets the smaller of two numbers.
+    pub fn min(self, other: Self) -> Self {
+        Ord::min(self, other)
+    }
+
+    /// Gets the larger of two numbers.
+    pub fn max(self, other: Self) -> Self {
+        Ord::max(self, other)
+    }
+
+    /// Restricts the number to a range.
+    pub fn clamp(self, min: Self, max: Self) -> Self {
+        Ord::clamp(self, min, max)
+    }
+
+    /// Gets the square root, rounded down. Negative numbers give zero.
+    pub fn sqrt(self) -> Self {
+        if self.0 <= 0 {
+            return Self::ZERO;
+        }
+        Self(isqrt((self.0 as u64) << FRAC_BITS) as i32)
+    }
+
+    /// Gets the sine of an angle in radians, from a table with linear interpolation.
+    pub fn sin(self) -> Self {
+        const STEPS: i64 = QUARTER_STEPS as i64 * 4;
+
+        // Position along a full turn, in table steps with 16 fractional bits.
+        let pos = (self.0 as i64 * (STEPS << FRAC_BITS) / Self::TAU.0 as i64)
+            .rem_euclid(STEPS << FRAC_BITS);
+        let i = (pos >> FRAC_BITS) as usize;
+        let t = pos & ((1 << FRAC_BITS) - 1);
+
+        let sample = |i: usize| {
+            let i = i % (QUARTER_STEPS * 4);
+            let (quadrant, step) = (i / QUARTER_STEPS, i % QUARTER_STEPS);
+            let sin = match quadrant {
+                0 => SIN_TABLE[step],
+                1 => SIN_TABLE[QUARTER_STEPS - step],
+                2 => -SIN_TABLE[step],
+                _ => -SIN_TABLE[QUARTER_STEPS - step],
+            };
+            sin as i64
+        };
+        let (a, b) = (sample(i), sample(i + 1));
+        Self((a + (((b - a) * t) >> FRAC_BITS)) as i32)
+    }
+
+    /// Gets the cosine of an angle in radians.
+    pub fn cos(self) -> Self {
+        (self + Self::FRAC_PI_2).sin()
+    }
+
+    /// Gets the sine and cosine of an angle in radians.
+    pub fn sin_cos(self) -> (Self, Self) {
+        (self.sin(), self.cos())
+    }
+
+    /// Gets the angle in radians of the point (x, y) from the positive x axis, from -π to π, with an error of at most about 0.002 radians.
+    pub fn atan2(y: Self, x: Self) -> Self {
+        if x.0 == 0 && y.0 == 0 {
+            return Self::ZERO;
+        }
+
+        // Approximate atan on [0, 1] as (π/4)z - z(z - 1)(0.2447 + 0.0663z), working in the first octant.
+        let (ax, ay) = (x.0.unsigned_abs() as i64, y.0.unsigned_abs() as i64);
+        let (small, large) = (ax.min(ay), ax.max(ay));
+        let z = (small << FRAC_BITS) / large;
+        let one = Self::ONE.0 as i64;
+        let mut angle = (((Self::PI.0 as i64 / 4) * z) >> FRAC_BITS)
+            - ((((z * (z - one)) >> FRAC_BITS) * (16_037 + ((4_345 * z) >> FRAC_BITS)))
+                >> FRAC_BITS);
+
+        if ay > ax {
+            angle = Self::FRAC_PI_2.0 as i64 - angle;
+        }
+        if x.0 < 0 {
+            angle = Self::PI.0 as i64 - angle;
+        }
+        if y.0 < 0 {
+            angle = -angle;
+        }
+        Self(angle as i32)
+    }
+}
+
+/// Integer square root, rounded down.
+fn isqrt(n: u64) -> u64 {
+    let mut x = n;
+    let mut y = x.div_ceil(2);
+    while y < x {
+        x = y;
+        y = (x + n / x) / 2;
+    }
+    x
+}
+
+impl From<i32> for Fixed {
+    fn from(n: i32) -> Self {
+        Self::from_int(n)
+    }
+}
+
+impl From<Fixed> for f32 {
+    fn from(f: Fixed) -> Self {
+        f.to_f32()
+    }
+}
+
+impl std::fmt::Display for Fixed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.to_f32(), f)
+    }
+}
+
+impl Add for Fixed {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0.wrapping_add(rhs.0))
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0.wrapping_sub(rhs.0))
+    }
+}
+
+impl Mul for Fixed {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self(((self.0 as i64 * rhs.0 as i64) >> FRAC_BITS) as i32)
+    }
+}
+
+/// Divides, saturating to [`Fixed::MIN`] or [`Fixed::MAX`] on division by zero.
+impl Div for Fixed {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        if rhs.0 == 0 {
+            return if self.0 < 0 { Self::MIN } else { Self::MAX };
+        }
+        Self((((self.0 as i64) << FRAC_BITS) / rhs.0 as i64) as i32)
+    }
+}
+
+impl Neg for Fixed {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(self.0.wrapping_neg())
+    }
+}
+
+impl AddAssign for Fixed {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Fixed {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign for Fixed {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl DivAssign for Fixed {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+/// A 2D vector of [`Fixed`] numbers.
+#[derive(
+    Clone, Copy, PartialEq, Eq, Hash, Default, Debug, serde::Serialize, serde::Deserialize,
+)]
+pub struct FVec2 {
+    /// The x component.
+    pub x: Fixed,
+
+    /// The y component.
+    pub y: Fixed,
+}
+
+impl FVec2 {
+    /// All zeroes.
+    pub const ZERO: Self = Self::new(Fixed::ZERO, Fixed::ZERO);
+
+    /// Creates a vector.
+    pub const fn new(x: Fixed, y: Fixed) -> Self {
+        Self { x, y }
+    }
+
+    /// Creates a vector from integer components.
+    pub const fn from_ints(x: i32, y: i32) -> Self {
+        Self::new(Fixed::from_int(x), Fixed::from_int(y))
+    }
+
+    /// Converts a float vector to the nearest fixed-point vector. See [`Fixed::from_f32`].
+    pub fn from_vec2(v: Vec2) -> Self {
+        Self::new(Fixed::from_f32(v.x), Fixed::from_f32(v.y))
+    }
+
+    /// Converts to a float vector, e.g. for drawing.
+    pub fn to_vec2(self) -> Vec2 {
+        Vec2::new(self.x.to_f32(), self.y.to_f32())
+    }
+
+    /// Creates a unit vector pointing at an angle in radians from the positive x axis.
+    pub fn from_angle(angle: Fixed) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self::new(cos, sin)
+    }
+
+    /// Gets the angle in radians of the vector from the positive x axis.
+    pub fn to_angle(self) -> Fixed {
+        Fixed::atan2(self.y, self.x)
+    }
+
+    /// Computes the dot product.
+    pub fn dot(self, rhs: Self) -> Fixed {
+        self.x * rhs.x + self.y * rhs.y
+    }
+
+    /// Computes the squared length, which is cheaper than [`FVec2::length`].
+    pub fn length_squared(self) -> Fixed {
+        self.dot(self)
+    }
+
+    /// Computes the length.
+    ///
+    /// This is computed with extra precision, so it doesn't overflow for vectors whose squared length would.
+    pub fn length(self) -> Fixed {
+        let (x, y) = (self.x.0 as i64, self.y.0 as i64);
+        Fixed(isqrt((x * x + y * y) as u64) as i32)
+    }
+
+    /// Computes the distance to another point.
+    pub fn distance(self, rhs: Self) -> Fixed {
+        (self - rhs).length()
+    }
+
+    /// Scales the vector to a length of 1, or returns zero if its length is zero.
+    pub fn normalize_or_zero(self) -> Self {
+        let length = self.length();
+        if length == Fixed::ZERO {
+            return Self::ZERO;
+        }
+        self / length
+    }
+
+    /// Rotates the vector by an angle in radians.
+    pub fn rotate(self, angle: Fixed) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self::new(self.x * cos - self.y * sin, self.x * sin + self.y * cos)
+    }
+}
+
+impl From<FVec2> for Vec2 {
+    fn from(v: FVec2) -> Self {
+        v.to_vec2()
+    }
+}
+
+impl Add for FVec2 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl Sub for FVec2 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl Mul<Fixed> for FVec2 {
+    type Output = Self;
+
+    fn mul(self, rhs: Fixed) -> Self {
+        Self::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl Div<Fixed> for FVec2 {
+    type Output = Self;
+
+    fn div(self, rhs: Fixed) -> Self {
+        Self::new(self.x / rhs, self.y / rhs)
+    }
+}
+
+impl Neg for FVec2 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+impl AddAssign for FVec2 {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for FVec2 {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign<Fixed> for FVec2 {
+    fn mul_assign(&mut self, rhs: Fixed) {
+        *self = *self * rhs;
+    }
+}
+
+impl DivAssign<Fixed> for FVec2 {
+    fn div_assign(&mut self, rhs: Fixed) {
+        *self = *self / rhs;
+    }
+}
@@ -0,0 +1,106 @@
+//! Interpolation between ticks.
+//!
+//! Ticks run at a fixed rate, while frames are drawn whenever the display is ready, so drawing the state of the latest tick makes movement stutter. Instead, draw the state between the last two ticks at [`crate::time::Timing::alpha`]. [`Interpolated`] keeps the previous value around for this:
+//!
+//! ```ignore
+//! // In update:
+//! self.pos.set(self.pos.current() + self.velocity);
+//! self.rotation.set(Angle(self.rotation.current().0 + self.spin));
+//!
+//! // In draw:
+//! let alpha = ctxt.time.alpha();
+//! let pos = self.pos.get(alpha);
+//! canvas.draw(sprite, math::translate(pos.x, pos.y) * math::rotate(self.rotation.get(alpha).0));
+//! ```
+
+use super::{lerp_angle, Affine2, Vec2};
+
+/// Values that can be blended between two states.
+pub trait Interpolate: Copy {
+    /// Blends from this value to another by t, from 0 (this value) to 1 (the other value).
+    fn interpolate(&self, other: &Self, t: f32) -> Self;
+}
+
+impl Interpolate for f32 {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Interpolate for Vec2 {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        self.lerp(*other, t)
+    }
+}
+
+/// Transforms are blended by scale, rotation (the shortest way around) and translation, so rotating sprites don't shrink halfway. Shear is not preserved.
+impl Interpolate for Affine2 {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        let (from_scale, from_angle, from_translation) = self.to_scale_angle_translation();
+        let (to_scale, to_angle, to_translation) = other.to_scale_angle_translation();
+        Affine2::from_scale_angle_translation(
+            from_scale.lerp(to_scale, t),
+            lerp_angle(from_angle, to_angle, t),
+            from_translation.lerp(to_translation, t),
+        )
+    }
+}
+
+/// An angle in radians, which is blended the shortest way around instead of linearly, e.g. from 350° to 10° via 0°.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct Angle(pub f32);
+
+impl Interpolate for Angle {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Angle(lerp_angle(self.0, other.0, t))
+    }
+}
+
+/// A value as of the last two ticks.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct Interpolated<T> {
+    previous: T,
+    current: T,
+}
+
+impl<T> Interpolated<T>
+where
+    T: Interpolate,
+{
+    /// Creates a value that hasn't moved yet.
+    pub fn new(value: T) -> Self {
+        Self {
+            previous: value,
+            current: value,
+        }
+    }
+
+    /// Sets the value for this tick, making the current value the previous one.
+    ///
+    /// Call this exactly once per tick, even if the value didn't change, such that the previous value doesn't lag behind.
+    pub fn set(&mut self, value: T) {
+        self.previous = self.current;
+        self.current = value;
+    }
+
+    /// Sets the value without blending from the previous one, e.g. when teleporting or respawning.
+    pub fn reset(&mut self, value: T) {
+        self.previous = value;
+        self.current = value;
+    }
+
+    /// Gets the value as of the latest tick, e.g. for game logic.
+    pub fn current(&self) -> T {
+        self.current
+    }
+
+    /// Gets the value as of the tick before.
+    pub fn previous(&self) -> T {
+        self.previous
+    }
+
+    /// Gets the value blended between the last two ticks, for drawing.
+    pub fn get(&self, alpha: f32) -> T {
+        self.previous.interpolate(&self.current, alpha)
+    }
+}
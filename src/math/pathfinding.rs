@@ -0,0 +1,217 @@
+//! Pathfinding on grids.
+//!
+//! The grid is described by a cost function that, given a cell, returns the cost of entering it, or [`None`] if the cell cannot be entered. For a grid where cells are simply solid or empty (e.g. a tilemap collision layer), use [`solid_cost`].
+
+use super::raycast::Ray;
+use super::{IVec2, Vec2};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+/// Which neighboring cells may be moved to from a cell.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Neighbors {
+    /// Only orthogonally adjacent cells.
+    #[default]
+    Four,
+
+    /// Orthogonally and diagonally adjacent cells.
+    ///
+    /// Diagonal moves are not allowed to cut the corners of cells that cannot be entered.
+    Eight,
+}
+
+/// Options for [`find_path`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PathOptions {
+    /// Which neighboring cells may be moved to from a cell.
+    pub neighbors: Neighbors,
+
+    /// The maximum number of cells to visit before giving up, if any.
+    ///
+    /// This bounds the time spent searching for unreachable goals on large or unbounded grids.
+    pub max_visited: Option<usize>,
+}
+
+/// A path found by [`find_path`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct Path {
+    /// The cells along the path, from the start to the goal, inclusive.
+    pub cells: Vec<IVec2>,
+
+    /// The total cost of the path.
+    pub cost: f32,
+}
+
+impl Path {
+    /// Converts the path into world space positions at the center of each cell.
+    pub fn to_world(&self, cell_size: Vec2) -> Vec<Vec2> {
+        self.cells
+            .iter()
+            .map(|cell| (cell.as_vec2() + 0.5) * cell_size)
+            .collect()
+    }
+
+    /// Removes unnecessary intermediate cells from the path, such that there is a straight line of walkable cells between each consecutive pair of cells.
+    pub fn smoothed(&self, is_walkable: impl FnMut(IVec2) -> bool) -> Self {
+        Self {
+            cells: smooth(&self.cells, is_walkable),
+            cost: self.cost,
+        }
+    }
+}
+
+/// Creates a cost function for a grid where cells are either solid or empty, and every empty cell costs 1 to enter.
+pub fn solid_cost(mut is_solid: impl FnMut(IVec2) -> bool) -> impl FnMut(IVec2) -> Option<f32> {
+    move |cell| (!is_solid(cell)).then_some(1.0)
+}
+
+#[derive(PartialEq)]
+struct Candidate {
+    estimate: f32,
+    cell: IVec2,
+}
+
+impl Eq for Candidate {}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed, such that the binary heap pops the lowest estimate first.
+        other.estimate.total_cmp(&self.estimate)
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+fn heuristic(neighbors: Neighbors, a: IVec2, b: IVec2) -> f32 {
+    let d = (a - b).abs().as_vec2();
+    match neighbors {
+        Neighbors::Four => d.x + d.y,
+        Neighbors::Eight => d.max_element() + (std::f32::consts::SQRT_2 - 1.0) * d.min_element(),
+    }
+}
+
+const ORTHOGONAL: [IVec2; 4] = [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y];
+const DIAGONAL: [IVec2; 4] = [
+    IVec2::new(1, 1),
+    IVec2::new(1, -1),
+    IVec2::new(-1, 1),
+    IVec2::new(-1, -1),
+];
+
+/// Finds the cheapest path from the start cell to the goal cell using A*.
+///
+/// The cost of moving to a cell is its cost from the cost function, multiplied by √2 for diagonal moves. The start cell's cost is never queried. Costs should be at least 1 for the search to find the cheapest path.
+///
+/// Returns [`None`] if no path exists, or if [`PathOptions::max_visited`] cells were visited without reaching the goal.
+pub fn find_path(
+    start: IVec2,
+    goal: IVec2,
+    options: &PathOptions,
+    mut cost: impl FnMut(IVec2) -> Option<f32>,
+) -> Option<Path> {
+    let mut open = BinaryHeap::new();
+    let mut came_from = HashMap::<IVec2, IVec2>::new();
+    let mut best_cost = HashMap::<IVec2, f32>::new();
+    let mut cell_costs = HashMap::<IVec2, Option<f32>>::new();
+    let mut cost_of = |cell: IVec2| *cell_costs.entry(cell).or_insert_with(|| cost(cell));
+
+    best_cost.insert(start, 0.0);
+    open.push(Candidate {
+        estimate: heuristic(options.neighbors, start, goal),
+        cell: start,
+    });
+
+    let mut visited = 0;
+    while let Some(Candidate { cell, estimate }) = open.pop() {
+        let current_cost = best_cost[&cell];
+        if estimate > current_cost + heuristic(options.neighbors, cell, goal) {
+            // Stale entry: a cheaper route to this cell has already been processed.
+            continue;
+        }
+
+        if cell == goal {
+            let mut cells = vec![goal];
+            let mut current = goal;
+            while let Some(prev) = came_from.get(&current) {
+                cells.push(*prev);
+                current = *prev;
+            }
+            cells.reverse();
+            return Some(Path {
+                cells,
+                cost: current_cost,
+            });
+        }
+
+        visited += 1;
+        if options.max_visited.is_some_and(|max| visited > max) {
+            return None;
+        }
+
+        let mut moves = ORTHOGONAL
+            .iter()
+            .map(|offset| (cell + *offset, 1.0))
+            .collect::<Vec<_>>();
+        if options.neighbors == Neighbors::Eight {
+            for offset in DIAGONAL {
+                if cost_of(cell + IVec2::new(offset.x, 0)).is_none()
+                    || cost_of(cell + IVec2::new(0, offset.y)).is_none()
+                {
+                    continue;
+                }
+                moves.push((cell + offset, std::f32::consts::SQRT_2));
+            }
+        }
+
+        for (next, multiplier) in moves {
+            let Some(step_cost) = cost_of(next) else {
+                continue;
+            };
+            let next_cost = current_cost + step_cost * multiplier;
+            if best_cost.get(&next).is_some_and(|c| *c <= next_cost) {
+                continue;
+            }
+            best_cost.insert(next, next_cost);
+            came_from.insert(next, cell);
+            open.push(Candidate {
+                estimate: next_cost + heuristic(options.neighbors, next, goal),
+                cell: next,
+            });
+        }
+    }
+
+    None
+}
+
+/// Checks if there is a straight line between the centers of two cells that only passes through walkable cells.
+pub fn line_of_sight(from: IVec2, to: IVec2, mut is_walkable: impl FnMut(IVec2) -> bool) -> bool {
+    let start = from.as_vec2() + 0.5;
+    let end = to.as_vec2() + 0.5;
+    Ray::between(start, end)
+        .traverse_grid(Vec2::ONE, start.distance(end))
+        .all(|step| is_walkable(step.cell))
+}
+
+/// Removes unnecessary intermediate cells from a path, such that there is a straight line of walkable cells between each consecutive pair of cells.
+pub fn smooth(cells: &[IVec2], mut is_walkable: impl FnMut(IVec2) -> bool) -> Vec<IVec2> {
+    let Some(first) = cells.first() else {
+        return vec![];
+    };
+
+    let mut smoothed = vec![*first];
+    let mut anchor = 0;
+    for i in 2..cells.len() {
+        if !line_of_sight(cells[anchor], cells[i], &mut is_walkable) {
+            anchor = i - 1;
+            smoothed.push(cells[anchor]);
+        }
+    }
+    if cells.len() > 1 {
+        smoothed.push(*cells.last().unwrap());
+    }
+    smoothed
+}
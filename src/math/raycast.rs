@@ -0,0 +1,229 @@
+//! Raycasting against shapes and grids.
+//!
+//! Rays may be cast against the shapes in [`super::collision`], or against a grid of cells (e.g. a tilemap) where each cell is either solid or empty.
+
+use super::collision::{Segment, Shape};
+use super::{IVec2, Vec2};
+
+/// A ray with an origin and a direction.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Ray {
+    /// The origin of the ray.
+    pub origin: Vec2,
+
+    /// The unit direction of the ray.
+    pub direction: Vec2,
+}
+
+/// Information about where a ray hit.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct RayHit {
+    /// The point where the ray hit.
+    pub point: Vec2,
+
+    /// The unit surface normal at the hit point.
+    pub normal: Vec2,
+
+    /// The distance from the origin of the ray to the hit point.
+    pub distance: f32,
+}
+
+/// Information about where a ray hit a grid.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct GridHit {
+    /// The cell that was hit.
+    pub cell: IVec2,
+
+    /// Where the cell was hit.
+    pub hit: RayHit,
+}
+
+impl Ray {
+    /// Creates a new ray. The direction will be normalized.
+    pub fn new(origin: Vec2, direction: Vec2) -> Self {
+        Self {
+            origin,
+            direction: direction.normalize_or_zero(),
+        }
+    }
+
+    /// Creates a ray pointing from one point towards another.
+    pub fn between(from: Vec2, to: Vec2) -> Self {
+        Self::new(from, to - from)
+    }
+
+    /// Gets the point at the given distance along the ray.
+    pub fn at(&self, distance: f32) -> Vec2 {
+        self.origin + self.direction * distance
+    }
+
+    /// Converts the ray to a segment of the given length.
+    pub fn to_segment(&self, max_distance: f32) -> Segment {
+        Segment::new(self.origin, self.at(max_distance))
+    }
+
+    /// Casts the ray against a shape, up to the given distance.
+    pub fn cast(&self, shape: &Shape, max_distance: f32) -> Option<RayHit> {
+        let hit = self.to_segment(max_distance).cast(shape)?;
+        Some(RayHit {
+            point: hit.point,
+            normal: hit.normal,
+            distance: hit.fraction * max_distance,
+        })
+    }
+
+    /// Casts the ray against a segment, up to the given distance.
+    pub fn cast_segment(&self, segment: &Segment, max_distance: f32) -> Option<RayHit> {
+        let hit = self.to_segment(max_distance).cast_segment(segment)?;
+        Some(RayHit {
+            point: hit.point,
+            normal: hit.normal,
+            distance: hit.fraction * max_distance,
+        })
+    }
+
+    /// Casts the ray against many shapes and returns the closest hit, along with the key of the shape that was hit.
+    pub fn cast_closest<'a, K>(
+        &self,
+        shapes: impl IntoIterator<Item = (K, &'a Shape)>,
+        max_distance: f32,
+    ) -> Option<(K, RayHit)> {
+        let mut closest: Option<(K, RayHit)> = None;
+        for (key, shape) in shapes {
+            let max_distance = closest
+                .as_ref()
+                .map_or(max_distance, |(_, hit)| hit.distance);
+            if let Some(hit) = self.cast(shape, max_distance) {
+                closest = Some((key, hit));
+            }
+        }
+        closest
+    }
+
+    /// Iterates over every grid cell the ray passes through in order, up to the given distance.
+    ///
+    /// Cells are `cell_size` large, with cell (0, 0) spanning from the origin to `cell_size`.
+    pub fn traverse_grid(&self, cell_size: Vec2, max_distance: f32) -> GridTraversal {
+        GridTraversal::new(self, cell_size, max_distance)
+    }
+
+    /// Casts the ray against a grid, returning the first cell for which `is_solid` returns true.
+    ///
+    /// Cells are `cell_size` large, with cell (0, 0) spanning from the origin to `cell_size`. If the ray starts inside a solid cell, the hit will be at distance 0.
+    pub fn cast_grid(
+        &self,
+        cell_size: Vec2,
+        max_distance: f32,
+        mut is_solid: impl FnMut(IVec2) -> bool,
+    ) -> Option<GridHit> {
+        self.traverse_grid(cell_size, max_distance)
+            .find(|step| is_solid(step.cell))
+            .map(|step| GridHit {
+                cell: step.cell,
+                hit: RayHit {
+                    point: self.at(step.distance),
+                    normal: step.normal,
+                    distance: step.distance,
+                },
+            })
+    }
+}
+
+/// A cell visited during grid traversal.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct GridStep {
+    /// The cell.
+    pub cell: IVec2,
+
+    /// The distance along the ray at which the ray entered the cell.
+    pub distance: f32,
+
+    /// The normal of the cell edge the ray entered through.
+    ///
+    /// For the starting cell, this is the opposite of the ray's direction.
+    pub normal: Vec2,
+}
+
+/// Iterator over the grid cells a ray passes through, using a digital differential analyzer.
+///
+/// Created by [`Ray::traverse_grid`].
+pub struct GridTraversal {
+    cell: IVec2,
+    step: IVec2,
+    t_max: Vec2,
+    t_delta: Vec2,
+    distance: f32,
+    normal: Vec2,
+    max_distance: f32,
+    done: bool,
+}
+
+impl GridTraversal {
+    fn new(ray: &Ray, cell_size: Vec2, max_distance: f32) -> Self {
+        let origin = ray.origin / cell_size;
+        let cell = origin.floor().as_ivec2();
+        let step = IVec2::new(
+            ray.direction.x.signum() as i32 * (ray.direction.x != 0.0) as i32,
+            ray.direction.y.signum() as i32 * (ray.direction.y != 0.0) as i32,
+        );
+
+        let axis = |i: usize| {
+            let d = ray.direction[i];
+            if d == 0.0 {
+                return (f32::INFINITY, f32::INFINITY);
+            }
+            let next_boundary = if d > 0.0 {
+                (cell[i] + 1) as f32
+            } else {
+                cell[i] as f32
+            };
+            let t_delta = cell_size[i] / d.abs();
+            let t_max = (next_boundary - origin[i]) * cell_size[i] / d;
+            (t_max, t_delta)
+        };
+        let (t_max_x, t_delta_x) = axis(0);
+        let (t_max_y, t_delta_y) = axis(1);
+
+        Self {
+            cell,
+            step,
+            t_max: Vec2::new(t_max_x, t_max_y),
+            t_delta: Vec2::new(t_delta_x, t_delta_y),
+            distance: 0.0,
+            normal: -ray.direction,
+            max_distance,
+            done: false,
+        }
+    }
+}
+
+impl Iterator for GridTraversal {
+    type Item = GridStep;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done || self.distance > self.max_distance {
+            return None;
+        }
+
+        let current = GridStep {
+            cell: self.cell,
+            distance: self.distance,
+            normal: self.normal,
+        };
+
+        if self.step == IVec2::ZERO {
+            // A ray with no direction only visits its starting cell.
+            self.done = true;
+            return Some(current);
+        }
+
+        let axis = if self.t_max.x < self.t_max.y { 0 } else { 1 };
+        self.distance = self.t_max[axis];
+        self.t_max[axis] += self.t_delta[axis];
+        self.cell[axis] += self.step[axis];
+        self.normal = Vec2::ZERO;
+        self.normal[axis] = -self.step[axis] as f32;
+
+        Some(current)
+    }
+}
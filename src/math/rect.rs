@@ -0,0 +1,208 @@
+use glam::{IVec2, UVec2, Vec2};
+
+macro_rules! impl_rect {
+    ($name:ident, $vec:ident, $scalar:ty, $doc:literal) => {
+        #[doc = $doc]
+        ///
+        /// The rectangle spans from `min` (inclusive) to `max` (exclusive).
+        #[derive(Clone, Copy, PartialEq, Debug, Default)]
+        pub struct $name {
+            /// The minimum corner of the rectangle.
+            pub min: $vec,
+
+            /// The maximum corner of the rectangle.
+            pub max: $vec,
+        }
+
+        impl $name {
+            /// Creates a rectangle from its minimum and maximum corners.
+            pub const fn from_min_max(min: $vec, max: $vec) -> Self {
+                Self { min, max }
+            }
+
+            /// Creates a rectangle from its position (the minimum corner) and its size.
+            pub fn from_pos_size(pos: $vec, size: $vec) -> Self {
+                Self {
+                    min: pos,
+                    max: pos + size,
+                }
+            }
+
+            /// Converts the rectangle into its minimum and maximum corners.
+            pub fn to_min_max(self) -> ($vec, $vec) {
+                (self.min, self.max)
+            }
+
+            /// Converts the rectangle into its position (the minimum corner) and its size.
+            pub fn to_pos_size(self) -> ($vec, $vec) {
+                (self.min, self.size())
+            }
+
+            /// Gets the position (the minimum corner) of the rectangle.
+            pub fn pos(&self) -> $vec {
+                self.min
+            }
+
+            /// Gets the size of the rectangle.
+            pub fn size(&self) -> $vec {
+                self.max - self.min
+            }
+
+            /// Gets the width of the rectangle.
+            pub fn width(&self) -> $scalar {
+                self.max.x - self.min.x
+            }
+
+            /// Gets the height of the rectangle.
+            pub fn height(&self) -> $scalar {
+                self.max.y - self.min.y
+            }
+
+            /// Checks if the rectangle has no area.
+            pub fn is_empty(&self) -> bool {
+                self.max.x <= self.min.x || self.max.y <= self.min.y
+            }
+
+            /// Checks if the point lies within the rectangle.
+            pub fn contains(&self, point: $vec) -> bool {
+                point.x >= self.min.x
+                    && point.y >= self.min.y
+                    && point.x < self.max.x
+                    && point.y < self.max.y
+            }
+
+            /// Checks if the other rectangle lies entirely within this rectangle.
+            pub fn contains_rect(&self, other: &Self) -> bool {
+                other.min.x >= self.min.x
+                    && other.min.y >= self.min.y
+                    && other.max.x <= self.max.x
+                    && other.max.y <= self.max.y
+            }
+
+            /// Checks if the two rectangles overlap.
+            ///
+            /// Rectangles that only share an edge do not overlap.
+            pub fn intersects(&self, other: &Self) -> bool {
+                self.min.x < other.max.x
+                    && other.min.x < self.max.x
+                    && self.min.y < other.max.y
+                    && other.min.y < self.max.y
+            }
+
+            /// Gets the overlapping region of the two rectangles, or [`None`] if they do not overlap.
+            pub fn intersection(&self, other: &Self) -> Option<Self> {
+                if !self.intersects(other) {
+                    return None;
+                }
+                Some(Self {
+                    min: self.min.max(other.min),
+                    max: self.max.min(other.max),
+                })
+            }
+
+            /// Gets the smallest rectangle containing both rectangles.
+            pub fn union(&self, other: &Self) -> Self {
+                Self {
+                    min: self.min.min(other.min),
+                    max: self.max.max(other.max),
+                }
+            }
+
+            /// Moves the rectangle by the given offset.
+            pub fn translate(&self, offset: $vec) -> Self {
+                Self {
+                    min: self.min + offset,
+                    max: self.max + offset,
+                }
+            }
+
+            /// Clamps the point to lie within the bounds of the rectangle, including the maximum corner.
+            pub fn clamp(&self, point: $vec) -> $vec {
+                point.max(self.min).min(self.max)
+            }
+        }
+    };
+}
+
+impl_rect!(Rect, Vec2, f32, "A rectangle with [`f32`] coordinates.");
+impl_rect!(IRect, IVec2, i32, "A rectangle with [`i32`] coordinates.");
+impl_rect!(URect, UVec2, u32, "A rectangle with [`u32`] coordinates.");
+
+impl Rect {
+    /// Creates a rectangle from its center and its size.
+    pub fn from_center_size(center: Vec2, size: Vec2) -> Self {
+        Self {
+            min: center - size / 2.0,
+            max: center + size / 2.0,
+        }
+    }
+
+    /// Gets the center of the rectangle.
+    pub fn center(&self) -> Vec2 {
+        (self.min + self.max) / 2.0
+    }
+
+    /// Grows the rectangle by the given amount on every side.
+    ///
+    /// A negative amount will shrink the rectangle.
+    pub fn inflate(&self, amount: f32) -> Self {
+        Self {
+            min: self.min - amount,
+            max: self.max + amount,
+        }
+    }
+
+    /// Casts the rectangle to [`IRect`].
+    pub fn as_irect(&self) -> IRect {
+        IRect::from_min_max(self.min.as_ivec2(), self.max.as_ivec2())
+    }
+
+    /// Casts the rectangle to [`URect`].
+    pub fn as_urect(&self) -> URect {
+        URect::from_min_max(self.min.as_uvec2(), self.max.as_uvec2())
+    }
+}
+
+impl IRect {
+    /// Grows the rectangle by the given amount on every side.
+    ///
+    /// A negative amount will shrink the rectangle.
+    pub fn inflate(&self, amount: i32) -> Self {
+        Self {
+            min: self.min - amount,
+            max: self.max + amount,
+        }
+    }
+
+    /// Casts the rectangle to [`Rect`].
+    pub fn as_rect(&self) -> Rect {
+        Rect::from_min_max(self.min.as_vec2(), self.max.as_vec2())
+    }
+
+    /// Casts the rectangle to [`URect`].
+    pub fn as_urect(&self) -> URect {
+        URect::from_min_max(self.min.as_uvec2(), self.max.as_uvec2())
+    }
+}
+
+impl URect {
+    /// Grows the rectangle by the given amount on every side.
+    ///
+    /// The minimum corner will not go below zero.
+    pub fn inflate(&self, amount: u32) -> Self {
+        Self {
+            min: self.min.saturating_sub(UVec2::splat(amount)),
+            max: self.max + amount,
+        }
+    }
+
+    /// Casts the rectangle to [`Rect`].
+    pub fn as_rect(&self) -> Rect {
+        Rect::from_min_max(self.min.as_vec2(), self.max.as_vec2())
+    }
+
+    /// Casts the rectangle to [`IRect`].
+    pub fn as_irect(&self) -> IRect {
+        IRect::from_min_max(self.min.as_ivec2(), self.max.as_ivec2())
+    }
+}
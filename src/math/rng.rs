@@ -0,0 +1,234 @@
+//! Deterministic random number generation.
+//!
+//! Unlike `rand::thread_rng`, [`Rng`] always produces the same sequence of numbers for the same seed on every platform, and its state may be serialized (e.g. for save states or replays).
+
+use super::{Rect, Vec2};
+use std::ops::Range;
+
+const PCG_MULTIPLIER: u64 = 6364136223846793005;
+const PCG_INCREMENT: u64 = 1442695040888963407;
+
+/// A seedable pseudorandom number generator.
+///
+/// This implements [PCG32](https://www.pcg-random.org/), which is fast and small but not cryptographically secure.
+#[derive(Clone, PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Creates a new generator from a seed.
+    pub fn new(seed: u64) -> Self {
+        let mut rng = Self { state: 0 };
+        rng.step();
+        rng.state = rng.state.wrapping_add(seed);
+        rng.step();
+        rng
+    }
+
+    /// Creates a new generator seeded from the current system time.
+    ///
+    /// This is useful when determinism is not required, e.g. for a new game. The seed may be retrieved by [`Rng::seed_from_time`] beforehand if it needs to be recorded.
+    pub fn from_time() -> Self {
+        Self::new(Self::seed_from_time())
+    }
+
+    /// Gets a seed derived from the current system time.
+    pub fn seed_from_time() -> u64 {
+        crate::time::SystemTime::now()
+            .duration_since(crate::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0)
+    }
+
+    fn step(&mut self) {
+        self.state = self
+            .state
+            .wrapping_mul(PCG_MULTIPLIER)
+            .wrapping_add(PCG_INCREMENT);
+    }
+
+    /// Generates a random [`u32`].
+    pub fn next_u32(&mut self) -> u32 {
+        let old_state = self.state;
+        self.step();
+        let xorshifted = (((old_state >> 18) ^ old_state) >> 27) as u32;
+        let rot = (old_state >> 59) as u32;
+        xorshifted.rotate_right(rot)
+    }
+
+    /// Generates a random [`u64`].
+    pub fn next_u64(&mut self) -> u64 {
+        ((self.next_u32() as u64) << 32) | self.next_u32() as u64
+    }
+
+    /// Generates a random [`f32`] in the range [0, 1).
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 * (1.0 / (1u32 << 24) as f32)
+    }
+
+    /// Generates a random [`f64`] in the range [0, 1).
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+
+    /// Generates a random integer in the range [0, n) without bias.
+    fn below(&mut self, n: u64) -> u64 {
+        // Lemire's nearly divisionless method.
+        let mut m = self.next_u64() as u128 * n as u128;
+        if (m as u64) < n {
+            let threshold = n.wrapping_neg() % n;
+            while (m as u64) < threshold {
+                m = self.next_u64() as u128 * n as u128;
+            }
+        }
+        (m >> 64) as u64
+    }
+
+    /// Generates a random value within the given range.
+    ///
+    /// Panics if the range is empty.
+    pub fn range<T>(&mut self, range: Range<T>) -> T
+    where
+        T: SampleRange,
+    {
+        assert!(range.start < range.end, "cannot sample empty range");
+        T::sample(self, range)
+    }
+
+    /// Returns true with the given probability, where `p` is between 0.0 and 1.0.
+    pub fn chance(&mut self, p: f32) -> bool {
+        self.next_f32() < p
+    }
+
+    /// Generates a random unit vector.
+    pub fn unit_vector(&mut self) -> Vec2 {
+        // Trigonometric functions may differ between platforms, but arithmetic and square roots are exactly rounded everywhere, so sample points in the unit circle and normalize them instead.
+        loop {
+            let x = self.next_f32() * 2.0 - 1.0;
+            let y = self.next_f32() * 2.0 - 1.0;
+            let length_squared = x * x + y * y;
+            if length_squared > 0.0 && length_squared <= 1.0 {
+                let length = length_squared.sqrt();
+                return Vec2::new(x / length, y / length);
+            }
+        }
+    }
+
+    /// Generates a random point on the edge of a circle of the given radius, centered at the origin.
+    pub fn on_circle(&mut self, radius: f32) -> Vec2 {
+        self.unit_vector() * radius
+    }
+
+    /// Generates a random point uniformly distributed inside a circle of the given radius, centered at the origin.
+    pub fn in_circle(&mut self, radius: f32) -> Vec2 {
+        self.unit_vector() * radius * self.next_f32().sqrt()
+    }
+
+    /// Generates a random point uniformly distributed inside a rectangle.
+    pub fn in_rect(&mut self, rect: &Rect) -> Vec2 {
+        rect.min + Vec2::new(self.next_f32(), self.next_f32()) * rect.size()
+    }
+
+    /// Chooses a random element from a slice, or [`None`] if the slice is empty.
+    pub fn choose<'a, T>(&mut self, slice: &'a [T]) -> Option<&'a T> {
+        if slice.is_empty() {
+            return None;
+        }
+        Some(&slice[self.below(slice.len() as u64) as usize])
+    }
+
+    /// Chooses a random element from an iterator, or [`None`] if the iterator is empty.
+    ///
+    /// This consumes the entire iterator.
+    pub fn choose_iter<I>(&mut self, iter: I) -> Option<I::Item>
+    where
+        I: IntoIterator,
+    {
+        // Reservoir sampling, such that the iterator does not need to be collected first.
+        let mut chosen = None;
+        for (i, item) in iter.into_iter().enumerate() {
+            if self.below(i as u64 + 1) == 0 {
+                chosen = Some(item);
+            }
+        }
+        chosen
+    }
+
+    /// Chooses a random index, where each index is chosen with probability proportional to its weight.
+    ///
+    /// Returns [`None`] if there are no weights or all the weights are zero. Negative weights are treated as zero.
+    pub fn weighted_index(&mut self, weights: &[f32]) -> Option<usize> {
+        let total = weights.iter().map(|w| w.max(0.0)).sum::<f32>();
+        if total <= 0.0 {
+            return None;
+        }
+
+        let mut target = self.next_f32() * total;
+        let mut last = None;
+        for (i, w) in weights.iter().enumerate() {
+            let w = w.max(0.0);
+            if w == 0.0 {
+                continue;
+            }
+            if target < w {
+                return Some(i);
+            }
+            target -= w;
+            last = Some(i);
+        }
+
+        // Account for floating point error.
+        last
+    }
+
+    /// Chooses a random element from a slice, where each element is chosen with probability proportional to its weight.
+    pub fn choose_weighted<'a, T>(
+        &mut self,
+        slice: &'a [T],
+        weight: impl Fn(&T) -> f32,
+    ) -> Option<&'a T> {
+        let weights = slice.iter().map(weight).collect::<Vec<_>>();
+        Some(&slice[self.weighted_index(&weights)?])
+    }
+
+    /// Shuffles a slice in place.
+    pub fn shuffle<T>(&mut self, slice: &mut [T]) {
+        for i in (1..slice.len()).rev() {
+            slice.swap(i, self.below(i as u64 + 1) as usize);
+        }
+    }
+}
+
+/// A type that may be sampled uniformly from a range by [`Rng::range`].
+pub trait SampleRange: PartialOrd + Sized {
+    /// Samples a value from the range.
+    fn sample(rng: &mut Rng, range: Range<Self>) -> Self;
+}
+
+macro_rules! impl_sample_range_int {
+    ($($ty:ty),*) => {
+        $(
+            impl SampleRange for $ty {
+                fn sample(rng: &mut Rng, range: Range<Self>) -> Self {
+                    let span = range.end.abs_diff(range.start) as u64;
+                    range.start.wrapping_add(rng.below(span) as $ty)
+                }
+            }
+        )*
+    };
+}
+
+impl_sample_range_int!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl SampleRange for f32 {
+    fn sample(rng: &mut Rng, range: Range<Self>) -> Self {
+        range.start + rng.next_f32() * (range.end - range.start)
+    }
+}
+
+impl SampleRange for f64 {
+    fn sample(rng: &mut Rng, range: Range<Self>) -> Self {
+        range.start + rng.next_f64() * (range.end - range.start)
+    }
+}
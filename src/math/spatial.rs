@@ -0,0 +1,293 @@
+//! Spatial partitioning for broad-phase collision detection.
+//!
+//! Testing every object against every other object scales quadratically. A [`SpatialHash`] buckets objects by the grid cells their bounds overlap, such that only objects in nearby cells need to be tested against each other.
+
+use super::raycast::{Ray, RayHit};
+use super::{IVec2, Rect, Vec2};
+use std::collections::{HashMap, HashSet};
+
+/// A handle to an object in a [`SpatialHash`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct Key {
+    index: u32,
+    generation: u32,
+}
+
+struct Slot<T> {
+    generation: u32,
+    entry: Option<Entry<T>>,
+}
+
+struct Entry<T> {
+    value: T,
+    bounds: Rect,
+}
+
+/// A spatial hash, which buckets objects into uniformly sized grid cells.
+///
+/// The cell size should be around the size of a typical object: too small and large objects will occupy many cells, too large and many objects will share a cell.
+pub struct SpatialHash<T> {
+    cell_size: f32,
+    cells: HashMap<IVec2, Vec<Key>>,
+    slots: Vec<Slot<T>>,
+    free: Vec<u32>,
+    len: usize,
+}
+
+impl<T> SpatialHash<T> {
+    /// Creates an empty spatial hash with the given cell size.
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+            slots: vec![],
+            free: vec![],
+            len: 0,
+        }
+    }
+
+    /// Gets the cell size.
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// Gets the number of objects.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Checks if there are no objects.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn cell_range(&self, bounds: &Rect) -> (IVec2, IVec2) {
+        (
+            (bounds.min / self.cell_size).floor().as_ivec2(),
+            (bounds.max / self.cell_size).floor().as_ivec2(),
+        )
+    }
+
+    fn cells_of(&self, bounds: &Rect) -> impl Iterator<Item = IVec2> {
+        let (min, max) = self.cell_range(bounds);
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| IVec2::new(x, y)))
+    }
+
+    fn link(&mut self, key: Key, bounds: &Rect) {
+        for cell in self.cells_of(bounds).collect::<Vec<_>>() {
+            self.cells.entry(cell).or_default().push(key);
+        }
+    }
+
+    fn unlink(&mut self, key: Key, bounds: &Rect) {
+        for cell in self.cells_of(bounds).collect::<Vec<_>>() {
+            let std::collections::hash_map::Entry::Occupied(mut entry) = self.cells.entry(cell)
+            else {
+                continue;
+            };
+            entry.get_mut().retain(|k| *k != key);
+            if entry.get().is_empty() {
+                entry.remove();
+            }
+        }
+    }
+
+    fn entry(&self, key: Key) -> Option<&Entry<T>> {
+        let slot = self.slots.get(key.index as usize)?;
+        if slot.generation != key.generation {
+            return None;
+        }
+        slot.entry.as_ref()
+    }
+
+    /// Inserts an object with the given bounds.
+    pub fn insert(&mut self, bounds: Rect, value: T) -> Key {
+        let key = if let Some(index) = self.free.pop() {
+            let slot = &mut self.slots[index as usize];
+            slot.entry = Some(Entry { value, bounds });
+            Key {
+                index,
+                generation: slot.generation,
+            }
+        } else {
+            self.slots.push(Slot {
+                generation: 0,
+                entry: Some(Entry { value, bounds }),
+            });
+            Key {
+                index: (self.slots.len() - 1) as u32,
+                generation: 0,
+            }
+        };
+        self.link(key, &bounds);
+        self.len += 1;
+        key
+    }
+
+    /// Updates the bounds of an object.
+    ///
+    /// Returns false if the object no longer exists.
+    pub fn update(&mut self, key: Key, bounds: Rect) -> bool {
+        let Some(old_bounds) = self.entry(key).map(|entry| entry.bounds) else {
+            return false;
+        };
+
+        if self.cell_range(&old_bounds) != self.cell_range(&bounds) {
+            self.unlink(key, &old_bounds);
+            self.link(key, &bounds);
+        }
+        self.slots[key.index as usize]
+            .entry
+            .as_mut()
+            .unwrap()
+            .bounds = bounds;
+        true
+    }
+
+    /// Removes an object, returning it if it existed.
+    pub fn remove(&mut self, key: Key) -> Option<T> {
+        let bounds = self.entry(key)?.bounds;
+        self.unlink(key, &bounds);
+
+        let slot = &mut self.slots[key.index as usize];
+        let entry = slot.entry.take()?;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(key.index);
+        self.len -= 1;
+        Some(entry.value)
+    }
+
+    /// Removes all objects.
+    ///
+    /// Slots are kept, such that keys of removed objects don't refer to objects inserted later.
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        for (index, slot) in self.slots.iter_mut().enumerate() {
+            if slot.entry.take().is_some() {
+                slot.generation = slot.generation.wrapping_add(1);
+                self.free.push(index as u32);
+            }
+        }
+        self.len = 0;
+    }
+
+    /// Gets an object.
+    pub fn get(&self, key: Key) -> Option<&T> {
+        self.entry(key).map(|entry| &entry.value)
+    }
+
+    /// Gets an object mutably.
+    pub fn get_mut(&mut self, key: Key) -> Option<&mut T> {
+        let slot = self.slots.get_mut(key.index as usize)?;
+        if slot.generation != key.generation {
+            return None;
+        }
+        slot.entry.as_mut().map(|entry| &mut entry.value)
+    }
+
+    /// Gets the bounds of an object.
+    pub fn bounds(&self, key: Key) -> Option<Rect> {
+        self.entry(key).map(|entry| entry.bounds)
+    }
+
+    /// Iterates over all objects.
+    pub fn iter(&self) -> impl Iterator<Item = (Key, &T)> + '_ {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            slot.entry.as_ref().map(|entry| {
+                (
+                    Key {
+                        index: index as u32,
+                        generation: slot.generation,
+                    },
+                    &entry.value,
+                )
+            })
+        })
+    }
+
+    fn candidates(&self, bounds: &Rect) -> Vec<Key> {
+        let mut keys = self
+            .cells_of(bounds)
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+            .collect::<Vec<_>>();
+        keys.sort_unstable();
+        keys.dedup();
+        keys
+    }
+
+    /// Iterates over all objects whose bounds overlap the given region.
+    pub fn query_rect(&self, region: &Rect) -> impl Iterator<Item = (Key, &T)> + '_ {
+        let region = *region;
+        self.candidates(&region).into_iter().filter_map(move |key| {
+            let entry = self.entry(key)?;
+            entry
+                .bounds
+                .intersects(&region)
+                .then_some((key, &entry.value))
+        })
+    }
+
+    /// Iterates over all objects whose bounds contain the given point.
+    pub fn query_point(&self, point: Vec2) -> impl Iterator<Item = (Key, &T)> + '_ {
+        let cell = (point / self.cell_size).floor().as_ivec2();
+        self.cells
+            .get(&cell)
+            .into_iter()
+            .flatten()
+            .filter_map(move |key| {
+                let entry = self.entry(*key)?;
+                entry.bounds.contains(point).then_some((*key, &entry.value))
+            })
+    }
+
+    /// Gets all objects whose bounds are hit by the ray, sorted by distance along the ray.
+    ///
+    /// Only the bounds of objects are tested: use [`super::collision`] for precise tests on the returned objects.
+    pub fn query_ray(&self, ray: &Ray, max_distance: f32) -> Vec<(Key, &T, RayHit)> {
+        let mut seen = HashSet::new();
+        let mut hits = vec![];
+        for step in ray.traverse_grid(Vec2::splat(self.cell_size), max_distance) {
+            let Some(keys) = self.cells.get(&step.cell) else {
+                continue;
+            };
+            for key in keys {
+                if !seen.insert(*key) {
+                    continue;
+                }
+                let Some(entry) = self.entry(*key) else {
+                    continue;
+                };
+                if let Some(hit) = ray.cast(&entry.bounds.into(), max_distance) {
+                    hits.push((*key, &entry.value, hit));
+                }
+            }
+        }
+        hits.sort_by(|(_, _, a), (_, _, b)| a.distance.total_cmp(&b.distance));
+        hits
+    }
+
+    /// Gets all pairs of objects whose bounds overlap each other.
+    ///
+    /// Each pair is only reported once.
+    pub fn overlapping_pairs(&self) -> Vec<(Key, Key)> {
+        let mut pairs = HashSet::new();
+        for keys in self.cells.values() {
+            for (i, a) in keys.iter().enumerate() {
+                let Some(a_entry) = self.entry(*a) else {
+                    continue;
+                };
+                for b in keys[i + 1..].iter() {
+                    let Some(b_entry) = self.entry(*b) else {
+                        continue;
+                    };
+                    if a_entry.bounds.intersects(&b_entry.bounds) {
+                        pairs.insert(if a < b { (*a, *b) } else { (*b, *a) });
+                    }
+                }
+            }
+        }
+        pairs.into_iter().collect()
+    }
+}
@@ -0,0 +1,126 @@
+//! Unreliable datagram networking, for real-time multiplayer.
+//!
+//! A [`Socket`] sends and receives small packets to and from peers. Packets may be dropped, duplicated or arrive out of order, so games should send state frequently rather than relying on every packet arriving.
+//!
+//! - On native platforms, this is a UDP socket.
+//! - On WASM, this is an unordered, unreliable WebRTC data channel, connected to peers via a [matchbox](https://github.com/johanhelsing/matchbox) signaling server.
+//!
+//! With the `matchbox` feature, native platforms also use WebRTC through the signaling server instead of UDP, so native and browser clients can find each other and play together without knowing each other's addresses:
+//!
+//! ```ignore
+//! // Everyone in the room becomes a peer.
+//! let socket = Socket::open("wss://example.com/my-game")?;
+//!
+//! // Or, matchmake: the server groups the next two players to join into a room of their own.
+//! let socket = Socket::open("wss://example.com/my-game?next=2")?;
+//! ```
+//!
+//! On native platforms, the `matchbox` feature requires the `tokio` feature instead of `smol` (so default features must be disabled), as matchbox's WebRTC stack runs on a tokio runtime.
+
+pub mod clock;
+
+#[cfg(not(any(target_arch = "wasm32", feature = "matchbox")))]
+mod native;
+
+#[cfg(not(any(target_arch = "wasm32", feature = "matchbox")))]
+use native as imp;
+
+#[cfg(any(target_arch = "wasm32", feature = "matchbox"))]
+mod matchbox;
+
+#[cfg(any(target_arch = "wasm32", feature = "matchbox"))]
+use matchbox as imp;
+
+/// Errors that can occur while networking.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    /// The operation is not supported on this platform.
+    #[error("unsupported")]
+    Unsupported,
+
+    /// An underlying error occurred.
+    #[error("other: {0}")]
+    Other(Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// A remote peer.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Peer(imp::PeerId);
+
+/// A change in the set of known peers.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PeerEvent {
+    /// A peer connected.
+    ///
+    /// On native platforms without the `matchbox` feature, this happens when a peer is added with [`Socket::add_peer`] or a packet is first received from it by [`Socket::receive`].
+    Connected(Peer),
+
+    /// A peer disconnected.
+    ///
+    /// On native platforms without the `matchbox` feature, this only happens when a peer is removed with [`Socket::remove_peer`], as UDP has no notion of connections.
+    Disconnected(Peer),
+}
+
+/// A socket for sending and receiving unreliable datagrams.
+pub struct Socket(imp::Socket);
+
+impl Socket {
+    /// Opens a socket.
+    ///
+    /// - On native platforms, `address` is the local address to bind to, e.g. `0.0.0.0:7777`, or `0.0.0.0:0` for any free port.
+    /// - On WASM, or with the `matchbox` feature, `address` is the URL of a matchbox signaling server room, e.g. `wss://example.com/my-game`. All other sockets in the same room become peers.
+    pub fn open(address: &str) -> Result<Self, Error> {
+        imp::Socket::open(address).map(Self)
+    }
+
+    /// Adds a peer by address, such that packets may be sent to it.
+    ///
+    /// This is only supported on native platforms, where `address` is a remote address such as `192.168.0.2:7777`. On WASM, or with the `matchbox` feature, peers are discovered through the signaling server and this always returns [`Error::Unsupported`].
+    pub fn add_peer(&mut self, address: &str) -> Result<Peer, Error> {
+        self.0.add_peer(address).map(Peer)
+    }
+
+    /// Removes a peer.
+    ///
+    /// On native platforms without the `matchbox` feature, the peer will be added again if another packet is received from it.
+    pub fn remove_peer(&mut self, peer: Peer) {
+        self.0.remove_peer(peer.0);
+    }
+
+    /// Gets all connected peers.
+    pub fn peers(&self) -> Vec<Peer> {
+        self.0.peers().into_iter().map(Peer).collect()
+    }
+
+    /// Sends a packet to a peer.
+    ///
+    /// Packets should be kept small (under about 1200 bytes) to avoid fragmentation.
+    pub fn send(&mut self, peer: Peer, data: &[u8]) {
+        self.0.send(peer.0, data);
+    }
+
+    /// Sends a packet to all connected peers.
+    pub fn broadcast(&mut self, data: &[u8]) {
+        for peer in self.0.peers() {
+            self.0.send(peer, data);
+        }
+    }
+
+    /// Gets changes to the set of peers since the last call.
+    ///
+    /// This should be called once per [`crate::Game::update`].
+    pub fn update_peers(&mut self) -> Vec<PeerEvent> {
+        self.0.update_peers()
+    }
+
+    /// Receives all packets that have arrived since the last call.
+    ///
+    /// This does not block, and should be called once per [`crate::Game::update`].
+    pub fn receive(&mut self) -> Vec<(Peer, Box<[u8]>)> {
+        self.0
+            .receive()
+            .into_iter()
+            .map(|(peer, data)| (Peer(peer), data))
+            .collect()
+    }
+}
@@ -0,0 +1,193 @@
+//! Clock synchronization.
+//!
+//! Players' clocks may be off by seconds or more, so games that need to agree on time (e.g. when a match starts, or which daily challenge is current) should ask a host or server for its time instead. [`ClockSync`] does this like [NTP](https://en.wikipedia.org/wiki/Network_Time_Protocol): it timestamps requests and replies on both ends, which gives the offset between the clocks and the round trip time, and keeps the samples with the lowest round trip time, whose offsets are the most accurate.
+//!
+//! Round trips are timed with a monotonic clock, such that adjustments to the system clock in the meantime don't corrupt samples.
+//!
+//! Messages are plain byte buffers sent over any transport, usually a [`Socket`](super::Socket):
+//!
+//! ```ignore
+//! // On the client, every few seconds:
+//! self.socket.send(host, &self.clock.request());
+//!
+//! // On the client and the host, for each received packet:
+//! for (peer, packet) in self.socket.receive() {
+//!     if let Some(reply) = clock::respond(&packet) {
+//!         self.socket.send(peer, &reply);
+//!     } else if !self.clock.handle(&packet) {
+//!         self.handle_game_packet(peer, &packet);
+//!     }
+//! }
+//!
+//! // Anywhere:
+//! let now = self.clock.now();
+//! ```
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// Prefix identifying clock messages, such that they can be told apart from game packets.
+const MAGIC: &[u8; 4] = b"TGCK";
+
+const REQUEST: u8 = 0;
+const REPLY: u8 = 1;
+
+/// Number of samples kept for estimating the offset.
+const MAX_SAMPLES: usize = 8;
+
+/// Gets the current system time in microseconds since the Unix epoch.
+fn now_micros() -> i64 {
+    match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(since) => since.as_micros() as i64,
+        Err(e) => -(e.duration().as_micros() as i64),
+    }
+}
+
+fn encode(tag: u8, timestamps: &[i64]) -> Vec<u8> {
+    let mut message = Vec::with_capacity(MAGIC.len() + 1 + timestamps.len() * 8);
+    message.extend_from_slice(MAGIC);
+    message.push(tag);
+    for timestamp in timestamps {
+        message.extend_from_slice(&timestamp.to_le_bytes());
+    }
+    message
+}
+
+fn decode<const N: usize>(message: &[u8], tag: u8) -> Option<[i64; N]> {
+    let rest = message.strip_prefix(MAGIC)?.strip_prefix(&[tag])?;
+    if rest.len() != N * 8 {
+        return None;
+    }
+    let mut timestamps = [0; N];
+    for (timestamp, bytes) in timestamps.iter_mut().zip(rest.chunks_exact(8)) {
+        *timestamp = i64::from_le_bytes(bytes.try_into().unwrap());
+    }
+    Some(timestamps)
+}
+
+/// Checks if a packet is a clock message, from either [`ClockSync::request`] or [`respond`].
+pub fn is_message(packet: &[u8]) -> bool {
+    packet.starts_with(MAGIC)
+}
+
+/// Replies to a clock request with this side's time, returning `None` if the packet isn't a request.
+///
+/// This is all the host or server has to do.
+pub fn respond(packet: &[u8]) -> Option<Vec<u8>> {
+    let start = Instant::now();
+    let received = now_micros();
+    let [sent, sent_monotonic] = decode::<2>(packet, REQUEST)?;
+    let processing = start.elapsed().as_micros() as i64;
+    Some(encode(REPLY, &[sent, sent_monotonic, received, processing]))
+}
+
+/// A measurement of the remote clock.
+#[derive(Clone, Copy, Debug)]
+struct Sample {
+    /// How far the remote clock is ahead of the local clock, in microseconds.
+    offset: i64,
+
+    /// Round trip time excluding the time the remote side took to reply, in microseconds.
+    round_trip: i64,
+}
+
+/// Estimates the offset of a remote clock from the local clock.
+pub struct ClockSync {
+    /// Start of the monotonic time sent with requests.
+    epoch: Instant,
+    samples: VecDeque<Sample>,
+}
+
+impl Default for ClockSync {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClockSync {
+    /// Creates a clock that hasn't been synchronized yet.
+    pub fn new() -> Self {
+        Self {
+            epoch: Instant::now(),
+            samples: VecDeque::new(),
+        }
+    }
+
+    fn monotonic_micros(&self) -> i64 {
+        self.epoch.elapsed().as_micros() as i64
+    }
+
+    /// Creates a request to send to the remote side, which answers it via [`respond`].
+    ///
+    /// Send requests regularly (e.g. every few seconds), as packets may be lost and network conditions change.
+    pub fn request(&self) -> Vec<u8> {
+        encode(REQUEST, &[now_micros(), self.monotonic_micros()])
+    }
+
+    /// Handles a reply from the remote side, returning whether the packet was a clock message.
+    pub fn handle(&mut self, packet: &[u8]) -> bool {
+        let received_monotonic = self.monotonic_micros();
+        let Some([sent, sent_monotonic, remote_received, processing]) = decode::<4>(packet, REPLY)
+        else {
+            return is_message(packet);
+        };
+
+        let elapsed = received_monotonic - sent_monotonic;
+        let round_trip = elapsed - processing;
+        if elapsed < 0 || round_trip < 0 {
+            // The reply doesn't belong to a request of this clock, e.g. one sent before it was created.
+            return true;
+        }
+
+        // The local receive time is derived from the send time, such that only one reading of the system clock is involved.
+        let received = sent + elapsed;
+        let remote_sent = remote_received + processing;
+        if self.samples.len() == MAX_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(Sample {
+            offset: ((remote_received - sent) + (remote_sent - received)) / 2,
+            round_trip,
+        });
+        true
+    }
+
+    /// Checks if at least one reply has been received.
+    pub fn is_synced(&self) -> bool {
+        !self.samples.is_empty()
+    }
+
+    /// Gets the most accurate recent sample, i.e. the one with the lowest round trip time.
+    fn best_sample(&self) -> Option<&Sample> {
+        self.samples.iter().min_by_key(|sample| sample.round_trip)
+    }
+
+    /// Gets how far the remote clock is ahead of the local clock in microseconds, negative if it is behind.
+    pub fn offset_micros(&self) -> Option<i64> {
+        self.best_sample().map(|sample| sample.offset)
+    }
+
+    /// Gets the round trip time of the most accurate recent sample, which bounds the error of the offset to half of it.
+    pub fn round_trip(&self) -> Option<Duration> {
+        self.best_sample()
+            .map(|sample| Duration::from_micros(sample.round_trip as u64))
+    }
+
+    /// Gets the current time of the remote clock, or the local time if not synchronized yet.
+    pub fn now(&self) -> SystemTime {
+        let offset = self.offset_micros().unwrap_or(0);
+        let now = SystemTime::now();
+        if offset >= 0 {
+            now + Duration::from_micros(offset as u64)
+        } else {
+            now - Duration::from_micros(offset.unsigned_abs())
+        }
+    }
+
+    /// Forgets all samples, e.g. after reconnecting to a different host.
+    pub fn reset(&mut self) {
+        self.samples.clear();
+    }
+}
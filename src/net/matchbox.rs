@@ -0,0 +1,72 @@
+#[cfg(not(target_arch = "wasm32"))]
+use matchbox_socket_native as matchbox_socket;
+
+use matchbox_socket::{PeerState, SingleChannel, WebRtcSocket};
+
+use super::{Error, Peer, PeerEvent};
+
+pub use matchbox_socket::PeerId;
+
+pub struct Socket {
+    socket: WebRtcSocket<SingleChannel>,
+    removed: Vec<PeerId>,
+}
+
+impl Socket {
+    pub fn open(address: &str) -> Result<Self, Error> {
+        let (socket, message_loop) = WebRtcSocket::new_unreliable(address);
+        crate::futures::spawn(async move {
+            if let Err(e) = message_loop.await {
+                log::error!("signaling failed: {e}");
+            }
+        });
+        Ok(Self {
+            socket,
+            removed: vec![],
+        })
+    }
+
+    pub fn add_peer(&mut self, _address: &str) -> Result<PeerId, Error> {
+        Err(Error::Unsupported)
+    }
+
+    pub fn remove_peer(&mut self, peer: PeerId) {
+        if !self.removed.contains(&peer) {
+            self.removed.push(peer);
+        }
+    }
+
+    pub fn peers(&self) -> Vec<PeerId> {
+        self.socket
+            .connected_peers()
+            .filter(|peer| !self.removed.contains(peer))
+            .collect()
+    }
+
+    pub fn send(&mut self, peer: PeerId, data: &[u8]) {
+        if self.removed.contains(&peer) {
+            return;
+        }
+        self.socket.send(data.into(), peer);
+    }
+
+    pub fn update_peers(&mut self) -> Vec<PeerEvent> {
+        self.socket
+            .update_peers()
+            .into_iter()
+            .filter(|(peer, _)| !self.removed.contains(peer))
+            .map(|(peer, state)| match state {
+                PeerState::Connected => PeerEvent::Connected(Peer(peer)),
+                PeerState::Disconnected => PeerEvent::Disconnected(Peer(peer)),
+            })
+            .collect()
+    }
+
+    pub fn receive(&mut self) -> Vec<(PeerId, Box<[u8]>)> {
+        self.socket
+            .receive()
+            .into_iter()
+            .filter(|(peer, _)| !self.removed.contains(peer))
+            .collect()
+    }
+}
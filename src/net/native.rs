@@ -0,0 +1,93 @@
+use std::net::{SocketAddr, ToSocketAddrs as _, UdpSocket};
+
+use super::{Error, Peer, PeerEvent};
+
+pub type PeerId = SocketAddr;
+
+/// Large enough for any UDP datagram.
+const MAX_PACKET_SIZE: usize = 65536;
+
+pub struct Socket {
+    socket: UdpSocket,
+    peers: Vec<SocketAddr>,
+    events: Vec<PeerEvent>,
+    buf: Box<[u8]>,
+}
+
+fn resolve(address: &str) -> Result<SocketAddr, Error> {
+    address
+        .to_socket_addrs()
+        .map_err(|e| Error::Other(e.into()))?
+        .next()
+        .ok_or_else(|| Error::Other(format!("could not resolve {address}").into()))
+}
+
+impl Socket {
+    pub fn open(address: &str) -> Result<Self, Error> {
+        let socket = UdpSocket::bind(resolve(address)?).map_err(|e| Error::Other(e.into()))?;
+        socket
+            .set_nonblocking(true)
+            .map_err(|e| Error::Other(e.into()))?;
+        Ok(Self {
+            socket,
+            peers: vec![],
+            events: vec![],
+            buf: vec![0; MAX_PACKET_SIZE].into_boxed_slice(),
+        })
+    }
+
+    fn connect(&mut self, addr: SocketAddr) {
+        if !self.peers.contains(&addr) {
+            self.peers.push(addr);
+            self.events.push(PeerEvent::Connected(Peer(addr)));
+        }
+    }
+
+    pub fn add_peer(&mut self, address: &str) -> Result<SocketAddr, Error> {
+        let addr = resolve(address)?;
+        self.connect(addr);
+        Ok(addr)
+    }
+
+    pub fn remove_peer(&mut self, peer: SocketAddr) {
+        let Some(i) = self.peers.iter().position(|p| *p == peer) else {
+            return;
+        };
+        self.peers.swap_remove(i);
+        self.events.push(PeerEvent::Disconnected(Peer(peer)));
+    }
+
+    pub fn peers(&self) -> Vec<SocketAddr> {
+        self.peers.clone()
+    }
+
+    pub fn send(&mut self, peer: SocketAddr, data: &[u8]) {
+        if let Err(e) = self.socket.send_to(data, peer) {
+            log::warn!("failed to send packet to {peer}: {e}");
+        }
+    }
+
+    pub fn update_peers(&mut self) -> Vec<PeerEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    pub fn receive(&mut self) -> Vec<(SocketAddr, Box<[u8]>)> {
+        let mut packets = vec![];
+        loop {
+            match self.socket.recv_from(&mut self.buf) {
+                Ok((len, addr)) => {
+                    self.connect(addr);
+                    packets.push((addr, self.buf[..len].into()));
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => break,
+                // On Windows, an ICMP port unreachable from a previous send is reported here.
+                Err(e) if e.kind() == std::io::ErrorKind::ConnectionReset => continue,
+                Err(e) => {
+                    log::warn!("failed to receive packet: {e}");
+                    break;
+                }
+            }
+        }
+        packets
+    }
+}
@@ -0,0 +1,236 @@
+//! Physics support, powered by [Rapier](https://rapier.rs).
+//!
+//! The [`PhysicsWorld`] in [`crate::Context`] is stepped automatically after every call to [`crate::Game::update`], so bodies move at a fixed rate regardless of frame rate.
+//!
+//! Rapier is tuned for objects sized in meters rather than pixels, so it is recommended to pick a scale (e.g. 32 pixels per meter) and convert when drawing.
+
+pub use rapier2d;
+
+use crate::math::{collision::Circle, IVec2, Rect, Vec2};
+use rapier2d::prelude::*;
+
+/// A physics world containing rigid bodies and colliders.
+pub struct PhysicsWorld {
+    /// Gravity applied to all dynamic bodies.
+    pub gravity: Vec2,
+
+    /// Integration parameters, e.g. the number of solver iterations.
+    ///
+    /// The time step is overwritten to match the current tick rate (see [`crate::time::Stepping::set_ticks_per_second`]).
+    pub integration_parameters: IntegrationParameters,
+
+    /// All rigid bodies.
+    pub bodies: RigidBodySet,
+
+    /// All colliders.
+    pub colliders: ColliderSet,
+
+    /// All impulse joints.
+    pub impulse_joints: ImpulseJointSet,
+
+    /// All multibody joints.
+    pub multibody_joints: MultibodyJointSet,
+
+    /// The query pipeline, for ray casts and shape casts.
+    pub query_pipeline: QueryPipeline,
+
+    physics_pipeline: PhysicsPipeline,
+    island_manager: IslandManager,
+    broad_phase: DefaultBroadPhase,
+    narrow_phase: NarrowPhase,
+    ccd_solver: CCDSolver,
+}
+
+fn to_vector(v: Vec2) -> Vector<Real> {
+    vector![v.x, v.y]
+}
+
+fn from_vector(v: &Vector<Real>) -> Vec2 {
+    Vec2::new(v.x, v.y)
+}
+
+impl PhysicsWorld {
+    pub(crate) fn new() -> Self {
+        Self {
+            gravity: Vec2::new(0.0, 9.81),
+            integration_parameters: IntegrationParameters::default(),
+            bodies: RigidBodySet::new(),
+            colliders: ColliderSet::new(),
+            impulse_joints: ImpulseJointSet::new(),
+            multibody_joints: MultibodyJointSet::new(),
+            query_pipeline: QueryPipeline::new(),
+            physics_pipeline: PhysicsPipeline::new(),
+            island_manager: IslandManager::new(),
+            broad_phase: DefaultBroadPhase::new(),
+            narrow_phase: NarrowPhase::new(),
+            ccd_solver: CCDSolver::new(),
+        }
+    }
+
+    pub(crate) fn step(&mut self, dt: f32) {
+        self.integration_parameters.dt = dt;
+        self.physics_pipeline.step(
+            &to_vector(self.gravity),
+            &self.integration_parameters,
+            &mut self.island_manager,
+            &mut self.broad_phase,
+            &mut self.narrow_phase,
+            &mut self.bodies,
+            &mut self.colliders,
+            &mut self.impulse_joints,
+            &mut self.multibody_joints,
+            &mut self.ccd_solver,
+            Some(&mut self.query_pipeline),
+            &(),
+            &(),
+        );
+    }
+
+    /// Adds a rigid body with a single collider.
+    pub fn add_body(
+        &mut self,
+        body: impl Into<RigidBody>,
+        collider: impl Into<Collider>,
+    ) -> RigidBodyHandle {
+        let handle = self.bodies.insert(body);
+        self.colliders
+            .insert_with_parent(collider, handle, &mut self.bodies);
+        handle
+    }
+
+    /// Adds a rigid body with a box collider matching the rectangle.
+    pub fn add_rect(&mut self, rect: &Rect, body_type: RigidBodyType) -> RigidBodyHandle {
+        let half_size = rect.size() / 2.0;
+        self.add_body(
+            RigidBodyBuilder::new(body_type).translation(to_vector(rect.center())),
+            ColliderBuilder::cuboid(half_size.x, half_size.y),
+        )
+    }
+
+    /// Adds a rigid body with a ball collider matching the circle.
+    pub fn add_circle(&mut self, circle: &Circle, body_type: RigidBodyType) -> RigidBodyHandle {
+        self.add_body(
+            RigidBodyBuilder::new(body_type).translation(to_vector(circle.center)),
+            ColliderBuilder::ball(circle.radius),
+        )
+    }
+
+    /// Adds fixed colliders for every solid cell of a grid (e.g. a tilemap collision layer) within the given cell range.
+    ///
+    /// Each row's runs of solid cells are merged into one collider per run, reducing the number of colliders and keeping bodies moving along a floor from catching on seams between its cells. Runs in different rows are not merged, so bodies sliding along a wall may still catch on the seams between rows.
+    pub fn add_grid(
+        &mut self,
+        cell_size: Vec2,
+        min: IVec2,
+        max: IVec2,
+        mut is_solid: impl FnMut(IVec2) -> bool,
+    ) -> RigidBodyHandle {
+        let handle = self.bodies.insert(RigidBodyBuilder::fixed());
+
+        for y in min.y..max.y {
+            let mut x = min.x;
+            while x < max.x {
+                if !is_solid(IVec2::new(x, y)) {
+                    x += 1;
+                    continue;
+                }
+                let start = x;
+                while x < max.x && is_solid(IVec2::new(x, y)) {
+                    x += 1;
+                }
+
+                let rect = Rect::from_min_max(
+                    IVec2::new(start, y).as_vec2() * cell_size,
+                    IVec2::new(x, y + 1).as_vec2() * cell_size,
+                );
+                let half_size = rect.size() / 2.0;
+                self.colliders.insert_with_parent(
+                    ColliderBuilder::cuboid(half_size.x, half_size.y)
+                        .translation(to_vector(rect.center())),
+                    handle,
+                    &mut self.bodies,
+                );
+            }
+        }
+
+        handle
+    }
+
+    /// Removes a rigid body and all its colliders.
+    pub fn remove_body(&mut self, handle: RigidBodyHandle) -> Option<RigidBody> {
+        self.bodies.remove(
+            handle,
+            &mut self.island_manager,
+            &mut self.colliders,
+            &mut self.impulse_joints,
+            &mut self.multibody_joints,
+            true,
+        )
+    }
+
+    /// Gets the position and rotation of a rigid body.
+    pub fn position(&self, handle: RigidBodyHandle) -> Option<(Vec2, f32)> {
+        let body = self.bodies.get(handle)?;
+        Some((from_vector(body.translation()), body.rotation().angle()))
+    }
+
+    /// Casts a ray and returns the first collider hit, along with the distance along the ray.
+    pub fn cast_ray(
+        &self,
+        origin: Vec2,
+        direction: Vec2,
+        max_distance: f32,
+    ) -> Option<(ColliderHandle, f32)> {
+        self.query_pipeline.cast_ray(
+            &self.bodies,
+            &self.colliders,
+            &Ray::new(point![origin.x, origin.y], to_vector(direction)),
+            max_distance,
+            true,
+            QueryFilter::default(),
+        )
+    }
+
+    /// Draws the outlines of all colliders.
+    #[cfg(feature = "debug")]
+    pub fn debug_draw(
+        &self,
+        canvas: &mut crate::graphics::debug::DebugCanvas,
+        color: crate::graphics::Color,
+    ) {
+        use crate::graphics::debug::DebugDraw as _;
+        use crate::math::collision::Polygon;
+
+        for (_, collider) in self.colliders.iter() {
+            let iso = collider.position();
+            let transform = |p: Point<Real>| {
+                let p = iso.transform_point(&p);
+                Vec2::new(p.x, p.y)
+            };
+
+            let shape = collider.shape();
+            if let Some(ball) = shape.as_ball() {
+                canvas.circle(from_vector(&iso.translation.vector), ball.radius, color);
+            } else if let Some(cuboid) = shape.as_cuboid() {
+                let h = cuboid.half_extents;
+                Polygon::new(vec![
+                    transform(point![-h.x, -h.y]),
+                    transform(point![h.x, -h.y]),
+                    transform(point![h.x, h.y]),
+                    transform(point![-h.x, h.y]),
+                ])
+                .debug_draw(canvas, color);
+            } else if let Some(polygon) = shape.as_convex_polygon() {
+                Polygon::new(polygon.points().iter().map(|p| transform(*p)).collect())
+                    .debug_draw(canvas, color);
+            } else {
+                let aabb = collider.compute_aabb();
+                Rect::from_min_max(
+                    Vec2::new(aabb.mins.x, aabb.mins.y),
+                    Vec2::new(aabb.maxs.x, aabb.maxs.y),
+                )
+                .debug_draw(canvas, color);
+            }
+        }
+    }
+}
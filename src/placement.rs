@@ -0,0 +1,82 @@
+//! Remembering the window's placement between runs, see [`crate::Game::REMEMBER_WINDOW_PLACEMENT`].
+
+use serde::{Deserialize, Serialize};
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+use winit::window::{Fullscreen, Window};
+
+use crate::storage;
+
+const STORAGE_KEY: &str = "window-placement";
+
+/// Where the window is on the desktop.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Placement {
+    /// Position of the window's outer top-left corner when not fullscreen, in physical pixels.
+    position: (i32, i32),
+
+    /// Inner size of the window when not fullscreen, in physical pixels.
+    size: (u32, u32),
+
+    /// Name of the monitor the window is on.
+    monitor: Option<String>,
+
+    fullscreen: bool,
+}
+
+impl Placement {
+    /// Gets the current placement of a window.
+    pub fn of(window: &Window) -> Self {
+        let position = window.outer_position().unwrap_or_default();
+        let size = window.inner_size();
+        Self {
+            position: (position.x, position.y),
+            size: (size.width, size.height),
+            monitor: window.current_monitor().and_then(|monitor| monitor.name()),
+            fullscreen: window.fullscreen().is_some(),
+        }
+    }
+
+    /// Updates the placement from a window, keeping the windowed position and size while it is fullscreen, such that leaving fullscreen in the next run restores them.
+    pub fn update(&mut self, window: &Window) {
+        let current = Self::of(window);
+        if current.fullscreen {
+            self.monitor = current.monitor;
+            self.fullscreen = true;
+        } else {
+            *self = current;
+        }
+    }
+
+    /// Loads the placement saved by the last run, if any.
+    pub fn load() -> Option<Self> {
+        let bytes = storage::get(STORAGE_KEY).ok()??;
+        postcard::from_bytes(&bytes).ok()
+    }
+
+    /// Saves the placement for the next run.
+    pub fn save(&self) {
+        let result = postcard::to_allocvec(self)
+            .map_err(|e| storage::Error::Other(e.into()))
+            .and_then(|bytes| storage::set(STORAGE_KEY, &bytes));
+        if let Err(e) = result {
+            log::warn!("failed to save window placement: {e}");
+        }
+    }
+
+    /// Moves and resizes a window to the placement.
+    ///
+    /// The position is only restored if the monitor the window was on is still connected, such that the window doesn't end up off-screen.
+    pub fn apply(&self, window: &Window) {
+        let monitor = window
+            .available_monitors()
+            .find(|monitor| monitor.name().is_some() && monitor.name() == self.monitor);
+
+        _ = window.request_inner_size(PhysicalSize::new(self.size.0, self.size.1));
+        if monitor.is_some() {
+            window.set_outer_position(PhysicalPosition::new(self.position.0, self.position.1));
+        }
+        if self.fullscreen {
+            window.set_fullscreen(Some(Fullscreen::Borderless(monitor)));
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! Battery and thermal status, e.g. for lowering the frame rate or turning off effects on hot or low-battery devices.
+//!
+//! ```ignore
+//! let low_battery = power::battery().is_some_and(|b| b.level < 0.2 && !b.charging);
+//! let hot = power::thermal().is_some_and(|t| t >= Thermal::Serious);
+//! self.particles_enabled = !low_battery && !hot;
+//! ```
+//!
+//! Status is only available where the platform provides it:
+//!
+//! - The battery is read on Android, on Linux and in browsers supporting the Battery Status API. In browsers, it only becomes available shortly after it is first queried.
+//! - The thermal status is read on Android 10 and later.
+//!
+//! Elsewhere, and on devices without a battery, `None` is returned.
+
+#[cfg(target_os = "android")]
+mod android;
+
+#[cfg(target_os = "android")]
+use android as imp;
+
+#[cfg(target_os = "linux")]
+mod linux;
+
+#[cfg(target_os = "linux")]
+use linux as imp;
+
+#[cfg(target_arch = "wasm32")]
+mod web;
+
+#[cfg(target_arch = "wasm32")]
+use web as imp;
+
+#[cfg(not(any(target_os = "android", target_os = "linux", target_arch = "wasm32")))]
+mod imp {
+    pub fn battery() -> Option<super::Battery> {
+        None
+    }
+
+    pub fn thermal() -> Option<super::Thermal> {
+        None
+    }
+}
+
+/// The state of the device's battery.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Battery {
+    /// Charge level, from 0 (empty) to 1 (full).
+    pub level: f32,
+
+    /// Whether the battery is being charged.
+    pub charging: bool,
+}
+
+/// How hot the device is, from coolest to hottest.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Thermal {
+    /// Not throttled.
+    Nominal,
+
+    /// Slightly warm, but not yet noticeably throttled.
+    Fair,
+
+    /// Throttled, such that performance is noticeably reduced.
+    Serious,
+
+    /// Heavily throttled, and close to shutting down to cool off.
+    Critical,
+}
+
+/// Gets the state of the device's battery, if it has one and the platform exposes it.
+pub fn battery() -> Option<Battery> {
+    imp::battery()
+}
+
+/// Gets how hot the device is, if the platform exposes it.
+pub fn thermal() -> Option<Thermal> {
+    imp::thermal()
+}
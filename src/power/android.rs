@@ -0,0 +1,55 @@
+use jni::objects::JValue;
+
+use super::{Battery, Thermal};
+use crate::android::with_system_service;
+
+/// `BatteryManager.BATTERY_PROPERTY_CAPACITY`.
+const BATTERY_PROPERTY_CAPACITY: i32 = 4;
+
+pub fn battery() -> Option<Battery> {
+    let result = with_system_service("BATTERY_SERVICE", |env, manager| {
+        let capacity = env
+            .call_method(
+                manager,
+                "getIntProperty",
+                "(I)I",
+                &[JValue::Int(BATTERY_PROPERTY_CAPACITY)],
+            )?
+            .i()?;
+        let charging = env.call_method(manager, "isCharging", "()Z", &[])?.z()?;
+        Ok((capacity, charging))
+    });
+    match result {
+        // The capacity is reported as the minimum integer if unsupported.
+        Ok(Some((capacity, charging))) if capacity >= 0 => Some(Battery {
+            level: capacity as f32 / 100.0,
+            charging,
+        }),
+        Ok(_) => None,
+        Err(e) => {
+            log::warn!("failed to get battery status: {e}");
+            None
+        }
+    }
+}
+
+pub fn thermal() -> Option<Thermal> {
+    let result = with_system_service("POWER_SERVICE", |env, manager| {
+        env.call_method(manager, "getCurrentThermalStatus", "()I", &[])?
+            .i()
+    });
+    match result {
+        Ok(Some(status)) => Some(match status {
+            0 => Thermal::Nominal,
+            1 => Thermal::Fair,
+            2 | 3 => Thermal::Serious,
+            _ => Thermal::Critical,
+        }),
+        Ok(None) => None,
+        Err(e) => {
+            // The method only exists on Android 10 and later.
+            log::debug!("failed to get thermal status: {e}");
+            None
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use super::{Battery, Thermal};
+
+pub fn battery() -> Option<Battery> {
+    // Laptops usually have a single battery named BAT0 or BAT1. Batteries of wireless mice and controllers are also listed, with a scope of Device.
+    let entries = std::fs::read_dir("/sys/class/power_supply").ok()?;
+    let dir = entries.flatten().map(|entry| entry.path()).find(|path| {
+        let read = |name| std::fs::read_to_string(path.join(name)).unwrap_or_default();
+        read("type").trim() == "Battery" && read("scope").trim() != "Device"
+    })?;
+
+    let capacity = std::fs::read_to_string(dir.join("capacity")).ok()?;
+    let status = std::fs::read_to_string(dir.join("status")).unwrap_or_default();
+    Some(Battery {
+        level: capacity.trim().parse::<f32>().ok()? / 100.0,
+        charging: matches!(status.trim(), "Charging" | "Full"),
+    })
+}
+
+pub fn thermal() -> Option<Thermal> {
+    None
+}
@@ -0,0 +1,43 @@
+use std::cell::RefCell;
+
+use wasm_bindgen::JsCast as _;
+
+use super::{Battery, Thermal};
+
+thread_local! {
+    /// The battery manager, once the browser has provided it. `None` inside means it was requested but isn't available (yet).
+    static MANAGER: RefCell<Option<Option<web_sys::BatteryManager>>> = const { RefCell::new(None) };
+}
+
+pub fn battery() -> Option<Battery> {
+    MANAGER.with_borrow_mut(|manager| {
+        let Some(manager) = manager else {
+            *manager = Some(None);
+            request_manager();
+            return None;
+        };
+        manager.as_ref().map(|manager| Battery {
+            level: manager.level() as f32,
+            charging: manager.charging(),
+        })
+    })
+}
+
+/// Requests the battery manager, which the browser provides asynchronously.
+fn request_manager() {
+    let Some(promise) = web_sys::window().and_then(|w| w.navigator().get_battery().ok()) else {
+        return;
+    };
+    wasm_bindgen_futures::spawn_local(async move {
+        let Ok(manager) = wasm_bindgen_futures::JsFuture::from(promise).await else {
+            return;
+        };
+        if let Ok(manager) = manager.dyn_into::<web_sys::BatteryManager>() {
+            MANAGER.set(Some(Some(manager)));
+        }
+    });
+}
+
+pub fn thermal() -> Option<Thermal> {
+    None
+}
@@ -0,0 +1,265 @@
+//! Achievements and stats.
+//!
+//! [`Progress`] tracks which achievements are unlocked and the values of stats (e.g. enemies defeated), and persists them via a [`Backend`]. By default, progress is saved with [`crate::storage`], but a backend for e.g. a storefront's achievement API may be slotted in without changing game code.
+//!
+//! ```ignore
+//! let mut progress = Progress::new(vec![
+//!     Achievement::new("first-blood", "First Blood", "Defeat an enemy."),
+//!     Achievement::new("exterminator", "Exterminator", "Defeat 1000 enemies.")
+//!         .with_stat_threshold("enemies-defeated", 1000.0),
+//! ]);
+//!
+//! progress.unlock("first-blood");
+//! progress.add_stat("enemies-defeated", 1.0);
+//! progress.save()?;
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Errors that can occur while loading or saving progress.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    /// Storage failed.
+    #[error("storage: {0}")]
+    Storage(#[from] crate::storage::Error),
+
+    /// An underlying error occurred.
+    #[error("other: {0}")]
+    Other(Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// Definition of an achievement.
+#[derive(Clone, Debug)]
+pub struct Achievement {
+    /// Unique identifier.
+    pub id: String,
+
+    /// Display name.
+    pub name: String,
+
+    /// Display description.
+    pub description: String,
+
+    /// Whether the achievement should be hidden until unlocked.
+    pub hidden: bool,
+
+    /// A stat and value at which the achievement is unlocked automatically.
+    pub stat_threshold: Option<(String, f64)>,
+}
+
+impl Achievement {
+    /// Creates a new achievement definition.
+    pub fn new(id: &str, name: &str, description: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: description.to_string(),
+            hidden: false,
+            stat_threshold: None,
+        }
+    }
+
+    /// Sets whether the achievement is hidden until unlocked.
+    pub fn with_hidden(mut self, hidden: bool) -> Self {
+        self.hidden = hidden;
+        self
+    }
+
+    /// Unlocks the achievement automatically when the stat reaches the given value.
+    pub fn with_stat_threshold(mut self, stat: &str, value: f64) -> Self {
+        self.stat_threshold = Some((stat.to_string(), value));
+        self
+    }
+}
+
+/// Saved progress.
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+pub struct Snapshot {
+    /// IDs of unlocked achievements.
+    pub unlocked: BTreeSet<String>,
+
+    /// Values of stats.
+    pub stats: BTreeMap<String, f64>,
+}
+
+/// Somewhere progress is persisted.
+pub trait Backend {
+    /// Loads previously saved progress, if any.
+    fn load(&mut self) -> Result<Option<Snapshot>, Error>;
+
+    /// Saves progress.
+    fn save(&mut self, snapshot: &Snapshot) -> Result<(), Error>;
+
+    /// Called when an achievement is unlocked, e.g. to show a platform notification.
+    fn unlocked(&mut self, id: &str) {
+        _ = id;
+    }
+
+    /// Called when a stat changes.
+    fn stat_changed(&mut self, id: &str, value: f64) {
+        _ = (id, value);
+    }
+}
+
+/// A backend that persists progress with [`crate::storage`].
+pub struct StorageBackend {
+    key: String,
+}
+
+impl StorageBackend {
+    /// Creates a backend that stores progress under the given storage key.
+    pub fn new(key: &str) -> Self {
+        Self {
+            key: key.to_string(),
+        }
+    }
+}
+
+impl Backend for StorageBackend {
+    fn load(&mut self) -> Result<Option<Snapshot>, Error> {
+        Ok(crate::storage::get(&self.key)?.and_then(|bytes| crate::state::from_bytes(&bytes)))
+    }
+
+    fn save(&mut self, snapshot: &Snapshot) -> Result<(), Error> {
+        Ok(crate::storage::set(
+            &self.key,
+            &crate::state::to_bytes(snapshot),
+        )?)
+    }
+}
+
+/// Tracks achievements and stats.
+pub struct Progress {
+    achievements: Vec<Achievement>,
+    snapshot: Snapshot,
+    backend: Box<dyn Backend>,
+    newly_unlocked: Vec<String>,
+    dirty: bool,
+}
+
+impl Progress {
+    /// Creates a tracker for the given achievements, loading saved progress from [`crate::storage`].
+    pub fn new(achievements: Vec<Achievement>) -> Self {
+        Self::with_backend(achievements, StorageBackend::new("progress"))
+    }
+
+    /// Creates a tracker for the given achievements, loading saved progress from the given backend.
+    ///
+    /// If loading fails, progress starts from scratch.
+    pub fn with_backend(
+        achievements: Vec<Achievement>,
+        mut backend: impl Backend + 'static,
+    ) -> Self {
+        let snapshot = backend
+            .load()
+            .unwrap_or_else(|e| {
+                log::warn!("failed to load progress: {e}");
+                None
+            })
+            .unwrap_or_default();
+        Self {
+            achievements,
+            snapshot,
+            backend: Box::new(backend),
+            newly_unlocked: vec![],
+            dirty: false,
+        }
+    }
+
+    /// Gets all achievement definitions.
+    pub fn achievements(&self) -> &[Achievement] {
+        &self.achievements
+    }
+
+    /// Gets an achievement definition by ID.
+    pub fn achievement(&self, id: &str) -> Option<&Achievement> {
+        self.achievements.iter().find(|a| a.id == id)
+    }
+
+    /// Checks if an achievement is unlocked.
+    pub fn is_unlocked(&self, id: &str) -> bool {
+        self.snapshot.unlocked.contains(id)
+    }
+
+    /// Unlocks an achievement, returning true if it was not already unlocked.
+    pub fn unlock(&mut self, id: &str) -> bool {
+        if self.achievement(id).is_none() {
+            log::warn!("unlocking unknown achievement {id}");
+        }
+        if !self.snapshot.unlocked.insert(id.to_string()) {
+            return false;
+        }
+        self.backend.unlocked(id);
+        self.newly_unlocked.push(id.to_string());
+        self.dirty = true;
+        true
+    }
+
+    /// Takes the IDs of achievements unlocked since the last call, e.g. for showing notifications.
+    pub fn take_newly_unlocked(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.newly_unlocked)
+    }
+
+    /// Gets the value of a stat, or 0 if it was never set.
+    pub fn stat(&self, id: &str) -> f64 {
+        self.snapshot.stats.get(id).copied().unwrap_or(0.0)
+    }
+
+    /// Sets the value of a stat, unlocking any achievements whose threshold is reached.
+    pub fn set_stat(&mut self, id: &str, value: f64) {
+        if self.snapshot.stats.get(id) == Some(&value) {
+            return;
+        }
+        self.snapshot.stats.insert(id.to_string(), value);
+        self.backend.stat_changed(id, value);
+        self.dirty = true;
+
+        let reached = self
+            .achievements
+            .iter()
+            .filter(|a| {
+                a.stat_threshold
+                    .as_ref()
+                    .is_some_and(|(stat, threshold)| stat == id && value >= *threshold)
+            })
+            .map(|a| a.id.clone())
+            .collect::<Vec<_>>();
+        for achievement in reached {
+            self.unlock(&achievement);
+        }
+    }
+
+    /// Adds to the value of a stat.
+    pub fn add_stat(&mut self, id: &str, delta: f64) {
+        self.set_stat(id, self.stat(id) + delta);
+    }
+
+    /// Sets the value of a stat if it is higher than the current value, e.g. for high scores.
+    pub fn max_stat(&mut self, id: &str, value: f64) {
+        if value > self.stat(id) {
+            self.set_stat(id, value);
+        }
+    }
+
+    /// Gets the current progress.
+    pub fn snapshot(&self) -> &Snapshot {
+        &self.snapshot
+    }
+
+    /// Clears all progress.
+    pub fn reset(&mut self) {
+        self.snapshot = Snapshot::default();
+        self.dirty = true;
+    }
+
+    /// Saves progress via the backend, if anything changed since the last save.
+    pub fn save(&mut self) -> Result<(), Error> {
+        if !self.dirty {
+            return Ok(());
+        }
+        self.backend.save(&self.snapshot)?;
+        self.dirty = false;
+        Ok(())
+    }
+}
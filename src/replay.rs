@@ -0,0 +1,204 @@
+//! Input recording and playback.
+//!
+//! If [`crate::Game::RECORD_REPLAY`] is enabled, the input state of every tick since the game started is recorded into a [`Replay`], available via [`crate::Context::replay`]. Saving it and passing it to [`crate::run_replay`] plays the game again with the recorded inputs instead of live ones, e.g. for demos, ghosts or reproducing bugs.
+//!
+//! Replays start with a format version, and replays recorded by a version of teenygame with a different format are rejected when loaded, rather than played back wrongly.
+//!
+//! For playback to match the original run, the game must be deterministic: all randomness must come from [`seed`], and [`crate::Game::update`] must not depend on anything other than the game state and input (e.g. not on the wall clock).
+//!
+//! ```ignore
+//! fn new() -> Self {
+//!     Self { rng: Rng::new(teenygame::replay::seed()), /* ... */ }
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::input::{Key, KeyCode, MouseButton};
+
+/// Identifies serialized replays.
+const MAGIC: &[u8; 4] = b"TGRP";
+
+/// Version of the replay format, which must be bumped whenever [`InputFrame`] or [`Replay`] change.
+const FORMAT_VERSION: u32 = 1;
+
+/// Errors that can occur while loading a replay.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    /// The replay was recorded by a version of teenygame with a different replay format.
+    #[error("unsupported replay format version {0}, expected {FORMAT_VERSION}")]
+    UnsupportedVersion(u32),
+
+    /// The data is not a replay, or is corrupt or truncated.
+    #[error("invalid replay")]
+    Invalid,
+}
+
+static SEED: AtomicU64 = AtomicU64::new(0);
+
+/// Gets the random seed for this run of the game.
+///
+/// This is different each time the game is run, except when playing back a replay, where it is the seed of the recorded run.
+pub fn seed() -> u64 {
+    SEED.load(Ordering::Relaxed)
+}
+
+pub(crate) fn set_seed(seed: u64) {
+    SEED.store(seed, Ordering::Relaxed);
+}
+
+/// The state of all input devices during a tick.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Default, Debug)]
+pub(crate) struct InputFrame {
+    pub(crate) keys: Vec<KeyCode>,
+    pub(crate) repeated_keys: Vec<KeyCode>,
+    pub(crate) logical_keys: Vec<(KeyCode, Key)>,
+    pub(crate) mouse_buttons: Vec<MouseButton>,
+    pub(crate) mouse_position: Option<(f64, f64)>,
+    pub(crate) scroll_lines: (f32, f32),
+    pub(crate) scroll_pixels: (f32, f32),
+    pub(crate) contacts: Vec<(u64, (f64, f64))>,
+}
+
+/// Recorded inputs of a run of the game.
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+pub struct Replay {
+    seed: u64,
+    ticks: u64,
+
+    /// Input frames along with the tick they start at, only recorded when the input changes.
+    frames: Vec<(u64, InputFrame)>,
+}
+
+impl Replay {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            ticks: 0,
+            frames: vec![],
+        }
+    }
+
+    /// Gets the random seed of the recorded run.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Gets the number of recorded ticks.
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    /// Serializes the replay, e.g. for saving to a file.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        bytes.extend(crate::state::to_bytes(self));
+        bytes
+    }
+
+    /// Deserializes a replay created by [`Replay::to_bytes`].
+    ///
+    /// Fails with [`Error::UnsupportedVersion`] if the replay was recorded by a version of teenygame with a different replay format.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let bytes = bytes.strip_prefix(MAGIC).ok_or(Error::Invalid)?;
+        let (version, bytes) = bytes.split_first_chunk().ok_or(Error::Invalid)?;
+        let version = u32::from_le_bytes(*version);
+        if version != FORMAT_VERSION {
+            return Err(Error::UnsupportedVersion(version));
+        }
+        postcard::from_bytes(bytes).map_err(|_| Error::Invalid)
+    }
+
+    pub(crate) fn record(&mut self, frame: InputFrame) {
+        // Scrolling and key repeats only last a tick, so they're recorded again even if they're the same.
+        let transient = !frame.repeated_keys.is_empty()
+            || frame.scroll_lines != (0.0, 0.0)
+            || frame.scroll_pixels != (0.0, 0.0);
+        if transient || self.frames.last().is_none_or(|(_, last)| *last != frame) {
+            self.frames.push((self.ticks, frame));
+        }
+        self.ticks += 1;
+    }
+}
+
+/// Plays back a replay tick by tick.
+pub(crate) struct Playback {
+    replay: Replay,
+    tick: u64,
+    next_frame: usize,
+}
+
+impl Playback {
+    pub(crate) fn new(replay: Replay) -> Self {
+        Self {
+            replay,
+            tick: 0,
+            next_frame: 0,
+        }
+    }
+
+    pub(crate) fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    /// Checks if all recorded ticks have been played back.
+    pub(crate) fn is_finished(&self) -> bool {
+        self.tick >= self.replay.ticks
+    }
+
+    /// Advances by one tick, returning the new input frame if the input changed.
+    pub(crate) fn next(&mut self) -> Option<&InputFrame> {
+        let tick = self.tick;
+        self.tick += 1;
+
+        let (start, frame) = self.replay.frames.get(self.next_frame)?;
+        if *start != tick {
+            return None;
+        }
+        self.next_frame += 1;
+        Some(frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replay() -> Replay {
+        let mut replay = Replay::new(42);
+        replay.record(InputFrame::default());
+        replay.record(InputFrame {
+            keys: vec![KeyCode::KeyA],
+            repeated_keys: vec![KeyCode::KeyA],
+            logical_keys: vec![(KeyCode::KeyA, Key::Character("a".into()))],
+            ..Default::default()
+        });
+        replay
+    }
+
+    #[test]
+    fn round_trips() {
+        let replay = replay();
+        let loaded = Replay::from_bytes(&replay.to_bytes()).unwrap();
+        assert_eq!(loaded.seed(), 42);
+        assert_eq!(loaded.ticks(), 2);
+        assert_eq!(loaded.frames, replay.frames);
+    }
+
+    #[test]
+    fn rejects_other_versions() {
+        let mut bytes = replay().to_bytes();
+        bytes[MAGIC.len()..MAGIC.len() + 4].copy_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+        assert!(matches!(
+            Replay::from_bytes(&bytes),
+            Err(Error::UnsupportedVersion(version)) if version == FORMAT_VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn rejects_non_replays() {
+        assert!(matches!(Replay::from_bytes(b"nope"), Err(Error::Invalid)));
+    }
+}
@@ -0,0 +1,263 @@
+//! Crash and error reporting.
+//!
+//! A [`Reporter`] set with [`set_reporter`] is invoked whenever the game panics or, on native platforms, logs an error with [`log::error!`]. Each [`Report`] includes information about the platform and GPU to help diagnose issues on players' machines.
+//!
+//! ```ignore
+//! fn new() -> Self {
+//!     teenygame::report::set_reporter(teenygame::report::FileReporter::new());
+//!     // ...
+//! }
+//! ```
+
+use std::cell::Cell;
+use std::sync::{Mutex, RwLock};
+
+/// What caused a report.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ReportKind {
+    /// The game panicked.
+    Panic,
+
+    /// An error was logged.
+    Error,
+}
+
+/// Information about the system the game is running on.
+#[derive(Clone, Debug)]
+pub struct SystemInfo {
+    /// The operating system, e.g. `linux`.
+    pub os: &'static str,
+
+    /// The CPU architecture, e.g. `x86_64`.
+    pub arch: &'static str,
+
+    /// The version of teenygame.
+    pub teenygame_version: &'static str,
+
+    /// A description of the GPU and graphics backend, once graphics have been initialized.
+    pub gpu: Option<String>,
+}
+
+impl SystemInfo {
+    const fn new() -> Self {
+        Self {
+            os: std::env::consts::OS,
+            arch: std::env::consts::ARCH,
+            teenygame_version: env!("CARGO_PKG_VERSION"),
+            gpu: None,
+        }
+    }
+}
+
+/// A report of a panic or error.
+#[derive(Clone, Debug)]
+pub struct Report {
+    /// What caused the report.
+    pub kind: ReportKind,
+
+    /// The panic or error message.
+    pub message: String,
+
+    /// Where the panic or error happened, if known.
+    pub location: Option<String>,
+
+    /// Information about the system.
+    pub system: SystemInfo,
+}
+
+impl std::fmt::Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{:?}: {}", self.kind, self.message)?;
+        if let Some(location) = &self.location {
+            writeln!(f, "location: {location}")?;
+        }
+        writeln!(f, "os: {} ({})", self.system.os, self.system.arch)?;
+        writeln!(f, "teenygame: {}", self.system.teenygame_version)?;
+        writeln!(
+            f,
+            "gpu: {}",
+            self.system.gpu.as_deref().unwrap_or("not initialized")
+        )
+    }
+}
+
+/// Receives reports of panics and errors.
+pub trait Reporter: Send + Sync {
+    /// Handles a report, e.g. by sending it to a server.
+    ///
+    /// This may be called from any thread, and should not panic.
+    fn report(&self, report: &Report);
+}
+
+static REPORTER: RwLock<Option<Box<dyn Reporter>>> = RwLock::new(None);
+static SYSTEM_INFO: Mutex<SystemInfo> = Mutex::new(SystemInfo::new());
+
+thread_local! {
+    /// Set while reporting, such that errors logged by the reporter itself are not reported again.
+    static REPORTING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Sets the reporter to invoke on panics and errors, replacing any previous reporter.
+pub fn set_reporter(reporter: impl Reporter + 'static) {
+    *REPORTER.write().unwrap_or_else(|e| e.into_inner()) = Some(Box::new(reporter));
+}
+
+/// Removes the reporter.
+pub fn clear_reporter() {
+    *REPORTER.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Gets information about the system the game is running on.
+pub fn system_info() -> SystemInfo {
+    SYSTEM_INFO
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+pub(crate) fn set_gpu_info(info: &wgpu::AdapterInfo) {
+    SYSTEM_INFO.lock().unwrap_or_else(|e| e.into_inner()).gpu = Some(format!(
+        "{} ({:?}, {} {})",
+        info.name, info.backend, info.driver, info.driver_info
+    ));
+}
+
+fn report(kind: ReportKind, message: String, location: Option<String>) {
+    if REPORTING.with(|r| r.replace(true)) {
+        return;
+    }
+    if let Some(reporter) = REPORTER.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        reporter.report(&Report {
+            kind,
+            message,
+            location,
+            system: system_info(),
+        });
+    }
+    REPORTING.with(|r| r.set(false));
+}
+
+/// Installs the panic hook, chaining to any existing hook.
+pub(crate) fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        report(
+            ReportKind::Panic,
+            message,
+            info.location().map(|l| l.to_string()),
+        );
+        previous(info);
+    }));
+}
+
+/// A logger that reports errors before passing records on to another logger.
+pub(crate) struct ReportingLogger<L>(pub(crate) L);
+
+impl<L> log::Log for ReportingLogger<L>
+where
+    L: log::Log,
+{
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() == log::Level::Error || self.0.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record) {
+        if record.level() == log::Level::Error {
+            report(
+                ReportKind::Error,
+                record.args().to_string(),
+                record
+                    .file()
+                    .map(|file| format!("{}:{}", file, record.line().unwrap_or(0))),
+            );
+        }
+        self.0.log(record);
+    }
+
+    fn flush(&self) {
+        self.0.flush();
+    }
+}
+
+/// A reporter that writes reports to files in a `reports` directory in the user's data directory.
+///
+/// On panic, the directory is opened in the system's file browser so the player can easily attach the report to a bug report.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct FileReporter {
+    open_folder_on_panic: bool,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileReporter {
+    /// Creates a new file reporter.
+    pub fn new() -> Self {
+        Self {
+            open_folder_on_panic: true,
+        }
+    }
+
+    /// Sets whether the reports directory is opened on panic.
+    pub fn with_open_folder_on_panic(mut self, open_folder_on_panic: bool) -> Self {
+        self.open_folder_on_panic = open_folder_on_panic;
+        self
+    }
+
+    fn write(&self, report: &Report) -> std::io::Result<std::path::PathBuf> {
+        let dir = crate::storage::data_dir()
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::NotFound, e.to_string()))?
+            .join("reports");
+        std::fs::create_dir_all(&dir)?;
+        let timestamp = crate::time::SystemTime::now()
+            .duration_since(crate::time::UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or(0);
+        let kind = match report.kind {
+            ReportKind::Panic => "panic",
+            ReportKind::Error => "error",
+        };
+        std::fs::write(
+            dir.join(format!("{timestamp}-{kind}.txt")),
+            report.to_string(),
+        )?;
+        Ok(dir)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Default for FileReporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Reporter for FileReporter {
+    fn report(&self, report: &Report) {
+        let dir = match self.write(report) {
+            Ok(dir) => dir,
+            Err(e) => {
+                log::warn!("failed to write report: {e}");
+                return;
+            }
+        };
+
+        if report.kind != ReportKind::Panic || !self.open_folder_on_panic {
+            return;
+        }
+
+        let opener = if cfg!(target_os = "windows") {
+            "explorer"
+        } else if cfg!(target_os = "macos") {
+            "open"
+        } else {
+            "xdg-open"
+        };
+        _ = std::process::Command::new(opener).arg(&dir).spawn();
+    }
+}
@@ -0,0 +1,266 @@
+//! Rollback netcode.
+//!
+//! A [`Rollback`] session runs a simulation ahead of remote players' inputs by predicting them, saving a snapshot of the state every tick. When an input arrives that differs from the prediction, the state is rolled back to the tick the input was for and resimulated with the corrected inputs, hiding network latency for fast-paced games.
+//!
+//! The simulation must be deterministic: given the same state and inputs, it must always produce the same result. Inputs are sent between players by the game, e.g. with [`crate::net::Socket`].
+//!
+//! ```ignore
+//! // In Game::update:
+//! let frame = self.session.add_local_input(read_input(ctxt.input));
+//! self.socket.broadcast(&encode(frame, &input));
+//! for (_, packet) in self.socket.receive() {
+//!     let (frame, input) = decode(&packet);
+//!     self.session.add_remote_input(REMOTE_PLAYER, frame, input);
+//! }
+//! if self.session.can_advance() {
+//!     self.session.advance(|state, inputs| state.step(inputs));
+//! }
+//! ```
+
+use std::collections::{BTreeMap, VecDeque};
+
+/// A rollback session for a simulation with state `S` and per-player inputs `I`.
+pub struct Rollback<S, I> {
+    state: S,
+    frame: u32,
+    local_player: usize,
+    input_delay: u32,
+    max_prediction: u32,
+
+    /// Known inputs for each player, by frame.
+    inputs: Vec<BTreeMap<u32, I>>,
+
+    /// For each player, the first frame for which the input is not yet known.
+    next_unknown: Vec<u32>,
+
+    /// Inputs used to simulate each unconfirmed frame.
+    used: VecDeque<Vec<I>>,
+
+    /// Snapshots of the state before each unconfirmed frame was simulated.
+    snapshots: VecDeque<S>,
+
+    /// The earliest frame that was simulated with a mispredicted input.
+    rollback_from: Option<u32>,
+}
+
+impl<S, I> Rollback<S, I>
+where
+    S: Clone,
+    I: Clone + PartialEq + Default,
+{
+    /// Creates a new session with the given initial state.
+    ///
+    /// `local_player` is the index of the player whose inputs are added with [`Rollback::add_local_input`].
+    pub fn new(state: S, num_players: usize, local_player: usize) -> Self {
+        assert!(local_player < num_players, "local player out of range");
+        Self {
+            state,
+            frame: 0,
+            local_player,
+            input_delay: 0,
+            max_prediction: 8,
+            inputs: (0..num_players).map(|_| BTreeMap::new()).collect(),
+            next_unknown: vec![0; num_players],
+            used: VecDeque::new(),
+            snapshots: VecDeque::new(),
+            rollback_from: None,
+        }
+    }
+
+    /// Sets the number of frames local inputs are delayed by.
+    ///
+    /// Delaying inputs gives them more time to reach remote players, reducing how often rollbacks happen at the cost of responsiveness. Defaults to 0.
+    ///
+    /// This must be set before the first input is added, and should be the same for all players, as no player sends inputs for the first `input_delay` frames.
+    pub fn with_input_delay(mut self, input_delay: u32) -> Self {
+        self.input_delay = input_delay;
+        for player in 0..self.inputs.len() {
+            for frame in 0..input_delay {
+                self.inputs[player].insert(frame, I::default());
+            }
+            self.update_next_unknown(player);
+        }
+        self
+    }
+
+    /// Sets the maximum number of frames the simulation may run ahead of the last frame for which all inputs are known.
+    ///
+    /// Defaults to 8.
+    pub fn with_max_prediction(mut self, max_prediction: u32) -> Self {
+        self.max_prediction = max_prediction;
+        self
+    }
+
+    /// Gets the current state.
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    /// Gets the number of frames simulated so far.
+    pub fn frame(&self) -> u32 {
+        self.frame
+    }
+
+    /// Gets the number of frames for which the inputs of all players are known, i.e. frames that will never be rolled back.
+    pub fn confirmed_frames(&self) -> u32 {
+        self.next_unknown.iter().copied().min().unwrap_or(0)
+    }
+
+    /// Adds the local player's input for the current frame, returning the frame it applies to once input delay is taken into account.
+    ///
+    /// The input and frame should be sent to all remote players.
+    pub fn add_local_input(&mut self, input: I) -> u32 {
+        let frame = self.frame + self.input_delay;
+        self.add_input(self.local_player, frame, input);
+        frame
+    }
+
+    /// Adds a remote player's input for the given frame.
+    ///
+    /// Inputs may be added in any order, and duplicate inputs are ignored.
+    pub fn add_remote_input(&mut self, player: usize, frame: u32, input: I) {
+        self.add_input(player, frame, input);
+    }
+
+    fn add_input(&mut self, player: usize, frame: u32, input: I) {
+        if frame < self.confirmed_frames() || self.inputs[player].contains_key(&frame) {
+            return;
+        }
+
+        if frame < self.frame {
+            let base = self.frame - self.used.len() as u32;
+            let used = &self.used[(frame - base) as usize];
+            if used[player] != input {
+                self.rollback_from = Some(self.rollback_from.map_or(frame, |f| f.min(frame)));
+            }
+        }
+
+        self.inputs[player].insert(frame, input);
+        self.update_next_unknown(player);
+    }
+
+    fn update_next_unknown(&mut self, player: usize) {
+        while self.inputs[player].contains_key(&self.next_unknown[player]) {
+            self.next_unknown[player] += 1;
+        }
+    }
+
+    /// Gets the input of a player for a frame, predicting it from the most recent known input if it is not known.
+    fn input(&self, player: usize, frame: u32) -> I {
+        self.inputs[player]
+            .range(..=frame)
+            .next_back()
+            .map(|(_, input)| input.clone())
+            .unwrap_or_default()
+    }
+
+    /// Checks if the simulation may advance without exceeding the maximum prediction.
+    ///
+    /// If this is false, the game should wait for remote inputs to arrive.
+    pub fn can_advance(&self) -> bool {
+        self.frame < self.confirmed_frames() + self.max_prediction
+    }
+
+    /// Advances the simulation by one frame, first rolling back and resimulating any frames that were simulated with mispredicted inputs.
+    ///
+    /// `step` is called with the state and each player's input for the frame, and must be deterministic. Returns the number of frames that were resimulated.
+    pub fn advance(&mut self, mut step: impl FnMut(&mut S, &[I])) -> u32 {
+        let base = self.frame - self.snapshots.len() as u32;
+
+        let mut resimulated = 0;
+        if let Some(from) = self.rollback_from.take() {
+            let i = (from - base) as usize;
+            self.state = self.snapshots[i].clone();
+            self.snapshots.truncate(i);
+            self.used.truncate(i);
+            for frame in from..self.frame {
+                self.simulate(frame, &mut step);
+                resimulated += 1;
+            }
+        }
+
+        self.simulate(self.frame, &mut step);
+        self.frame += 1;
+
+        // Discard snapshots of frames that can no longer be rolled back.
+        let confirmed = self.confirmed_frames().min(self.frame);
+        while self.frame - (self.snapshots.len() as u32) < confirmed {
+            self.snapshots.pop_front();
+            self.used.pop_front();
+        }
+        for inputs in self.inputs.iter_mut() {
+            // Keep the most recent confirmed input around for prediction.
+            *inputs = inputs.split_off(&confirmed.saturating_sub(1));
+        }
+
+        resimulated
+    }
+
+    fn simulate(&mut self, frame: u32, step: &mut impl FnMut(&mut S, &[I])) {
+        let inputs = (0..self.inputs.len())
+            .map(|player| self.input(player, frame))
+            .collect::<Vec<_>>();
+        self.snapshots.push_back(self.state.clone());
+        step(&mut self.state, &inputs);
+        self.used.push_back(inputs);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Records the inputs of every simulated frame as the state.
+    fn step(state: &mut Vec<Vec<u8>>, inputs: &[u8]) {
+        state.push(inputs.to_vec());
+    }
+
+    #[test]
+    fn mispredicted_input_resimulates_once_from_its_frame() {
+        let mut session = Rollback::new(vec![], 2, 0);
+        for _ in 0..3 {
+            session.add_local_input(1);
+            assert_eq!(session.advance(step), 0);
+        }
+
+        // The remote input for frame 0 matches the prediction, but the one for frame 1 doesn't.
+        session.add_remote_input(1, 0, 0);
+        session.add_remote_input(1, 1, 5);
+
+        session.add_local_input(1);
+        let mut steps = 0;
+        let resimulated = session.advance(|state, inputs| {
+            steps += 1;
+            step(state, inputs);
+        });
+        assert_eq!(resimulated, 2);
+        assert_eq!(steps, 3);
+        assert_eq!(
+            session.state(),
+            &[vec![1, 0], vec![1, 5], vec![1, 5], vec![1, 5]]
+        );
+
+        session.add_local_input(1);
+        assert_eq!(session.advance(step), 0);
+    }
+
+    #[test]
+    fn input_delay_stalls_at_max_prediction() {
+        let mut session = Rollback::new(vec![], 2, 0)
+            .with_input_delay(2)
+            .with_max_prediction(3);
+
+        // Inputs for the delayed frames are known, so the simulation may run ahead of them by the maximum prediction.
+        let mut advanced = 0;
+        while session.can_advance() && advanced < 100 {
+            assert_eq!(session.add_local_input(1), session.frame() + 2);
+            session.advance(step);
+            advanced += 1;
+        }
+        assert_eq!(advanced, 5);
+        assert_eq!(session.confirmed_frames(), 2);
+
+        session.add_remote_input(1, 2, 0);
+        assert!(session.can_advance());
+    }
+}
@@ -0,0 +1,217 @@
+//! Screenshots for sharing.
+//!
+//! [`crate::graphics::Graphics::capture_to_clipboard`] and [`crate::graphics::Graphics::capture_and_share`] capture the frame currently being drawn once it is complete, e.g. for a share button:
+//!
+//! ```ignore
+//! if share_button.clicked() {
+//!     ctxt.gfx.capture_and_share("My high score!");
+//! }
+//! ```
+//!
+//! Sharing depends on the platform:
+//!
+//! - In browsers, the screenshot is shared via the Web Share API where it supports files (mostly mobile browsers), and downloaded otherwise. Browsers only allow copying to the clipboard and sharing shortly after user input, so request captures in response to it.
+//! - On desktop platforms, the screenshot is copied to the clipboard if the `clipboard` feature is enabled, or saved to the user's pictures directory when sharing.
+//! - On Android and iOS, the clipboard is not supported, and sharing saves to the app's data directory.
+//!
+//! Screenshots are taken asynchronously, and failures are logged rather than reported to the game.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::graphics::Color;
+use crate::image::Img;
+
+/// Errors that can occur while copying or sharing a screenshot.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    /// The platform doesn't support the operation.
+    #[error("not supported")]
+    NotSupported,
+
+    /// Encoding the screenshot failed.
+    #[error("image: {0}")]
+    Image(#[from] image::ImageError),
+
+    /// An underlying error occurred.
+    #[error("other: {0}")]
+    Other(Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// What to do with a captured frame.
+pub(crate) enum Action {
+    Clipboard,
+    Share { title: String },
+}
+
+impl Action {
+    async fn run(self, img: &Img<Vec<Color>>) {
+        let result = match self {
+            Action::Clipboard => copy_to_clipboard(img).await,
+            Action::Share { title } => share(img, &title).await,
+        };
+        if let Err(e) = result {
+            log::warn!("failed to share screenshot: {e}");
+        }
+    }
+}
+
+/// Copies an image to the clipboard.
+pub async fn copy_to_clipboard(img: &Img<Vec<Color>>) -> Result<(), Error> {
+    #[cfg(target_arch = "wasm32")]
+    {
+        let bytes = crate::image::encode_png(img)?;
+        crate::web::copy_png(&bytes)
+            .await
+            .map_err(|e| Error::Other(format!("{e:?}").into()))
+    }
+
+    #[cfg(all(
+        feature = "clipboard",
+        not(any(target_arch = "wasm32", target_os = "android", target_os = "ios"))
+    ))]
+    {
+        use crate::image::AsImgRef as _;
+        use std::sync::Mutex;
+
+        // On some platforms (e.g. X11), the clipboard's contents are only available while the clipboard is open.
+        static CLIPBOARD: Mutex<Option<arboard::Clipboard>> = Mutex::new(None);
+
+        let mut clipboard = CLIPBOARD.lock().unwrap_or_else(|e| e.into_inner());
+        if clipboard.is_none() {
+            *clipboard = Some(arboard::Clipboard::new().map_err(|e| Error::Other(e.into()))?);
+        }
+        let size = img.size();
+        let len = (size.x * size.y) as usize;
+        clipboard
+            .as_mut()
+            .unwrap()
+            .set_image(arboard::ImageData {
+                width: size.x as usize,
+                height: size.y as usize,
+                bytes: bytemuck::cast_slice(&img.as_ref().as_buf()[..len]).into(),
+            })
+            .map_err(|e| Error::Other(e.into()))
+    }
+
+    #[cfg(all(
+        not(target_arch = "wasm32"),
+        any(not(feature = "clipboard"), target_os = "android", target_os = "ios")
+    ))]
+    {
+        _ = img;
+        Err(Error::NotSupported)
+    }
+}
+
+/// Shares an image with the given title, see the [module documentation](self) for how this works on each platform.
+pub async fn share(img: &Img<Vec<Color>>, title: &str) -> Result<(), Error> {
+    let bytes = crate::image::encode_png(img)?;
+    let filename = format!(
+        "screenshot-{}.png",
+        crate::time::SystemTime::now()
+            .duration_since(crate::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+    );
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        let shared = crate::web::share_file(&filename, &bytes, "image/png", title)
+            .await
+            .map_err(|e| Error::Other(format!("{e:?}").into()))?;
+        if !shared {
+            crate::web::download(&filename, &bytes, "image/png")
+                .map_err(|e| Error::Other(format!("{e:?}").into()))?;
+        }
+        Ok(())
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        _ = title;
+        let dir = dirs::picture_dir()
+            .or_else(dirs::data_dir)
+            .ok_or(Error::NotSupported)?;
+        let path = dir.join(filename);
+        std::fs::write(&path, bytes).map_err(|e| Error::Other(e.into()))?;
+        log::info!("saved screenshot to {}", path.display());
+        Ok(())
+    }
+}
+
+/// Number of captures whose readback hasn't finished yet.
+static PENDING: AtomicUsize = AtomicUsize::new(0);
+
+/// Renders a canvas to an offscreen texture of the given format and reads it back.
+pub(crate) fn capture(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    canvasette_renderer: &mut canvasette::Renderer,
+    canvas: &crate::graphics::Canvas,
+    size: wgpu::Extent3d,
+    format: wgpu::TextureFormat,
+) -> impl Future<Output = Option<Img<Vec<Color>>>> {
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("teenygame: Screenshot"),
+        size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    crate::graphics::render_to_texture(
+        device,
+        queue,
+        canvasette_renderer,
+        canvas,
+        size,
+        &texture.create_view(&wgpu::TextureViewDescriptor::default()),
+    );
+
+    // Mapping finishes on a later frame's poll on native, and on its own in the browser.
+    let readback = crate::graphics::read_back(device, queue, &texture);
+    PENDING.fetch_add(1, Ordering::Relaxed);
+    async move {
+        let result = readback.await;
+        PENDING.fetch_sub(1, Ordering::Relaxed);
+        let img = match result {
+            Ok(img) => img,
+            Err(e) => {
+                log::warn!("failed to read back screenshot: {e}");
+                return None;
+            }
+        };
+        // The frame is opaque, even where its alpha says otherwise.
+        Some(Img::from_fn(img.size(), |pos| {
+            let c = img.get_pixel(pos).unwrap();
+            Color::new(c.r, c.g, c.b, 0xff)
+        }))
+    }
+}
+
+/// Checks whether captures have finished reading back, without blocking.
+///
+/// This is called every frame, such that readbacks finish while the game keeps running.
+pub(crate) fn poll(device: &wgpu::Device) {
+    if PENDING.load(Ordering::Relaxed) > 0 {
+        device.poll(wgpu::Maintain::Poll);
+    }
+}
+
+/// Captures a frame and runs the requested actions on it in the background.
+pub(crate) fn run(
+    capture: impl Future<Output = Option<Img<Vec<Color>>>> + crate::marker::WasmNotSend + 'static,
+    actions: Vec<Action>,
+) {
+    crate::futures::spawn(async move {
+        let Some(img) = capture.await else {
+            return;
+        };
+        for action in actions {
+            action.run(&img).await;
+        }
+    });
+}
@@ -0,0 +1,357 @@
+//! Scripting support, powered by [Rhai](https://rhai.rs).
+//!
+//! A [`Script`] is a Rhai source file whose functions may be called from the game, e.g. to drive enemy patterns or cutscenes. Scripts can query input, and queue up sprites to draw and sounds to play, which are then drawn and played by the game by name:
+//!
+//! ```rhai
+//! fn update() {
+//!     if key_held("ArrowLeft") { this.x -= 2.0; }
+//!     if key_pressed("Space") { play_sound("jump"); }
+//! }
+//!
+//! fn draw() {
+//!     draw("player", this.x, 100.0);
+//! }
+//! ```
+//!
+//! ```ignore
+//! // In Game::update:
+//! self.script.poll_reload()?;
+//! self.script.call(ctxt.input, "update", ())?;
+//! self.script.play_sounds(ctxt.audio, |name| self.sounds.get(name));
+//!
+//! // In Game::draw:
+//! self.script.call(ctxt.input, "draw", ())?;
+//! self.script.draw(canvas, |name| self.sprites.get(name).copied());
+//! ```
+//!
+//! Script state is kept in `this`, an object map that persists across calls and reloads.
+
+pub use rhai;
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use rhai::{CallFnOptions, Dynamic, Engine, FuncArgs, Scope, AST};
+use serde::Deserialize as _;
+
+#[cfg(feature = "audio")]
+use crate::audio::{Audio, Sound};
+use crate::graphics::{Canvas, TextureSlice};
+use crate::input::{InputState, KeyCode, MouseButton};
+use crate::math::{Affine2, Vec2};
+
+/// Errors that can occur while running scripts.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    /// The script could not be read.
+    #[error("file: {0}")]
+    File(#[from] crate::file::Error),
+
+    /// The script could not be parsed.
+    #[error("parse: {0}")]
+    Parse(#[from] rhai::ParseError),
+
+    /// The script failed while running.
+    #[error("runtime: {0}")]
+    Runtime(#[from] Box<rhai::EvalAltResult>),
+}
+
+/// A sprite queued to be drawn by a script.
+#[derive(Clone, Debug)]
+pub struct ScriptDraw {
+    /// Name of the sprite.
+    pub sprite: String,
+
+    /// Transform to draw the sprite with.
+    pub transform: Affine2,
+}
+
+/// Input state visible to scripts, captured before each call.
+#[derive(Default)]
+struct ScriptInput {
+    held_keys: HashSet<KeyCode>,
+    pressed_keys: HashSet<KeyCode>,
+    released_keys: HashSet<KeyCode>,
+    held_buttons: HashSet<MouseButton>,
+    pressed_buttons: HashSet<MouseButton>,
+    mouse_position: Option<Vec2>,
+}
+
+impl ScriptInput {
+    fn capture(input: &InputState) -> Self {
+        const BUTTONS: [MouseButton; 3] =
+            [MouseButton::Left, MouseButton::Right, MouseButton::Middle];
+        Self {
+            held_keys: input.keyboard.held_keys().collect(),
+            pressed_keys: input.keyboard.pressed_keys().collect(),
+            released_keys: input.keyboard.released_keys().collect(),
+            held_buttons: BUTTONS
+                .into_iter()
+                .filter(|b| input.mouse.is_button_held(*b))
+                .collect(),
+            pressed_buttons: BUTTONS
+                .into_iter()
+                .filter(|b| input.mouse.is_button_pressed(*b))
+                .collect(),
+            mouse_position: input.mouse.position(),
+        }
+    }
+}
+
+/// Output queued by scripts, to be flushed by the game.
+#[derive(Default)]
+struct ScriptOutput {
+    draws: Vec<ScriptDraw>,
+    sounds: Vec<String>,
+}
+
+fn parse_key(name: &str) -> Option<KeyCode> {
+    KeyCode::deserialize(serde::de::value::StrDeserializer::<serde::de::value::Error>::new(name))
+        .ok()
+}
+
+fn parse_button(name: &str) -> Option<MouseButton> {
+    MouseButton::deserialize(
+        serde::de::value::StrDeserializer::<serde::de::value::Error>::new(name),
+    )
+    .ok()
+}
+
+fn create_engine(input: &Rc<RefCell<ScriptInput>>, output: &Rc<RefCell<ScriptOutput>>) -> Engine {
+    let mut engine = Engine::new();
+
+    engine.on_print(|s| log::info!("{s}"));
+    engine.on_debug(|s, _, pos| log::debug!("{pos:?}: {s}"));
+
+    let i = input.clone();
+    engine.register_fn("key_held", move |name: &str| {
+        parse_key(name).is_some_and(|key| i.borrow().held_keys.contains(&key))
+    });
+    let i = input.clone();
+    engine.register_fn("key_pressed", move |name: &str| {
+        parse_key(name).is_some_and(|key| i.borrow().pressed_keys.contains(&key))
+    });
+    let i = input.clone();
+    engine.register_fn("key_released", move |name: &str| {
+        parse_key(name).is_some_and(|key| i.borrow().released_keys.contains(&key))
+    });
+    let i = input.clone();
+    engine.register_fn("mouse_held", move |name: &str| {
+        parse_button(name).is_some_and(|button| i.borrow().held_buttons.contains(&button))
+    });
+    let i = input.clone();
+    engine.register_fn("mouse_pressed", move |name: &str| {
+        parse_button(name).is_some_and(|button| i.borrow().pressed_buttons.contains(&button))
+    });
+    let i = input.clone();
+    engine.register_fn("mouse_x", move || {
+        i.borrow()
+            .mouse_position
+            .map_or(0.0, |pos| pos.x as rhai::FLOAT)
+    });
+    let i = input.clone();
+    engine.register_fn("mouse_y", move || {
+        i.borrow()
+            .mouse_position
+            .map_or(0.0, |pos| pos.y as rhai::FLOAT)
+    });
+
+    let o = output.clone();
+    engine.register_fn(
+        "draw",
+        move |sprite: &str, x: rhai::FLOAT, y: rhai::FLOAT| {
+            o.borrow_mut().draws.push(ScriptDraw {
+                sprite: sprite.to_string(),
+                transform: Affine2::from_translation(Vec2::new(x as f32, y as f32)),
+            });
+        },
+    );
+    let o = output.clone();
+    engine.register_fn(
+        "draw",
+        move |sprite: &str,
+              x: rhai::FLOAT,
+              y: rhai::FLOAT,
+              rotation: rhai::FLOAT,
+              scale: rhai::FLOAT| {
+            o.borrow_mut().draws.push(ScriptDraw {
+                sprite: sprite.to_string(),
+                transform: Affine2::from_scale_angle_translation(
+                    Vec2::splat(scale as f32),
+                    rotation as f32,
+                    Vec2::new(x as f32, y as f32),
+                ),
+            });
+        },
+    );
+    let o = output.clone();
+    engine.register_fn("play_sound", move |name: &str| {
+        o.borrow_mut().sounds.push(name.to_string());
+    });
+
+    engine
+}
+
+/// A loaded script.
+pub struct Script {
+    engine: Engine,
+    ast: AST,
+    scope: Scope<'static>,
+    state: Dynamic,
+    input: Rc<RefCell<ScriptInput>>,
+    output: Rc<RefCell<ScriptOutput>>,
+    path: Option<String>,
+    #[cfg(not(target_arch = "wasm32"))]
+    modified: Option<std::time::SystemTime>,
+}
+
+impl Script {
+    /// Compiles a script from source and runs its top-level statements.
+    pub fn new(source: &str) -> Result<Self, Error> {
+        let input = Rc::new(RefCell::new(ScriptInput::default()));
+        let output = Rc::new(RefCell::new(ScriptOutput::default()));
+        let engine = create_engine(&input, &output);
+        let ast = engine.compile(source)?;
+        let mut script = Self {
+            engine,
+            ast,
+            scope: Scope::new(),
+            state: Dynamic::from_map(Default::default()),
+            input,
+            output,
+            path: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            modified: None,
+        };
+        script
+            .engine
+            .run_ast_with_scope(&mut script.scope, &script.ast)?;
+        Ok(script)
+    }
+
+    /// Loads a script from the given path.
+    ///
+    /// On native platforms, the script may be reloaded when the file changes with [`Script::poll_reload`].
+    pub async fn load(path: &str) -> Result<Self, Error> {
+        let source = crate::file::read(path).await?;
+        let mut script = Self::new(&String::from_utf8_lossy(&source))?;
+        script.path = Some(path.to_string());
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            script.modified = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+        }
+        Ok(script)
+    }
+
+    /// Replaces the script's code with new source, keeping `this`.
+    ///
+    /// If the new source fails to compile, the old code is kept.
+    pub fn reload(&mut self, source: &str) -> Result<(), Error> {
+        let ast = self.engine.compile(source)?;
+        self.ast = ast;
+        self.scope.clear();
+        self.engine.run_ast_with_scope(&mut self.scope, &self.ast)?;
+        Ok(())
+    }
+
+    /// Reloads the script if it was loaded with [`Script::load`] and the file has changed since, returning if it was reloaded.
+    ///
+    /// This is intended to be called every tick during development. On WASM, scripts are never reloaded.
+    pub fn poll_reload(&mut self) -> Result<bool, Error> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let Some(path) = self.path.clone() else {
+                return Ok(false);
+            };
+            let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+            if modified.is_none() || modified == self.modified {
+                return Ok(false);
+            }
+            self.modified = modified;
+
+            let source = std::fs::read_to_string(&path)
+                .map_err(|e| Error::File(crate::file::Error::Other(e.into())))?;
+            self.reload(&source)?;
+            log::info!("reloaded script {path}");
+            Ok(true)
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            Ok(false)
+        }
+    }
+
+    /// Gets the script state, `this`.
+    pub fn state(&self) -> &Dynamic {
+        &self.state
+    }
+
+    /// Gets the script state, `this`, mutably.
+    pub fn state_mut(&mut self) -> &mut Dynamic {
+        &mut self.state
+    }
+
+    /// Calls a function defined by the script, returning its result.
+    ///
+    /// The result may be converted with e.g. [`Dynamic::try_cast`].
+    pub fn call(
+        &mut self,
+        input: &InputState,
+        name: &str,
+        args: impl FuncArgs,
+    ) -> Result<Dynamic, Error> {
+        *self.input.borrow_mut() = ScriptInput::capture(input);
+        Ok(self.engine.call_fn_with_options(
+            CallFnOptions::new()
+                .eval_ast(false)
+                .bind_this_ptr(&mut self.state),
+            &mut self.scope,
+            &self.ast,
+            name,
+            args,
+        )?)
+    }
+
+    /// Takes all sprites queued to be drawn by the script.
+    pub fn take_draws(&mut self) -> Vec<ScriptDraw> {
+        std::mem::take(&mut self.output.borrow_mut().draws)
+    }
+
+    /// Takes the names of all sounds queued to be played by the script.
+    pub fn take_sounds(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.output.borrow_mut().sounds)
+    }
+
+    /// Draws all sprites queued by the script, looking up sprites by name.
+    pub fn draw<'a>(
+        &mut self,
+        canvas: &mut Canvas<'a>,
+        mut sprite: impl FnMut(&str) -> Option<TextureSlice<'a>>,
+    ) {
+        for draw in self.take_draws() {
+            let Some(slice) = sprite(&draw.sprite) else {
+                log::warn!("script drew unknown sprite {}", draw.sprite);
+                continue;
+            };
+            canvas.draw(slice, draw.transform);
+        }
+    }
+
+    /// Plays all sounds queued by the script, looking up sounds by name.
+    #[cfg(feature = "audio")]
+    pub fn play_sounds<'s>(
+        &mut self,
+        audio: &mut Audio,
+        mut sound: impl FnMut(&str) -> Option<&'s Sound>,
+    ) {
+        for name in self.take_sounds() {
+            let Some(sound) = sound(&name) else {
+                log::warn!("script played unknown sound {name}");
+                continue;
+            };
+            audio.play(sound).detach();
+        }
+    }
+}
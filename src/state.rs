@@ -0,0 +1,76 @@
+//! Game state snapshots.
+//!
+//! [`crate::Game::save_state`] and [`crate::Game::load_state`] may be implemented for any [`serde`] serializable game with the [`crate::serde_state`] macro:
+//!
+//! ```ignore
+//! #[derive(serde::Serialize, serde::Deserialize)]
+//! struct World { /* ... */ }
+//!
+//! impl teenygame::Game for Game {
+//!     // Only save the fields that make up the game state, skipping e.g. textures.
+//!     teenygame::serde_state!(world, score);
+//!
+//!     // ...
+//! }
+//! ```
+
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Serializes a value into a compact binary snapshot.
+///
+/// Returns an empty snapshot if the value could not be serialized.
+pub fn to_bytes<T>(value: &T) -> Vec<u8>
+where
+    T: Serialize + ?Sized,
+{
+    postcard::to_allocvec(value).unwrap_or_else(|e| {
+        log::error!("failed to save state: {e}");
+        vec![]
+    })
+}
+
+/// Deserializes a value from a snapshot created by [`to_bytes`].
+///
+/// Returns [`None`] if the snapshot is empty or could not be deserialized, e.g. because it was created by an older version of the game.
+pub fn from_bytes<T>(bytes: &[u8]) -> Option<T>
+where
+    T: DeserializeOwned,
+{
+    if bytes.is_empty() {
+        return None;
+    }
+    postcard::from_bytes(bytes)
+        .inspect_err(|e| log::error!("failed to load state: {e}"))
+        .ok()
+}
+
+/// Implements [`crate::Game::save_state`] and [`crate::Game::load_state`] using [`serde`].
+///
+/// - `serde_state!()` saves the entire game, which must implement [`serde::Serialize`] and [`serde::Deserialize`].
+/// - `serde_state!(a, b, ...)` saves only the given fields.
+#[macro_export]
+macro_rules! serde_state {
+    () => {
+        fn save_state(&self) -> Vec<u8> {
+            $crate::state::to_bytes(self)
+        }
+
+        fn load_state(&mut self, state: &[u8]) {
+            if let Some(loaded) = $crate::state::from_bytes(state) {
+                *self = loaded;
+            }
+        }
+    };
+
+    ($($field:ident),+ $(,)?) => {
+        fn save_state(&self) -> Vec<u8> {
+            $crate::state::to_bytes(&($(&self.$field,)+))
+        }
+
+        fn load_state(&mut self, state: &[u8]) {
+            if let Some(($($field,)+)) = $crate::state::from_bytes(state) {
+                $(self.$field = $field;)+
+            }
+        }
+    };
+}
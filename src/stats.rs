@@ -0,0 +1,86 @@
+//! Resource memory accounting.
+//!
+//! Memory used by textures, framebuffers and decoded audio is tracked as resources are created and dropped, and is available via [`stats`] or [`crate::Context::stats`]. This helps diagnose running out of memory on web and mobile, where limits are tight.
+//!
+//! Budgets may be set with [`set_texture_budget`] and [`set_audio_budget`], in which case a warning is logged whenever usage goes over budget.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Memory used by loaded resources.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct Stats {
+    /// Estimated GPU memory used by textures and framebuffers, in bytes.
+    pub texture_bytes: u64,
+
+    /// Memory used by decoded audio, in bytes.
+    #[cfg(feature = "audio")]
+    pub audio_bytes: u64,
+}
+
+pub(crate) struct Counter {
+    name: &'static str,
+    used: AtomicU64,
+
+    /// The budget in bytes, or 0 if there is none.
+    budget: AtomicU64,
+}
+
+impl Counter {
+    const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            used: AtomicU64::new(0),
+            budget: AtomicU64::new(0),
+        }
+    }
+
+    pub(crate) fn add(&self, bytes: u64) {
+        let used = self.used.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        let budget = self.budget.load(Ordering::Relaxed);
+        if budget != 0 && used > budget && used - bytes <= budget {
+            log::warn!(
+                "{} memory is over budget: {} bytes used, {} bytes budgeted",
+                self.name,
+                used,
+                budget
+            );
+        }
+    }
+
+    pub(crate) fn sub(&self, bytes: u64) {
+        self.used.fetch_sub(bytes, Ordering::Relaxed);
+    }
+
+    fn get(&self) -> u64 {
+        self.used.load(Ordering::Relaxed)
+    }
+
+    fn set_budget(&self, budget: Option<u64>) {
+        self.budget.store(budget.unwrap_or(0), Ordering::Relaxed);
+    }
+}
+
+pub(crate) static TEXTURES: Counter = Counter::new("texture");
+
+#[cfg(feature = "audio")]
+pub(crate) static AUDIO: Counter = Counter::new("audio");
+
+/// Gets the memory currently used by loaded resources.
+pub fn stats() -> Stats {
+    Stats {
+        texture_bytes: TEXTURES.get(),
+        #[cfg(feature = "audio")]
+        audio_bytes: AUDIO.get(),
+    }
+}
+
+/// Sets the texture memory budget in bytes, or removes it if `None`.
+pub fn set_texture_budget(budget: Option<u64>) {
+    TEXTURES.set_budget(budget);
+}
+
+/// Sets the decoded audio memory budget in bytes, or removes it if `None`.
+#[cfg(feature = "audio")]
+pub fn set_audio_budget(budget: Option<u64>) {
+    AUDIO.set_budget(budget);
+}
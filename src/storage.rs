@@ -0,0 +1,52 @@
+//! Persistent key-value storage, e.g. for save data and settings.
+//!
+//! - On native platforms, each key is stored as a file in a per-game directory in the user's data directory.
+//! - On WASM, values are stored in the browser's local storage, namespaced per game with [`crate::Game::WEB_STORAGE_NAMESPACE`].
+//!
+//! Keys should only contain ASCII letters, digits, `-`, `_` and `.`.
+
+#[cfg(not(target_arch = "wasm32"))]
+mod native;
+
+#[cfg(not(target_arch = "wasm32"))]
+use native as imp;
+
+/// Gets the per-game directory in the user's data directory.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use native::dir as data_dir;
+
+#[cfg(target_arch = "wasm32")]
+mod web;
+
+#[cfg(target_arch = "wasm32")]
+use web as imp;
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) use web::set_namespace;
+
+/// Errors that can occur while accessing storage.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    /// Storage is not available on this platform, or was denied by the user.
+    #[error("unavailable")]
+    Unavailable,
+
+    /// An underlying error occurred.
+    #[error("other: {0}")]
+    Other(Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// Reads the value stored for a key, if any.
+pub fn get(key: &str) -> Result<Option<Vec<u8>>, Error> {
+    imp::get(key)
+}
+
+/// Stores a value for a key, replacing any existing value.
+pub fn set(key: &str, value: &[u8]) -> Result<(), Error> {
+    imp::set(key, value)
+}
+
+/// Removes the value stored for a key, if any.
+pub fn remove(key: &str) -> Result<(), Error> {
+    imp::remove(key)
+}
@@ -0,0 +1,37 @@
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use super::Error;
+
+pub(crate) fn dir() -> Result<PathBuf, Error> {
+    let exe = std::env::current_exe().map_err(|e| Error::Other(e.into()))?;
+    let name = exe.file_stem().ok_or(Error::Unavailable)?;
+    Ok(dirs::data_dir().ok_or(Error::Unavailable)?.join(name))
+}
+
+pub fn get(key: &str) -> Result<Option<Vec<u8>>, Error> {
+    match std::fs::read(dir()?.join(key)) {
+        Ok(value) => Ok(Some(value)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(Error::Other(e.into())),
+    }
+}
+
+pub fn set(key: &str, value: &[u8]) -> Result<(), Error> {
+    let dir = dir()?;
+    std::fs::create_dir_all(&dir).map_err(|e| Error::Other(e.into()))?;
+
+    // Write to a temporary file first, such that the value is never left half-written.
+    let tmp_path = dir.join(format!("{key}.tmp"));
+    std::fs::write(&tmp_path, value).map_err(|e| Error::Other(e.into()))?;
+    std::fs::rename(&tmp_path, dir.join(key)).map_err(|e| Error::Other(e.into()))?;
+    Ok(())
+}
+
+pub fn remove(key: &str) -> Result<(), Error> {
+    match std::fs::remove_file(dir()?.join(key)) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(Error::Other(e.into())),
+    }
+}
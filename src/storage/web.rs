@@ -0,0 +1,72 @@
+use std::sync::OnceLock;
+
+use super::Error;
+
+/// Namespace of the game's items, such that games hosted on the same site don't overwrite each other's.
+static NAMESPACE: OnceLock<String> = OnceLock::new();
+
+pub(crate) fn set_namespace(namespace: Option<&str>) {
+    let namespace = namespace.map(str::to_string).unwrap_or_else(|| {
+        // Default to the directory of the page, which differs between games on the same site.
+        let path = web_sys::window()
+            .and_then(|window| window.location().pathname().ok())
+            .unwrap_or_default();
+        path[..path.rfind('/').map_or(0, |i| i + 1)].to_string()
+    });
+    _ = NAMESPACE.set(namespace);
+}
+
+fn local_storage() -> Result<web_sys::Storage, Error> {
+    web_sys::window()
+        .ok_or(Error::Unavailable)?
+        .local_storage()
+        .map_err(|_| Error::Unavailable)?
+        .ok_or(Error::Unavailable)
+}
+
+fn item_name(key: &str) -> String {
+    let namespace = NAMESPACE.get().map_or("", String::as_str);
+    format!("teenygame.{namespace}.{key}")
+}
+
+fn js_error(e: wasm_bindgen::JsValue) -> Error {
+    Error::Other(format!("{e:?}").into())
+}
+
+// Local storage only holds strings, so values are stored hex encoded.
+
+fn encode(value: &[u8]) -> String {
+    value.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn decode(value: &str) -> Result<Vec<u8>, Error> {
+    (0..value.len())
+        .step_by(2)
+        .map(|i| {
+            value
+                .get(i..i + 2)
+                .and_then(|b| u8::from_str_radix(b, 16).ok())
+                .ok_or_else(|| Error::Other("malformed value".into()))
+        })
+        .collect()
+}
+
+pub fn get(key: &str) -> Result<Option<Vec<u8>>, Error> {
+    local_storage()?
+        .get_item(&item_name(key))
+        .map_err(js_error)?
+        .map(|value| decode(&value))
+        .transpose()
+}
+
+pub fn set(key: &str, value: &[u8]) -> Result<(), Error> {
+    local_storage()?
+        .set_item(&item_name(key), &encode(value))
+        .map_err(js_error)
+}
+
+pub fn remove(key: &str) -> Result<(), Error> {
+    local_storage()?
+        .remove_item(&item_name(key))
+        .map_err(js_error)
+}
@@ -0,0 +1,199 @@
+//! Streaming large worlds in chunks.
+//!
+//! A [`Streamer`] divides the world into a grid of chunks, such as pieces of a tilemap together with the textures and sounds they use, and keeps only the chunks around the camera loaded. Chunks are loaded in the background, and unloaded once they are far enough away:
+//!
+//! ```ignore
+//! let mut streamer = Streamer::new(Vec2::splat(512.0), 256.0, 512.0);
+//!
+//! // In update:
+//! let unloaded = streamer.update(self.camera.visible_rect(), |coords| {
+//!     futures::spawn_with_handle(async move { load_chunk(coords).await })
+//! });
+//! for (coords, chunk) in unloaded {
+//!     save_chunk(coords, chunk);
+//! }
+//!
+//! // In draw:
+//! for (coords, chunk) in streamer.loaded() {
+//!     chunk.draw(canvas, streamer.chunk_rect(coords));
+//! }
+//! ```
+//!
+//! Chunks start loading a margin before they become visible, and are only unloaded a larger margin after they stop being visible, such that moving back and forth along a chunk edge doesn't load and unload the same chunks over and over.
+
+use std::collections::HashMap;
+
+use crate::futures::JoinHandle;
+use crate::math::{IVec2, Rect, Vec2};
+
+/// Number of updates to wait before retrying a chunk that failed to load, doubled after each further failure.
+const RETRY_DELAY: u64 = 60;
+
+/// Maximum number of times [`RETRY_DELAY`] is doubled.
+const MAX_RETRY_DOUBLINGS: u32 = 6;
+
+enum Chunk<T> {
+    /// Loading, after the given number of failed attempts.
+    Loading(JoinHandle<T>, u32),
+    Loaded(T),
+    /// Failed to load the given number of times, to be retried at the given update.
+    Failed(u32, u64),
+}
+
+/// Loads and unloads chunks of a world around the visible area.
+pub struct Streamer<T> {
+    chunk_size: Vec2,
+    load_margin: f32,
+    unload_margin: f32,
+    chunks: HashMap<IVec2, Chunk<T>>,
+    updates: u64,
+}
+
+impl<T> Streamer<T> {
+    /// Creates a streamer for chunks of the given size in world units.
+    ///
+    /// Chunks within `load_margin` of the visible area are loaded, and chunks further than `unload_margin` from it are unloaded. The unload margin is raised to at least the load margin.
+    pub fn new(chunk_size: Vec2, load_margin: f32, unload_margin: f32) -> Self {
+        Self {
+            chunk_size,
+            load_margin,
+            unload_margin: unload_margin.max(load_margin),
+            chunks: HashMap::new(),
+            updates: 0,
+        }
+    }
+
+    /// Gets the size of chunks in world units.
+    pub fn chunk_size(&self) -> Vec2 {
+        self.chunk_size
+    }
+
+    /// Gets the coordinates of the chunk containing a point.
+    pub fn chunk_at(&self, pos: Vec2) -> IVec2 {
+        (pos / self.chunk_size).floor().as_ivec2()
+    }
+
+    /// Gets the area a chunk covers in world units.
+    pub fn chunk_rect(&self, coords: IVec2) -> Rect {
+        Rect::from_pos_size(coords.as_vec2() * self.chunk_size, self.chunk_size)
+    }
+
+    /// Gets the coordinates of all chunks overlapping an area.
+    fn chunks_in(&self, area: Rect) -> impl Iterator<Item = IVec2> {
+        let min = self.chunk_at(area.min);
+        let max = self.chunk_at(area.max);
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| IVec2::new(x, y)))
+    }
+
+    /// Starts loading chunks near the visible area and unloads chunks far from it, returning the unloaded chunks.
+    ///
+    /// `load` is called for each chunk that should start loading, and usually spawns its loading via [`crate::futures::spawn_with_handle`]. Chunks that finished loading become available via [`Streamer::get`]. Chunks whose loading failed are retried after a delay, which grows with each further failure.
+    pub fn update(
+        &mut self,
+        visible: Rect,
+        mut load: impl FnMut(IVec2) -> JoinHandle<T>,
+    ) -> Vec<(IVec2, T)> {
+        self.updates += 1;
+
+        // Collect finished loads.
+        for (coords, chunk) in self.chunks.iter_mut() {
+            let Chunk::Loading(handle, failures) = chunk else {
+                continue;
+            };
+            match handle.try_take() {
+                Ok(Some(value)) => *chunk = Chunk::Loaded(value),
+                Ok(None) => {}
+                Err(e) => {
+                    log::warn!("failed to load chunk {coords}: {e}");
+                    let delay = RETRY_DELAY << (*failures).min(MAX_RETRY_DOUBLINGS);
+                    *chunk = Chunk::Failed(*failures + 1, self.updates + delay);
+                }
+            }
+        }
+
+        // Unload far chunks. Chunks still loading are dropped, discarding their results.
+        let keep = visible.inflate(self.unload_margin);
+        let far = self
+            .chunks
+            .keys()
+            .copied()
+            .filter(|coords| !self.chunk_rect(*coords).intersects(&keep))
+            .collect::<Vec<_>>();
+        let mut unloaded = vec![];
+        for coords in far {
+            if let Some(Chunk::Loaded(value)) = self.chunks.remove(&coords) {
+                unloaded.push((coords, value));
+            }
+        }
+
+        // Load near chunks.
+        for coords in self
+            .chunks_in(visible.inflate(self.load_margin))
+            .collect::<Vec<_>>()
+        {
+            // New chunks start out like failed chunks that are due to be retried.
+            let chunk = self
+                .chunks
+                .entry(coords)
+                .or_insert(Chunk::Failed(0, self.updates));
+            if let Chunk::Failed(failures, retry_at) = *chunk {
+                if retry_at <= self.updates {
+                    *chunk = Chunk::Loading(load(coords), failures);
+                }
+            }
+        }
+
+        unloaded
+    }
+
+    /// Gets a loaded chunk.
+    pub fn get(&self, coords: IVec2) -> Option<&T> {
+        match self.chunks.get(&coords) {
+            Some(Chunk::Loaded(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Gets a loaded chunk mutably.
+    pub fn get_mut(&mut self, coords: IVec2) -> Option<&mut T> {
+        match self.chunks.get_mut(&coords) {
+            Some(Chunk::Loaded(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Iterates over all loaded chunks, in no particular order.
+    pub fn loaded(&self) -> impl Iterator<Item = (IVec2, &T)> {
+        self.chunks
+            .iter()
+            .filter_map(|(coords, chunk)| match chunk {
+                Chunk::Loaded(value) => Some((*coords, value)),
+                Chunk::Loading(..) | Chunk::Failed(..) => None,
+            })
+    }
+
+    /// Gets the number of chunks that are still loading, e.g. to show a loading indicator.
+    pub fn pending(&self) -> usize {
+        self.chunks
+            .values()
+            .filter(|chunk| matches!(chunk, Chunk::Loading(..)))
+            .count()
+    }
+
+    /// Checks if every chunk overlapping an area has loaded, e.g. before letting the player move into it.
+    pub fn is_area_loaded(&self, area: Rect) -> bool {
+        self.chunks_in(area)
+            .all(|coords| matches!(self.chunks.get(&coords), Some(Chunk::Loaded(_))))
+    }
+
+    /// Unloads all chunks, returning those that had loaded.
+    pub fn clear(&mut self) -> Vec<(IVec2, T)> {
+        self.chunks
+            .drain()
+            .filter_map(|(coords, chunk)| match chunk {
+                Chunk::Loaded(value) => Some((coords, value)),
+                Chunk::Loading(..) | Chunk::Failed(..) => None,
+            })
+            .collect()
+    }
+}
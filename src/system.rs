@@ -0,0 +1,21 @@
+//! System preferences: the color theme and the user's languages.
+//!
+//! UIs may follow the system theme, and localized games may pick their initial language from the preferred locales:
+//!
+//! ```ignore
+//! let language = system::locales()
+//!     .iter()
+//!     .find_map(|locale| SUPPORTED.iter().find(|l| locale.starts_with(*l)))
+//!     .unwrap_or(&"en");
+//! ```
+
+pub use winit::window::Theme;
+
+/// Gets the user's preferred locales as BCP 47 language tags (e.g. `en-US`), most preferred first.
+///
+/// Returns an empty list if the platform doesn't provide them.
+///
+/// Unlike the theme, changes are not reported to the game. The locales are queried again on every call, so a language menu can offer the current ones, but on some platforms they only change after a restart.
+pub fn locales() -> Vec<String> {
+    sys_locale::get_locales().collect()
+}
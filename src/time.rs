@@ -2,7 +2,208 @@
 //!
 //! Reexports either `std::time` or `web_time`, depending on platform.
 
+use std::collections::VecDeque;
+use std::time::Duration;
+
 #[cfg(not(target_arch = "wasm32"))]
 pub use std::time::{Instant, SystemTime, SystemTimeError, UNIX_EPOCH};
 #[cfg(target_arch = "wasm32")]
 pub use web_time::{Instant, SystemTime, SystemTimeError, UNIX_EPOCH};
+
+/// Number of frames kept for computing frame time statistics.
+const FRAME_HISTORY: usize = 120;
+
+/// Timing statistics for the game loop.
+pub struct Timing {
+    ticks: u64,
+    frames: u64,
+    frame_times: VecDeque<Duration>,
+    update_duration: Duration,
+    draw_duration: Duration,
+    alpha: f32,
+}
+
+impl Timing {
+    pub(crate) fn new() -> Self {
+        Self {
+            ticks: 0,
+            frames: 0,
+            frame_times: VecDeque::with_capacity(FRAME_HISTORY),
+            update_duration: Duration::ZERO,
+            draw_duration: Duration::ZERO,
+            alpha: 0.0,
+        }
+    }
+
+    /// Gets the number of ticks (calls to [`crate::Game::update`]) that have completed.
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    /// Gets the number of frames (calls to [`crate::Game::draw`]) that have completed.
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    /// Gets the time between the last two frames.
+    pub fn last_frame_time(&self) -> Duration {
+        self.frame_times.back().copied().unwrap_or(Duration::ZERO)
+    }
+
+    /// Gets the average time between frames over recent frames.
+    pub fn average_frame_time(&self) -> Duration {
+        if self.frame_times.is_empty() {
+            return Duration::ZERO;
+        }
+        self.frame_times.iter().sum::<Duration>() / self.frame_times.len() as u32
+    }
+
+    /// Gets the given percentile of the time between frames over recent frames, where `p` is between 0.0 and 1.0.
+    ///
+    /// For instance, `frame_time_percentile(0.99)` gets the time that 99% of recent frames were at least as fast as.
+    pub fn frame_time_percentile(&self, p: f32) -> Duration {
+        if self.frame_times.is_empty() {
+            return Duration::ZERO;
+        }
+        let mut sorted = self.frame_times.iter().copied().collect::<Vec<_>>();
+        sorted.sort_unstable();
+        let i = ((sorted.len() - 1) as f32 * p.clamp(0.0, 1.0)).round() as usize;
+        sorted[i]
+    }
+
+    /// Gets the frames per second, as computed from the average frame time.
+    pub fn fps(&self) -> f32 {
+        let average = self.average_frame_time();
+        if average.is_zero() {
+            return 0.0;
+        }
+        1.0 / average.as_secs_f32()
+    }
+
+    /// Gets how long all the calls to [`crate::Game::update`] took during the last frame.
+    pub fn update_duration(&self) -> Duration {
+        self.update_duration
+    }
+
+    /// Gets how long the call to [`crate::Game::draw`] took during the last frame.
+    pub fn draw_duration(&self) -> Duration {
+        self.draw_duration
+    }
+
+    /// Gets how far time has advanced from the last tick towards the next one when drawing, from 0 to 1.
+    ///
+    /// Drawing the state between the last two ticks by this amount makes movement smooth when the frame rate and tick rate differ. See [`crate::math::interpolate`].
+    pub fn alpha(&self) -> f32 {
+        self.alpha
+    }
+
+    pub(crate) fn record_frame_time(&mut self, frame_time: Duration) {
+        if self.frame_times.len() == FRAME_HISTORY {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(frame_time);
+    }
+
+    pub(crate) fn record_tick(&mut self) {
+        self.ticks += 1;
+    }
+
+    pub(crate) fn record_update_duration(&mut self, duration: Duration) {
+        self.update_duration = duration;
+    }
+
+    pub(crate) fn record_alpha(&mut self, alpha: f32) {
+        self.alpha = alpha;
+    }
+
+    pub(crate) fn record_draw_duration(&mut self, duration: Duration) {
+        self.draw_duration = duration;
+        self.frames += 1;
+    }
+}
+
+/// Controls for pausing, single-stepping and slowing down the tick loop, e.g. for debugging fast-moving gameplay.
+///
+/// Drawing continues while paused. With the `debug` feature enabled, [`crate::Game::PAUSE_KEY`] (F6 by default) toggles pausing, [`crate::Game::STEP_KEY`] (F7) advances a single tick while paused, and [`crate::Game::SLOW_MOTION_KEY`] (F8) toggles running at 10% speed.
+pub struct Stepping {
+    paused: bool,
+    pending_steps: u32,
+    time_scale: f32,
+    ticks_per_second: u32,
+}
+
+impl Stepping {
+    pub(crate) fn new(ticks_per_second: u32) -> Self {
+        Self {
+            paused: false,
+            pending_steps: 0,
+            time_scale: 1.0,
+            ticks_per_second: ticks_per_second.max(1),
+        }
+    }
+
+    /// Checks if the tick loop is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Pauses or resumes the tick loop.
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+        self.pending_steps = 0;
+    }
+
+    /// Advances exactly one tick on the next frame while paused.
+    pub fn step(&mut self) {
+        if self.paused {
+            self.pending_steps += 1;
+        }
+    }
+
+    /// Gets how fast ticks run relative to real time.
+    pub fn time_scale(&self) -> f32 {
+        self.time_scale
+    }
+
+    /// Sets how fast ticks run relative to real time, e.g. 0.1 for 10% speed.
+    ///
+    /// The tick interval passed to physics is unchanged, so slowed down ticks behave exactly like regular ones.
+    pub fn set_time_scale(&mut self, time_scale: f32) {
+        self.time_scale = time_scale.max(0.0);
+    }
+
+    /// Gets how many times [`crate::Game::update`] is called per second, initially [`crate::Game::TICKS_PER_SECOND`].
+    pub fn ticks_per_second(&self) -> u32 {
+        self.ticks_per_second
+    }
+
+    /// Sets how many times [`crate::Game::update`] is called per second, e.g. lower for menus or 1 for debugging.
+    ///
+    /// Unlike [`Stepping::set_time_scale`], this changes the length of a tick, including the time step passed to physics. Time accumulated towards the next tick is carried over as the same fraction of a tick.
+    pub fn set_ticks_per_second(&mut self, ticks_per_second: u32) {
+        self.ticks_per_second = ticks_per_second.max(1);
+    }
+
+    /// Gets the time between ticks at the current tick rate.
+    pub(crate) fn tick_interval(&self) -> Duration {
+        Duration::from_secs(1) / self.ticks_per_second
+    }
+
+    /// Scales real time elapsed since the last frame to time that ticks should be run for.
+    pub(crate) fn scale(&self, frame_time: Duration) -> Duration {
+        if self.paused {
+            Duration::ZERO
+        } else {
+            frame_time.mul_f32(self.time_scale)
+        }
+    }
+
+    /// Takes a pending single step, if any.
+    pub(crate) fn take_step(&mut self) -> bool {
+        if self.pending_steps == 0 {
+            return false;
+        }
+        self.pending_steps -= 1;
+        true
+    }
+}
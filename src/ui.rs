@@ -0,0 +1,245 @@
+//! User interface helpers.
+//!
+//! [`FocusNav`] lets menus be navigated without a mouse: given the on-screen rectangles of the focusable widgets, it moves focus between them directionally and reports when the focused widget is activated or the menu is cancelled.
+
+use crate::accessibility::Politeness;
+use crate::input::{InputState, KeyCode, MouseButton};
+use crate::math::{Rect, Vec2};
+
+/// A direction to move focus in.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Direction {
+    /// Towards the top of the screen.
+    Up,
+
+    /// Towards the bottom of the screen.
+    Down,
+
+    /// Towards the left of the screen.
+    Left,
+
+    /// Towards the right of the screen.
+    Right,
+}
+
+impl Direction {
+    fn to_vec2(self) -> Vec2 {
+        match self {
+            Direction::Up => Vec2::NEG_Y,
+            Direction::Down => Vec2::Y,
+            Direction::Left => Vec2::NEG_X,
+            Direction::Right => Vec2::X,
+        }
+    }
+}
+
+/// Keys used for focus navigation.
+#[derive(Clone, Debug)]
+pub struct NavBindings {
+    /// Keys that move focus up.
+    pub up: Vec<KeyCode>,
+
+    /// Keys that move focus down.
+    pub down: Vec<KeyCode>,
+
+    /// Keys that move focus left.
+    pub left: Vec<KeyCode>,
+
+    /// Keys that move focus right.
+    pub right: Vec<KeyCode>,
+
+    /// Keys that activate the focused widget.
+    pub activate: Vec<KeyCode>,
+
+    /// Keys that cancel (e.g. go back from a menu).
+    pub cancel: Vec<KeyCode>,
+}
+
+impl Default for NavBindings {
+    fn default() -> Self {
+        Self {
+            up: vec![KeyCode::ArrowUp, KeyCode::KeyW],
+            down: vec![KeyCode::ArrowDown, KeyCode::KeyS],
+            left: vec![KeyCode::ArrowLeft, KeyCode::KeyA],
+            right: vec![KeyCode::ArrowRight, KeyCode::KeyD],
+            activate: vec![KeyCode::Enter, KeyCode::Space, KeyCode::KeyZ],
+            cancel: vec![KeyCode::Escape, KeyCode::Backspace, KeyCode::KeyX],
+        }
+    }
+}
+
+/// Something that happened during focus navigation.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NavEvent<Id> {
+    /// Focus moved to the given widget.
+    FocusChanged(Id),
+
+    /// The given widget was activated.
+    Activated(Id),
+
+    /// Cancel was pressed.
+    Cancelled,
+}
+
+impl<Id> NavEvent<Id>
+where
+    Id: Copy,
+{
+    /// Announces the event to screen readers, given a function that gets the label of a widget.
+    ///
+    /// Focus changes interrupt earlier announcements, such that quickly moving through a menu only reads out where focus ends up.
+    pub fn announce(&self, label: impl FnOnce(Id) -> String) {
+        if let NavEvent::FocusChanged(id) = self {
+            crate::accessibility::announce_with(&label(*id), Politeness::Assertive);
+        }
+    }
+}
+
+/// Tracks which widget is focused and moves focus in response to input.
+///
+/// Widgets are identified by any copyable ID, e.g. an enum of menu items.
+pub struct FocusNav<Id> {
+    focused: Option<Id>,
+
+    /// Mouse position during the last update, such that hovering only moves focus when the mouse moved.
+    last_mouse_pos: Option<Vec2>,
+
+    /// The keys used for navigation.
+    pub bindings: NavBindings,
+
+    /// Whether hovering and clicking the mouse also moves focus and activates.
+    pub mouse: bool,
+}
+
+impl<Id> FocusNav<Id>
+where
+    Id: Copy + PartialEq,
+{
+    /// Creates a new focus navigator with the default bindings, with nothing focused.
+    pub fn new() -> Self {
+        Self {
+            focused: None,
+            last_mouse_pos: None,
+            bindings: NavBindings::default(),
+            mouse: true,
+        }
+    }
+
+    /// Gets the focused widget.
+    pub fn focused(&self) -> Option<Id> {
+        self.focused
+    }
+
+    /// Checks if the given widget is focused.
+    pub fn is_focused(&self, id: Id) -> bool {
+        self.focused == Some(id)
+    }
+
+    /// Focuses the given widget.
+    pub fn set_focused(&mut self, id: Option<Id>) {
+        self.focused = id;
+    }
+
+    /// Moves focus in the given direction to the nearest widget, returning the newly focused widget if focus changed.
+    ///
+    /// If nothing is focused, the first widget is focused.
+    pub fn navigate(&mut self, widgets: &[(Id, Rect)], direction: Direction) -> Option<Id> {
+        let Some(current) = self
+            .focused
+            .and_then(|id| widgets.iter().find(|(other, _)| *other == id))
+        else {
+            self.focused = widgets.first().map(|(id, _)| *id);
+            return self.focused;
+        };
+
+        let origin = current.1.center();
+        let dir = direction.to_vec2();
+
+        let next = widgets
+            .iter()
+            .filter(|(id, _)| *id != current.0)
+            .filter_map(|(id, rect)| {
+                let delta = rect.center() - origin;
+                let along = delta.dot(dir);
+                if along <= 0.0 {
+                    return None;
+                }
+                // Prefer widgets that are directly in line over those that are closer but off to the side.
+                let across = delta.perp_dot(dir).abs();
+                Some((*id, along + across * 2.0))
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(id, _)| id)?;
+
+        self.focused = Some(next);
+        Some(next)
+    }
+
+    /// Updates focus from input, returning what happened, if anything.
+    ///
+    /// This should be called once per [`crate::Game::update`] with the rectangles of all focusable widgets, in screen coordinates.
+    pub fn update(&mut self, widgets: &[(Id, Rect)], input: &InputState) -> Option<NavEvent<Id>> {
+        if let Some(focused) = self.focused {
+            if !widgets.iter().any(|(id, _)| *id == focused) {
+                self.focused = None;
+            }
+        }
+
+        // Only hovering with a moving mouse steals focus, such that a resting cursor doesn't undo keyboard navigation.
+        let pos = input.mouse.position();
+        let moved = pos != std::mem::replace(&mut self.last_mouse_pos, pos);
+
+        let pressed = |keys: &[KeyCode]| keys.iter().any(|key| input.keyboard.is_key_pressed(*key));
+
+        if pressed(&self.bindings.cancel) {
+            return Some(NavEvent::Cancelled);
+        }
+
+        if pressed(&self.bindings.activate) {
+            if let Some(focused) = self.focused {
+                return Some(NavEvent::Activated(focused));
+            }
+        }
+
+        let direction = [
+            (&self.bindings.up, Direction::Up),
+            (&self.bindings.down, Direction::Down),
+            (&self.bindings.left, Direction::Left),
+            (&self.bindings.right, Direction::Right),
+        ]
+        .into_iter()
+        .find(|(keys, _)| pressed(keys))
+        .map(|(_, direction)| direction);
+        if let Some(direction) = direction {
+            return self
+                .navigate(widgets, direction)
+                .map(NavEvent::FocusChanged);
+        }
+
+        if self.mouse {
+            if let Some(pos) = pos {
+                if let Some((id, _)) = widgets.iter().find(|(_, rect)| rect.contains(pos)) {
+                    if input.mouse.is_button_pressed(MouseButton::Left) {
+                        self.focused = Some(*id);
+                        return Some(NavEvent::Activated(*id));
+                    }
+                    if moved && self.focused != Some(*id) {
+                        self.focused = Some(*id);
+                        return Some(NavEvent::FocusChanged(*id));
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl<Id> Default for FocusNav<Id>
+where
+    Id: Copy + PartialEq,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
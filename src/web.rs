@@ -0,0 +1,374 @@
+//! Glue for running in the browser.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use wasm_bindgen::{closure::Closure, JsCast as _};
+use winit::dpi::LogicalSize;
+use winit::platform::web::WindowExtWebSys as _;
+
+use crate::input::KeyCode;
+
+/// Set when the canvas' parent was resized or the device pixel ratio changed, such that the canvas must be fitted to its parent again.
+static FIT_PENDING: AtomicBool = AtomicBool::new(true);
+
+/// Resizes the window's canvas to fill its parent element, or the browser window if the canvas is a direct child of the body.
+///
+/// Changes to the parent's size and the device pixel ratio are observed, so this only does any work after they changed.
+pub fn fit_canvas_to_parent(window: &winit::window::Window) {
+    let Some(canvas) = window.canvas() else {
+        return;
+    };
+    let Some(web_window) = web_sys::window() else {
+        return;
+    };
+    let body = web_window.document().and_then(|document| document.body());
+
+    static OBSERVED: std::sync::Once = std::sync::Once::new();
+    OBSERVED.call_once(|| {
+        let on_resize = Closure::<dyn FnMut()>::new(|| FIT_PENDING.store(true, Ordering::Relaxed));
+        match canvas.parent_element() {
+            Some(parent) if body.as_deref() != Some(&parent) => {
+                if let Ok(observer) =
+                    web_sys::ResizeObserver::new(on_resize.as_ref().unchecked_ref())
+                {
+                    observer.observe(&parent);
+                    // The observer must outlive this function to keep observing.
+                    std::mem::forget(observer);
+                }
+            }
+            _ => {
+                _ = web_window
+                    .add_event_listener_with_callback("resize", on_resize.as_ref().unchecked_ref());
+            }
+        }
+        on_resize.forget();
+        watch_device_pixel_ratio(web_window.clone());
+    });
+    if !FIT_PENDING.swap(false, Ordering::Relaxed) {
+        return;
+    }
+
+    let size = match canvas.parent_element() {
+        Some(parent) if body.as_deref() != Some(&parent) => {
+            LogicalSize::new(parent.client_width() as f64, parent.client_height() as f64)
+        }
+        _ => LogicalSize::new(
+            web_window
+                .inner_width()
+                .ok()
+                .and_then(|w| w.as_f64())
+                .unwrap_or(0.0),
+            web_window
+                .inner_height()
+                .ok()
+                .and_then(|h| h.as_f64())
+                .unwrap_or(0.0),
+        ),
+    };
+    if size.width <= 0.0 || size.height <= 0.0 {
+        return;
+    }
+
+    // Compare in physical pixels, such that changes to the device pixel ratio are also picked up.
+    let physical = size.to_physical::<u32>(window.scale_factor());
+    if physical != window.inner_size() {
+        _ = window.request_inner_size(size);
+    }
+}
+
+/// Sets [`FIT_PENDING`] when the device pixel ratio changes, e.g. when zooming or moving to another monitor.
+fn watch_device_pixel_ratio(web_window: web_sys::Window) {
+    // A media query only matches a single ratio, so watch for the current one to stop matching, then watch the new one.
+    let query = format!("(resolution: {}dppx)", web_window.device_pixel_ratio());
+    let Ok(Some(media)) = web_window.match_media(&query) else {
+        return;
+    };
+    let on_change = Closure::once_into_js({
+        let media = media.clone();
+        move || {
+            media.set_onchange(None);
+            FIT_PENDING.store(true, Ordering::Relaxed);
+            watch_device_pixel_ratio(web_window);
+        }
+    });
+    media.set_onchange(Some(on_change.unchecked_ref()));
+}
+
+/// Prevents the browser's default handling (e.g. scrolling) of the given keys, and optionally of the context menu on the canvas.
+///
+/// Keys are only prevented while nothing else on the page (e.g. a text field) has focus.
+pub fn prevent_default(
+    window: &winit::window::Window,
+    keys: &'static [KeyCode],
+    context_menu: bool,
+) {
+    static INSTALLED: std::sync::Once = std::sync::Once::new();
+    INSTALLED.call_once(|| {
+        let Some(web_window) = web_sys::window() else {
+            return;
+        };
+        let canvas = window.canvas();
+
+        if !keys.is_empty() {
+            // KeyCode variants are named after KeyboardEvent.code values.
+            let codes = keys
+                .iter()
+                .map(|key| format!("{key:?}"))
+                .collect::<Vec<_>>();
+            let canvas: Option<web_sys::Element> = canvas.clone().map(Into::into);
+            let document = web_window.document();
+            let on_keydown = Closure::<dyn FnMut(web_sys::KeyboardEvent)>::new(
+                move |event: web_sys::KeyboardEvent| {
+                    let active = document.as_ref().and_then(|d| d.active_element());
+                    let game_focused = match &active {
+                        None => true,
+                        Some(active) => {
+                            document.as_ref().and_then(|d| d.body()).as_deref() == Some(active)
+                                || canvas.as_ref() == Some(active)
+                        }
+                    };
+                    if game_focused && codes.contains(&event.code()) {
+                        event.prevent_default();
+                    }
+                },
+            );
+            _ = web_window
+                .add_event_listener_with_callback("keydown", on_keydown.as_ref().unchecked_ref());
+            on_keydown.forget();
+        }
+
+        if context_menu {
+            if let Some(canvas) = canvas {
+                let on_context_menu =
+                    Closure::<dyn FnMut(web_sys::Event)>::new(|event: web_sys::Event| {
+                        event.prevent_default();
+                    });
+                _ = canvas.add_event_listener_with_callback(
+                    "contextmenu",
+                    on_context_menu.as_ref().unchecked_ref(),
+                );
+                on_context_menu.forget();
+            }
+        }
+    });
+}
+
+/// Dispatches a `teenygame-progress` event on the browser window.
+pub fn dispatch_progress(progress: crate::loading::Progress) {
+    let detail = js_sys::Object::new();
+    _ = js_sys::Reflect::set(&detail, &"loaded".into(), &progress.loaded.into());
+    _ = js_sys::Reflect::set(&detail, &"total".into(), &progress.total.into());
+    dispatch_event("teenygame-progress", &detail);
+}
+
+/// Dispatches a `teenygame-ready` event on the browser window, if it has not been dispatched yet.
+pub fn notify_ready() {
+    static READY: std::sync::Once = std::sync::Once::new();
+    READY.call_once(|| dispatch_event("teenygame-ready", &wasm_bindgen::JsValue::NULL));
+}
+
+fn dispatch_event(name: &str, detail: &wasm_bindgen::JsValue) {
+    let Some(web_window) = web_sys::window() else {
+        return;
+    };
+    let init = web_sys::CustomEventInit::new();
+    init.set_detail(detail);
+    if let Ok(event) = web_sys::CustomEvent::new_with_event_init_dict(name, &init) {
+        _ = web_window.dispatch_event(&event);
+    }
+}
+
+/// Gets the area of the window's canvas that is not obscured by display cutouts or browser UI, in physical pixels.
+///
+/// This reads the CSS `safe-area-inset-*` environment variables, which are only non-zero if the page sets `viewport-fit=cover`.
+pub fn safe_area(window: &winit::window::Window) -> Option<crate::math::URect> {
+    let canvas = window.canvas()?;
+    let web_window = web_sys::window()?;
+    let document = web_window.document()?;
+    let body = document.body()?;
+
+    // Environment variables can't be read directly, so apply them to an invisible element and read back its computed style.
+    let probe = document
+        .create_element("div")
+        .ok()?
+        .dyn_into::<web_sys::HtmlElement>()
+        .ok()?;
+    let style = probe.style();
+    _ = style.set_property("position", "fixed");
+    _ = style.set_property("visibility", "hidden");
+    for side in ["top", "right", "bottom", "left"] {
+        _ = style.set_property(
+            &format!("padding-{side}"),
+            &format!("env(safe-area-inset-{side})"),
+        );
+    }
+    body.append_child(&probe).ok()?;
+    let computed = web_window.get_computed_style(&probe).ok().flatten();
+    _ = body.remove_child(&probe);
+    let inset = |side: &str| {
+        computed
+            .as_ref()
+            .and_then(|c| c.get_property_value(&format!("padding-{side}")).ok())
+            .and_then(|v| v.trim_end_matches("px").parse::<f64>().ok())
+            .unwrap_or(0.0)
+    };
+
+    let viewport_width = web_window.inner_width().ok()?.as_f64()?;
+    let viewport_height = web_window.inner_height().ok()?.as_f64()?;
+    let rect = canvas.get_bounding_client_rect();
+    let scale_factor = window.scale_factor();
+
+    // Intersect the safe area of the viewport with the canvas, in canvas coordinates.
+    let to_physical = |v: f64| (v * scale_factor).round().max(0.0) as u32;
+    let min_x = to_physical(inset("left") - rect.left());
+    let min_y = to_physical(inset("top") - rect.top());
+    let max_x =
+        to_physical(viewport_width - inset("right") - rect.left()).min(to_physical(rect.width()));
+    let max_y =
+        to_physical(viewport_height - inset("bottom") - rect.top()).min(to_physical(rect.height()));
+    Some(crate::math::URect::from_min_max(
+        crate::math::uvec2(min_x, min_y),
+        crate::math::uvec2(max_x.max(min_x), max_y.max(min_y)),
+    ))
+}
+
+/// Prompts the browser to download the given bytes as a file.
+pub fn download(filename: &str, bytes: &[u8], mime: &str) -> Result<(), wasm_bindgen::JsValue> {
+    let document = web_sys::window()
+        .and_then(|w| w.document())
+        .ok_or_else(|| wasm_bindgen::JsValue::from_str("no document"))?;
+
+    let url = web_sys::Url::create_object_url_with_blob(&blob(bytes, mime)?)?;
+
+    let anchor = document
+        .create_element("a")?
+        .dyn_into::<web_sys::HtmlAnchorElement>()?;
+    anchor.set_href(&url);
+    anchor.set_download(filename);
+    anchor.click();
+
+    web_sys::Url::revoke_object_url(&url)
+}
+
+/// Creates a blob of the given bytes.
+fn blob(bytes: &[u8], mime: &str) -> Result<web_sys::Blob, wasm_bindgen::JsValue> {
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(bytes));
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type(mime);
+    web_sys::Blob::new_with_u8_array_sequence_and_options(&parts, &options)
+}
+
+/// Copies a PNG image to the clipboard.
+pub async fn copy_png(bytes: &[u8]) -> Result<(), wasm_bindgen::JsValue> {
+    let web_window =
+        web_sys::window().ok_or_else(|| wasm_bindgen::JsValue::from_str("no window"))?;
+
+    // The asynchronous clipboard API isn't fully covered by web-sys without unstable APIs, so call it dynamically.
+    let items = js_sys::Object::new();
+    js_sys::Reflect::set(&items, &"image/png".into(), &blob(bytes, "image/png")?)?;
+    let item_constructor = js_sys::Reflect::get(&web_window, &"ClipboardItem".into())?
+        .dyn_into::<js_sys::Function>()?;
+    let item = js_sys::Reflect::construct(&item_constructor, &js_sys::Array::of1(&items))?;
+    let clipboard = js_sys::Reflect::get(&web_window.navigator(), &"clipboard".into())?;
+    let write =
+        js_sys::Reflect::get(&clipboard, &"write".into())?.dyn_into::<js_sys::Function>()?;
+    let promise = write
+        .call1(&clipboard, &js_sys::Array::of1(&item))?
+        .dyn_into::<js_sys::Promise>()?;
+    wasm_bindgen_futures::JsFuture::from(promise).await?;
+    Ok(())
+}
+
+/// Shares a file via the Web Share API, returning false if the browser can't share files.
+///
+/// Canceling the share dialog counts as sharing.
+pub async fn share_file(
+    filename: &str,
+    bytes: &[u8],
+    mime: &str,
+    title: &str,
+) -> Result<bool, wasm_bindgen::JsValue> {
+    let navigator = web_sys::window()
+        .ok_or_else(|| wasm_bindgen::JsValue::from_str("no window"))?
+        .navigator();
+
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(bytes));
+    let options = web_sys::FilePropertyBag::new();
+    options.set_type(mime);
+    let file = web_sys::File::new_with_u8_array_sequence_and_options(&parts, filename, &options)?;
+    let data = js_sys::Object::new();
+    js_sys::Reflect::set(&data, &"files".into(), &js_sys::Array::of1(&file))?;
+    js_sys::Reflect::set(&data, &"title".into(), &title.into())?;
+
+    let Ok(can_share) =
+        js_sys::Reflect::get(&navigator, &"canShare".into())?.dyn_into::<js_sys::Function>()
+    else {
+        return Ok(false);
+    };
+    if !can_share.call1(&navigator, &data)?.is_truthy() {
+        return Ok(false);
+    }
+    let share =
+        js_sys::Reflect::get(&navigator, &"share".into())?.dyn_into::<js_sys::Function>()?;
+    let promise = share
+        .call1(&navigator, &data)?
+        .dyn_into::<js_sys::Promise>()?;
+    if let Err(e) = wasm_bindgen_futures::JsFuture::from(promise).await {
+        let aborted = js_sys::Reflect::get(&e, &"name".into())
+            .ok()
+            .and_then(|name| name.as_string())
+            .is_some_and(|name| name == "AbortError");
+        if !aborted {
+            return Err(e);
+        }
+    }
+    Ok(true)
+}
+
+/// Checks if a CSS media query (e.g. `(prefers-reduced-motion: reduce)`) currently matches.
+pub fn media_matches(query: &str) -> bool {
+    web_sys::window()
+        .and_then(|w| w.match_media(query).ok().flatten())
+        .is_some_and(|list| list.matches())
+}
+
+/// Announces text to screen readers via a visually hidden ARIA live region, created on first use.
+pub fn announce(text: &str, assertive: bool) {
+    let Some(web_window) = web_sys::window() else {
+        return;
+    };
+    let Some(document) = web_window.document() else {
+        return;
+    };
+    let (id, live) = if assertive {
+        ("teenygame-announce-assertive", "assertive")
+    } else {
+        ("teenygame-announce-polite", "polite")
+    };
+
+    let region = match document.get_element_by_id(id) {
+        Some(region) => region,
+        None => {
+            let (Ok(region), Some(body)) = (document.create_element("div"), document.body()) else {
+                return;
+            };
+            region.set_id(id);
+            _ = region.set_attribute("aria-live", live);
+            _ = region.set_attribute("aria-atomic", "true");
+            _ = region.set_attribute(
+                "style",
+                "position: absolute; width: 1px; height: 1px; margin: -1px; overflow: hidden; clip: rect(0 0 0 0); white-space: nowrap;",
+            );
+            if body.append_child(&region).is_err() {
+                return;
+            }
+            region
+        }
+    };
+
+    // Clear the region first and only set the text once the browser has seen it empty, such that repeating the same text is announced again.
+    region.set_text_content(None);
+    let text = text.to_string();
+    let set_text = Closure::once_into_js(move || region.set_text_content(Some(&text)));
+    _ = web_window
+        .set_timeout_with_callback_and_timeout_and_arguments_0(set_text.unchecked_ref(), 100);
+}
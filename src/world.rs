@@ -0,0 +1,316 @@
+//! Structured storage for game entities.
+//!
+//! This is not a full entity component system: a [`World`] simply hands out [`Entity`] handles and stores components of any type against them, with helpers for iterating over entities that have given components.
+//!
+//! ```ignore
+//! let mut world = World::new();
+//! let player = world.spawn();
+//! world.insert(player, Position(Vec2::ZERO));
+//! world.insert(player, Velocity(Vec2::X));
+//!
+//! world.for_each_mut_with::<Position, Velocity>(|_, pos, vel| pos.0 += vel.0);
+//! ```
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+/// A handle to an entity in a [`World`].
+///
+/// Handles are generational: once an entity is despawned, its handle will never refer to another entity, even if its slot is reused.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct Entity {
+    index: u32,
+    generation: u32,
+}
+
+trait AnyStorage: Any {
+    fn remove_index(&mut self, index: usize);
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+struct Storage<T>(Vec<Option<T>>);
+
+impl<T> AnyStorage for Storage<T>
+where
+    T: 'static,
+{
+    fn remove_index(&mut self, index: usize) {
+        if let Some(slot) = self.0.get_mut(index) {
+            *slot = None;
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// A collection of entities and their components.
+#[derive(Default)]
+pub struct World {
+    generations: Vec<u32>,
+    alive: Vec<bool>,
+    free: Vec<u32>,
+    len: usize,
+    storages: HashMap<TypeId, Box<dyn AnyStorage>>,
+}
+
+impl World {
+    /// Creates an empty world.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gets the number of live entities.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Checks if there are no live entities.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Spawns a new entity with no components.
+    pub fn spawn(&mut self) -> Entity {
+        self.len += 1;
+        if let Some(index) = self.free.pop() {
+            self.alive[index as usize] = true;
+            return Entity {
+                index,
+                generation: self.generations[index as usize],
+            };
+        }
+
+        self.generations.push(0);
+        self.alive.push(true);
+        Entity {
+            index: (self.generations.len() - 1) as u32,
+            generation: 0,
+        }
+    }
+
+    /// Despawns an entity and drops all its components.
+    ///
+    /// Returns false if the entity was already despawned.
+    pub fn despawn(&mut self, entity: Entity) -> bool {
+        if !self.is_alive(entity) {
+            return false;
+        }
+
+        let index = entity.index as usize;
+        for storage in self.storages.values_mut() {
+            storage.remove_index(index);
+        }
+        self.alive[index] = false;
+        self.generations[index] = self.generations[index].wrapping_add(1);
+        self.free.push(entity.index);
+        self.len -= 1;
+        true
+    }
+
+    /// Despawns all entities.
+    pub fn clear(&mut self) {
+        for entity in self.entities().collect::<Vec<_>>() {
+            self.despawn(entity);
+        }
+    }
+
+    /// Checks if an entity has not been despawned.
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        let index = entity.index as usize;
+        self.alive.get(index).copied().unwrap_or(false)
+            && self.generations[index] == entity.generation
+    }
+
+    /// Iterates over all live entities.
+    pub fn entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.alive
+            .iter()
+            .enumerate()
+            .filter(|(_, alive)| **alive)
+            .map(|(index, _)| self.entity_at(index))
+    }
+
+    fn entity_at(&self, index: usize) -> Entity {
+        Entity {
+            index: index as u32,
+            generation: self.generations[index],
+        }
+    }
+
+    fn storage<T>(&self) -> Option<&Vec<Option<T>>>
+    where
+        T: 'static,
+    {
+        self.storages
+            .get(&TypeId::of::<T>())
+            .map(|storage| &storage.as_any().downcast_ref::<Storage<T>>().unwrap().0)
+    }
+
+    fn storage_mut<T>(&mut self) -> Option<&mut Vec<Option<T>>>
+    where
+        T: 'static,
+    {
+        self.storages
+            .get_mut(&TypeId::of::<T>())
+            .map(|storage| &mut storage.as_any_mut().downcast_mut::<Storage<T>>().unwrap().0)
+    }
+
+    /// Adds a component to an entity, returning the previous component of the same type if there was one.
+    ///
+    /// Does nothing if the entity has been despawned.
+    pub fn insert<T>(&mut self, entity: Entity, component: T) -> Option<T>
+    where
+        T: 'static,
+    {
+        if !self.is_alive(entity) {
+            return None;
+        }
+
+        let storage = &mut self
+            .storages
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(Storage::<T>(vec![])))
+            .as_any_mut()
+            .downcast_mut::<Storage<T>>()
+            .unwrap()
+            .0;
+
+        let index = entity.index as usize;
+        if storage.len() <= index {
+            storage.resize_with(index + 1, || None);
+        }
+        storage[index].replace(component)
+    }
+
+    /// Removes a component from an entity, returning it if it existed.
+    pub fn remove<T>(&mut self, entity: Entity) -> Option<T>
+    where
+        T: 'static,
+    {
+        if !self.is_alive(entity) {
+            return None;
+        }
+        self.storage_mut::<T>()?
+            .get_mut(entity.index as usize)?
+            .take()
+    }
+
+    /// Gets a component of an entity.
+    pub fn get<T>(&self, entity: Entity) -> Option<&T>
+    where
+        T: 'static,
+    {
+        if !self.is_alive(entity) {
+            return None;
+        }
+        self.storage::<T>()?.get(entity.index as usize)?.as_ref()
+    }
+
+    /// Gets a component of an entity mutably.
+    pub fn get_mut<T>(&mut self, entity: Entity) -> Option<&mut T>
+    where
+        T: 'static,
+    {
+        if !self.is_alive(entity) {
+            return None;
+        }
+        self.storage_mut::<T>()?
+            .get_mut(entity.index as usize)?
+            .as_mut()
+    }
+
+    /// Checks if an entity has a component.
+    pub fn has<T>(&self, entity: Entity) -> bool
+    where
+        T: 'static,
+    {
+        self.get::<T>(entity).is_some()
+    }
+
+    /// Iterates over all entities with a component.
+    pub fn iter<T>(&self) -> impl Iterator<Item = (Entity, &T)> + '_
+    where
+        T: 'static,
+    {
+        self.storage::<T>()
+            .into_iter()
+            .flatten()
+            .enumerate()
+            .filter_map(|(index, component)| Some((self.entity_at(index), component.as_ref()?)))
+    }
+
+    /// Iterates mutably over all entities with a component.
+    pub fn iter_mut<T>(&mut self) -> impl Iterator<Item = (Entity, &mut T)> + '_
+    where
+        T: 'static,
+    {
+        let generations = &self.generations;
+        self.storages
+            .get_mut(&TypeId::of::<T>())
+            .map(|storage| &mut storage.as_any_mut().downcast_mut::<Storage<T>>().unwrap().0)
+            .into_iter()
+            .flatten()
+            .enumerate()
+            .filter_map(|(index, component)| {
+                Some((
+                    Entity {
+                        index: index as u32,
+                        generation: generations[index],
+                    },
+                    component.as_mut()?,
+                ))
+            })
+    }
+
+    /// Iterates over all entities with both components.
+    pub fn iter2<A, B>(&self) -> impl Iterator<Item = (Entity, &A, &B)> + '_
+    where
+        A: 'static,
+        B: 'static,
+    {
+        let b = self.storage::<B>();
+        self.iter::<A>().filter_map(move |(entity, a)| {
+            Some((entity, a, b?.get(entity.index as usize)?.as_ref()?))
+        })
+    }
+
+    /// Calls a function for all entities with both components, with mutable access to the first component.
+    ///
+    /// Panics if `A` and `B` are the same type.
+    pub fn for_each_mut_with<A, B>(&mut self, mut f: impl FnMut(Entity, &mut A, &B))
+    where
+        A: 'static,
+        B: 'static,
+    {
+        assert_ne!(
+            TypeId::of::<A>(),
+            TypeId::of::<B>(),
+            "components must be of different types"
+        );
+
+        // Temporarily take the first storage out, such that both storages may be borrowed at once.
+        let Some(mut a_storage) = self.storages.remove(&TypeId::of::<A>()) else {
+            return;
+        };
+        if let Some(b) = self.storage::<B>() {
+            let a = &mut a_storage
+                .as_any_mut()
+                .downcast_mut::<Storage<A>>()
+                .unwrap()
+                .0;
+            for (index, (a, b)) in a.iter_mut().zip(b.iter()).enumerate() {
+                if let (Some(a), Some(b)) = (a, b) {
+                    f(self.entity_at(index), a, b);
+                }
+            }
+        }
+        self.storages.insert(TypeId::of::<A>(), a_storage);
+    }
+}